@@ -41,6 +41,7 @@ from chc.api.CGlobalContract import CGlobalContract
 from chc.app.CCompInfo import CCompInfo
 from chc.app.CFile import CFile
 from chc.app.CVarInfo import CVarInfo
+from chc.app.GlobalSymbolTable import GlobalSymbolTable
 from chc.app.IndexManager import IndexManager, FileVarReference, FileKeyReference
 from chc.app.CGlobalDeclarations import CGlobalDeclarations
 from chc.app.CGlobalDictionary import CGlobalDictionary
@@ -101,6 +102,7 @@ class CApplication(chc_rust.app.c_application.CApplication):
         self._dictionary: Optional[CGlobalDictionary] = None
         self._declarations: Optional[CGlobalDeclarations] = None
         self._files: Optional[Dict[int, CFile]] = None  # file-index -> CFile
+        self._symboltable: Optional[GlobalSymbolTable] = None
         self._callgraph: Optional[
             Dict[Tuple[int, int],
                  List[Tuple[Tuple[int, int], "CFunctionCallsiteSPOs"]]]] = None
@@ -134,6 +136,18 @@ class CApplication(chc_rust.app.c_application.CApplication):
     def cfiles(self) -> Iterable[CFile]:
         return self.files.values()
 
+    @property
+    def symboltable(self) -> GlobalSymbolTable:
+        """Returns the functions and global variables across all files.
+
+        The table is built again after the index manager changed.
+        """
+
+        if self._symboltable is None or not self._symboltable.is_current:
+            self._symboltable = self.indexmanager.symbol_table(
+                list(self.cfiles))
+        return self._symboltable
+
     @property
     def filenames(self) -> List[str]:
         """Returns full filenames relative to the project path (without .c)."""
@@ -518,25 +532,41 @@ class CApplication(chc_rust.app.c_application.CApplication):
 
     def _initialize_from_target_files(self) -> None:
         chklogger.logger.info("Initialize from target files")
-        tgtxnode = UF.get_targetfiles_xnode(self.targetpath, self.projectname)
-        if tgtxnode is None:
-            raise UF.CHCXmlParseError(self.targetpath, 0, (0, 0))
-
         self._files = {}
+        for (fid, cfilename_c) in self.target_files():
+            self._files[fid] = self._initialize_file(fid, cfilename_c)
 
-        for c in tgtxnode.findall("c-file"):
-            cfilename_c = c.get("name")
-            if cfilename_c is None:
-                raise UF.CHCXmlParseError(self.targetpath, 0, (0, 0))
-            if cfilename_c in self.excludefiles:
-                continue
-            id = c.get("id")
-            if id is None:
-                chklogger.logger.error(
-                    "No id found for target file %s", cfilename_c)
-            else:
-                fid = int(id)
-                self._files[fid] = self._initialize_file(fid, cfilename_c)
+    def load_files(
+            self,
+            workers: Optional[int] = None,
+            memory_budget: Optional[int] = None) -> Dict[int, CFile]:
+        """Loads all files with their dictionaries, declarations and functions.
+
+        The xml files are parsed in parallel; see the base class for the
+        meaning of workers and memory_budget.
+        """
+        self._files = super().load_files(workers, memory_budget)
+        return self._files
+
+    def reload_changed_files(self) -> List[int]:
+        """Reloads the files whose analysis results changed on disk.
+
+        Only the changed files and their functions are read again; returns
+        the indices of the files reloaded. A file that fails to reload is
+        still reported as changed by the next call.
+        """
+        changed = self.changed_files()
+        for fid in changed:
+            cfile = self.files[fid]
+            chklogger.logger.info("Reload changed file %s", cfile.name)
+            self.indexmanager.remove_file(fid)
+            cfile.reload()
+            self.indexmanager.add_file(cfile)
+            self.commit_fingerprint(fid)
+        if len(changed) > 0:
+            self._callgraph = None
+            self._revcallgraph = None
+        return changed
 
     def initialize_single_file(self, fname: str) -> None:
         xcfile = UF.get_cfile_xnode(
@@ -546,6 +576,7 @@ class CApplication(chc_rust.app.c_application.CApplication):
             self._files = {}
             self._files[0] = cfile
             self.indexmanager.add_file(cfile)
+            self.record_fingerprint(cfile)
             chklogger.logger.info("Single c file was initialized %s", fname)
         else:
             chklogger.logger.error("c_file could not be extracted %s", fname)
@@ -561,6 +592,7 @@ class CApplication(chc_rust.app.c_application.CApplication):
             "Initialize file name: %s, file path: %s", cfilename, str(cfilepath))
         cfile = CFile(self, index, cfilename[:-2], cfilepath)
         self.indexmanager.add_file(cfile)
+        self.record_fingerprint(cfile)
         chklogger.logger.info("initialized cfile %s", fname)
         return cfile
 
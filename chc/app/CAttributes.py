@@ -56,344 +56,43 @@ CAttrStr = chc_rust.app.c_attributes.CAttrStr
 CAttrCons = chc_rust.app.c_attributes.CAttrCons
 
 
-@cdregistry.register_tag("asizeof", CAttr)
-class CAttrSizeOf(CAttr):
-    """Attribute that describes the size of a type.
+CAttrSizeOf = chc_rust.app.c_attributes.CAttrSizeOf
 
-    * args[0]: index of target type in cdictionary
-    """
 
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CAttrSizeOf":
-        return super().__new__(cls, cd, ixval)
+CAttrSizeOfE = chc_rust.app.c_attributes.CAttrSizeOfE
 
-    @property
-    def typ(self) -> "CTyp":
-        return self.cd.get_typ(int(self.args[0]))
 
-    @property
-    def is_sizeof(self) -> bool:
-        return True
+CAttrSizeOfS = chc_rust.app.c_attributes.CAttrSizeOfS
 
-    def __str__(self) -> str:
-        return "asizeof(" + str(self.typ) + ")"
 
+CAttrAlignOf = chc_rust.app.c_attributes.CAttrAlignOf
 
-@cdregistry.register_tag("asizeofe", CAttr)
-class CAttrSizeOfE(CAttr):
-    """Size of an attribute parameter.
 
-    * args[0]: index of argument parameter in cdictionary
-    """
+CAttrAlignOfE = chc_rust.app.c_attributes.CAttrAlignOfE
 
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CAttrSizeOfE":
-        return super().__new__(cls, cd, ixval)
 
-    @property
-    def param(self) -> CAttr:
-        return self.cd.get_attrparam(int(self.args[0]))
+CAttrAlignOfS = chc_rust.app.c_attributes.CAttrAlignOfS
 
-    @property
-    def is_sizeofe(self) -> bool:
-        return True
 
-    def __str__(self) -> str:
-        return "asizeofe(" + str(self.param) + ")"
+CAttrUnOp = chc_rust.app.c_attributes.CAttrUnOp
 
 
-@cdregistry.register_tag("asizeofs", CAttr)
-class CAttrSizeOfS(CAttr):
-    """Replacement ASizeOf in type signatures.
+CAttrBinOp = chc_rust.app.c_attributes.CAttrBinOp
 
-    * args[0]: index of target typsig in cdictionary
-    """
 
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CAttrSizeOfS":
-        return super().__new__(cls, cd, ixval)
+CAttrDot = chc_rust.app.c_attributes.CAttrDot
 
-    @property
-    def typsig(self) -> "CTypsig":
-        return self.cd.get_typsig(int(self.args[0]))
 
-    @property
-    def is_sizeofs(self) -> bool:
-        return True
+CAttrStar = chc_rust.app.c_attributes.CAttrStar
 
-    def __str__(self) -> str:
-        return "asizeofs(" + str(self.typsig) + ")"
 
+CAttrAddrOf = chc_rust.app.c_attributes.CAttrAddrOf
 
-@cdregistry.register_tag("aalignof", CAttr)
-class CAttrAlignOf(CAttr):
-    """Alignment of a type.
 
-    * args[0]: index of target type in cdictionary
-    """
+CAttrIndex = chc_rust.app.c_attributes.CAttrIndex
 
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CAttrAlignOf":
-        return super().__new__(cls, cd, ixval)
 
-    @property
-    def typ(self) -> "CTyp":
-        return self.cd.get_typ(int(self.args[0]))
-
-    @property
-    def is_alignof(self) -> bool:
-        return True
-
-    def __str__(self) -> str:
-        return "aalignof(" + str(self.typ) + ")"
-
-
-@cdregistry.register_tag("aalignofe", CAttr)
-class CAttrAlignOfE(CAttr):
-    """Alignment of an attribute parameter.
-
-    * args[0]: index of attribute parameter in cdictionary
-    """
-
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CAttrAlignOfE":
-        return super().__new__(cls, cd, ixval)
-
-    @property
-    def param(self) -> CAttr:
-        return self.cd.get_attrparam(int(self.args[0]))
-
-    @property
-    def is_alignofe(self) -> bool:
-        return True
-
-    def __str__(self) -> str:
-        return "aalignofe(" + str(self.param) + ")"
-
-
-@cdregistry.register_tag("aalignofs", CAttr)
-class CAttrAlignOfS(CAttr):
-    """Alignment of a type signature.
-
-    * args[0]: target type signature
-    """
-
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CAttrAlignOfS":
-        return super().__new__(cls, cd, ixval)
-
-    @property
-    def typsig(self) -> "CTypsig":
-        return self.cd.get_typsig(int(self.args[0]))
-
-    @property
-    def is_alignofs(self) -> bool:
-        return True
-
-    def __str__(self) -> str:
-        return "aalignofs(" + str(self.typsig) + ")"
-
-
-@cdregistry.register_tag("aunop", CAttr)
-class CAttrUnOp(CAttr):
-    """Unary attribute parameter operation.
-
-    * tags[1]: operator
-    * args[0]: index of attribute parameter in cdictionary
-    """
-
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CAttrUnOp":
-        return super().__new__(cls, cd, ixval)
-
-    @property
-    def op(self) -> str:
-        return self.tags[1]
-
-    @property
-    def param(self) -> CAttr:
-        return self.cd.get_attrparam(int(self.args[0]))
-
-    @property
-    def is_unop(self) -> bool:
-        return True
-
-    def __str__(self) -> str:
-        return "aunop(" + self.op + "," + str(self.param) + ")"
-
-
-@cdregistry.register_tag("abinop", CAttr)
-class CAttrBinOp(CAttr):
-    """Binary attribute parameter operation.
-
-    * tags[1]: operator
-    * args[0]: index of first attribute parameter in cdictionary
-    * args[1]: index of second attribute parameter in cdictionary
-    """
-
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CAttrBinOp":
-        return super().__new__(cls, cd, ixval)
-
-    @property
-    def op(self) -> str:
-        return self.tags[1]
-
-    @property
-    def param1(self) -> CAttr:
-        return self.cd.get_attrparam(int(self.args[0]))
-
-    @property
-    def param2(self) -> CAttr:
-        return self.cd.get_attrparam(int(self.args[1]))
-
-    @property
-    def is_binop(self) -> bool:
-        return True
-
-    def __str__(self) -> str:
-        return (
-            "abinop("
-            + str(self.param1)
-            + " "
-            + self.op
-            + " "
-            + str(self.param2)
-            + ")"
-        )
-
-
-@cdregistry.register_tag("adot", CAttr)
-class CAttrDot(CAttr):
-    """Dot operator on attributes.
-
-    * tags[1]: string suffix
-    * args[0]: index of attribute parameter in cdictionary
-    """
-
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CAttrDot":
-        return super().__new__(cls, cd, ixval)
-
-    @property
-    def suffix(self) -> str:
-        return self.tags[1]
-
-    @property
-    def param(self) -> CAttr:
-        return self.cd.get_attrparam(int(self.args[0]))
-
-    @property
-    def is_dot(self) -> bool:
-        return True
-
-    def __str__(self) -> str:
-        return "adot(" + str(self.param) + "." + self.suffix + ")"
-
-
-@cdregistry.register_tag("astr", CAttr)
-class CAttrStar(CAttr):
-    """Star operation on attribute.
-
-    * args[0]: index of attribute parameter in cdictionary
-    """
-
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CAttrStar":
-        return super().__new__(cls, cd, ixval)
-
-    @property
-    def param(self) -> CAttr:
-        chklogger.logger.info("Self-referential attribute (*)")
-        if self.index == int(self.args[0]):
-            return self
-        else:
-            return self.cd.get_attrparam(int(self.args[0]))
-
-    @property
-    def is_star(self) -> bool:
-        return True
-
-    def __str__(self) -> str:
-        if self.index == self.args[0]:
-            return "astar()"
-        else:
-            return "astar(" + str(self.param) + ")"
-
-
-@cdregistry.register_tag("aaddrof", CAttr)
-class CAttrAddrOf(CAttr):
-    """Addressof operator on attribute.
-
-    * args[0]: index of attribute parameter in cdictionary
-    """
-
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CAttrAddrOf":
-        return super().__new__(cls, cd, ixval)
-
-    @property
-    def param(self) -> CAttr:
-        return self.cd.get_attrparam(int(self.args[0]))
-
-    @property
-    def is_addrof(self) -> bool:
-        return True
-
-    def __str__(self) -> str:
-        return "aaddrof(" + str(self.param) + ")"
-
-
-@cdregistry.register_tag("aindex", CAttr)
-class CAttrIndex(CAttr):
-    """Index operation on attributes
-
-    * args[0]: index of first attribute parameter in cdictionary
-    * args[1]: index of second attribute parameter in cdictionary
-    """
-
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CAttrIndex":
-        return super().__new__(cls, cd, ixval)
-
-    @property
-    def param1(self) -> CAttr:
-        return self.cd.get_attrparam(int(self.args[0]))
-
-    @property
-    def param2(self) -> CAttr:
-        return self.cd.get_attrparam(int(self.args[1]))
-
-    @property
-    def is_index(self) -> bool:
-        return True
-
-    def __str__(self) -> str:
-        return "aindex(" + str(self.param1) + "," + str(self.param2) + ")"
-
-
-@cdregistry.register_tag("aquestion", CAttr)
-class CAttrQuestion(CAttr):
-    """Question operator on attributes
-
-    * args[0]: index of first attribute paramter in cdictionary
-    * args[1]: index of second attribute parameter in cdictionary
-    * args[2]: index of third attribute parameter in cdictionary
-    """
-
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CAttrQuestion":
-        return super().__new__(cls, cd, ixval)
-
-    @property
-    def param1(self) -> CAttr:
-        return self.cd.get_attrparam(int(self.args[0]))
-
-    @property
-    def param2(self) -> CAttr:
-        return self.cd.get_attrparam(int(self.args[1]))
-
-    @property
-    def param3(self) -> CAttr:
-        return self.cd.get_attrparam(int(self.args[2]))
-
-    def __str__(self) -> str:
-        return (
-            "aquestion("
-            + str(self.param1)
-            + ","
-            + str(self.param2)
-            + ","
-            + str(self.param3)
-            + ")"
-        )
+CAttrQuestion = chc_rust.app.c_attributes.CAttrQuestion
 
 
 CAttribute = chc_rust.app.c_attributes.CAttribute
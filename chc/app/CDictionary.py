@@ -49,6 +49,7 @@ import chc.util.fileutil as UF
 from chc.util.IndexedTable import IndexedTable, IndexedTableValue
 from chc.util.loggingutil import chklogger
 from chc.util.StringIndexedTable import StringIndexedTable
+import chc.util.xmlutil as UX
 
 if TYPE_CHECKING:
     from chc.api.ApiParameter import APGlobal
@@ -558,7 +559,8 @@ class CDictionary(chc_rust.app.c_dictionary.CDictionary):
             args = [
                 self.index_exp(e.condition, subst=subst, fid=fid),
                 self.index_exp(e.true_exp, subst=subst, fid=fid),
-                self.index_exp(e.false_exp, subst=subst, fid=fid)]
+                self.index_exp(e.false_exp, subst=subst, fid=fid),
+                self.index_typ(e.typ)]
             return self.mk_exp_index(e.tags, args)
 
         if e.is_caste:
@@ -771,6 +773,44 @@ class CDictionary(chc_rust.app.c_dictionary.CDictionary):
         self.string_table.write_xml(tnode)
         node.append(tnode)
 
+    def splice_xml(self, node: ET.Element, splices: UX.Splices) -> None:
+        """Register node to be written with all tables by the Rust writer.
+
+        node itself stays empty; the tables only appear in the output of
+        UX.doc_to_pretty called with the same splices.
+        """
+        entries = self.string_table.entries()
+        splices[node] = (
+            lambda indent: self.write_xml_text(node.tag, entries, indent))
+
+    def check_integrity(self) -> Any:
+        """Return an IntegrityReport on all tables, including the string table.
+
+        Flags dangling argument indices, records with too few arguments,
+        duplicate keys, gaps, uncommitted reserved indices, and tags without
+        a registered record type.
+        """
+        return self.check(set(self.string_table.indextable.keys()))
+
+    def read_only_view(self) -> Any:
+        """Return a CDictionaryView snapshot, including the string table.
+
+        The view holds no python objects; it can be queried from other
+        threads and from Rust without the GIL.
+        """
+        return self.view(dict(self.string_table.indextable))
+
+    def diff_with(self, other: "CDictionary") -> List[Any]:
+        """Return a TableDiff per table for the changes from self to other.
+
+        Entries are matched by what they denote, not by index, so the
+        dictionaries of two analysis runs can be compared directly.
+        """
+        return self.diff(
+            other,
+            dict(self.string_table.indextable),
+            dict(other.string_table.indextable))
+
     # --------------------------- printing -------------------------------------
 
     def objectmap_to_string(self, name: str) -> str:
@@ -817,3 +857,36 @@ class CDictionary(chc_rust.app.c_dictionary.CDictionary):
         else:
             raise UF.CHCError(
                 "Error reading stringtable: " + self.string_table.name)
+
+    def initialize_from_file(
+            self, filename: str, cachedir: Optional[str] = None) -> None:
+        """Fill the tables directly from filename, without ElementTree.
+
+        If cachedir is given, a binary snapshot of the tables is kept there
+        and used instead of filename as long as filename is unchanged.
+        """
+        if cachedir is None:
+            xtables = chc_rust.util.dictionary_xml.read_xml_tables(filename)
+        else:
+            xtables = chc_rust.util.dictionary_cache.read_xml_tables_cached(
+                filename, cachedir)
+        self.initialize_from_tables(xtables)
+
+    def initialize_from_tables(
+            self, xtables: chc_rust.util.dictionary_xml.XmlTables) -> None:
+        """Fill the tables from tables already read from a dictionary file."""
+        for t in self.tables:
+            if not xtables.has_table(t.name):
+                raise UF.CHCError("Error reading table " + t.name)
+        self.read_xml_tables(xtables)
+        if not self.is_global:
+            for t in self.tables:
+                chklogger.logger.info(
+                    "%s: Read xml table %s with %d entries",
+                    self.cfile.name, t.name, t.size())
+        self.string_table.reset()
+        entries = xtables.string_table(self.string_table.name)
+        if entries is None:
+            raise UF.CHCError(
+                "Error reading stringtable: " + self.string_table.name)
+        self.string_table.read_entries(entries)
@@ -28,21 +28,13 @@
 # ------------------------------------------------------------------------------
 """C-file main access point."""
 
-import os
 import xml.etree.ElementTree as ET
 
 from typing import (
-    Any, Callable, Dict, Iterable, List, Optional, Tuple, TYPE_CHECKING)
+    Any, Callable, Dict, Iterable, List, Optional, TYPE_CHECKING)
 
-from chc.api.InterfaceDictionary import InterfaceDictionary
-from chc.api.CFileContracts import CFileContracts
 from chc.api.CFunctionContract import CFunctionContract
 
-from chc.app.CContextDictionary import CContextDictionary
-from chc.app.CFileAssignmentDictionary import CFileAssignmentDictionary
-from chc.app.CFileDictionary import CFileDictionary
-from chc.app.CFileDeclarations import CFileDeclarations
-from chc.app.CFileGlobals import CFileGlobals
 from chc.app.CFunction import CFunction
 from chc.app.CFileGlobals import CGCompTag
 from chc.app.CFileGlobals import CGEnumTag
@@ -51,12 +43,9 @@ from chc.app.CFileGlobals import CGType
 from chc.app.CFileGlobals import CGVarDecl
 from chc.app.CFileGlobals import CGVarDef
 
-from chc.proof.CFilePredicateDictionary import CFilePredicateDictionary
 from chc.proof.CFunctionPO import CFunctionPO
 from chc.proof.CFunctionPPO import CFunctionPPO
 
-from chc.source.CSrcFile import CSrcFile
-
 import chc.util.fileutil as UF
 from chc.util.loggingutil import chklogger
 import chc.util.xmlutil as UX
@@ -98,90 +87,6 @@ class CFunctionNotFoundException(Exception):
 class CFile(chc_rust.app.c_file.CFile):
     """C File main access point."""
 
-    def __new__(
-            cls,
-            capp: "CApplication",
-            index: int,
-            cfilename: str,
-            cfilepath: Optional[str]) -> "CFile":
-        self = super().__new__(cls)
-        self._index = index
-        self._capp = capp
-        self._cfilename = cfilename
-        self._cfilepath = cfilepath
-        self._xnode: Optional[ET.Element] = None
-        self._declarations: Optional[CFileDeclarations] = None
-        self._dictionary: Optional[CFileDictionary] = None
-        self._contextdictionary: Optional[CContextDictionary] = None
-        self._predicatedictionary: Optional[CFilePredicateDictionary] = None
-        self._interfacedictionary: Optional[InterfaceDictionary] = None
-        self._assigndictionary: Optional[CFileAssignmentDictionary] = None
-        self._functions: Optional[Dict[int, CFunction]] = None  # vid -> CFunction
-        self.strings: Dict[int, Tuple[int, str]] = {}  # string-index -> (len,string)
-        self._sourcefile: Optional[CSrcFile] = None
-        self._contracts: Optional[CFileContracts] = None
-
-        self._cfileglobals: Optional[CFileGlobals] = None
-        return self
-
-    @property
-    def index(self) -> int:
-        return self._index
-
-    @property
-    def cfilename(self) -> str:
-        """Returns base filename (without extension)."""
-
-        return self._cfilename
-
-    @property
-    def cfilepath(self) -> Optional[str]:
-        """Returns path relative to project directory or None if at toplevel."""
-
-        return self._cfilepath
-
-    @property
-    def name(self) -> str:
-        """Returns the full name relative to the project directory.
-
-        Note: the filename is without extension
-        """
-
-        if self.cfilepath is None:
-            return self.cfilename
-        else:
-            return os.path.join(self.cfilepath, self.cfilename)
-
-    @property
-    def capp(self) -> "CApplication":
-        return self._capp
-
-    @property
-    def targetpath(self) -> str:
-        return self.capp.targetpath
-
-    @property
-    def projectname(self) -> str:
-        return self.capp.projectname
-
-    @property
-    def contractpath(self) -> str:
-        return self.capp.contractpath
-
-    @property
-    def cfileglobals(self) -> CFileGlobals:
-        if self._cfileglobals is None:
-            chklogger.logger.info("Load _cfile for %s", self.name)
-            xcfile = UF.get_cfile_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename)
-            if xcfile is None:
-                raise UF.CHCError(f"_cfile.xml not found for {self.name}")
-            self._cfileglobals = CFileGlobals(self, xcfile)
-        return self._cfileglobals
-
     @property
     def gfunctions(self) -> Dict[int, "CGFunction"]:
         return self.cfileglobals.gfunctions
@@ -218,157 +123,6 @@ class CFile(chc_rust.app.c_file.CFile):
     def gvardefs(self) -> Dict[int, "CGVarDef"]:
         return self.cfileglobals.gvardefs
 
-    def has_global_varinfo(self, vid: int) -> bool:
-        return vid in self.cfileglobals.global_varinfo_vids
-
-    def get_global_varinfo(self, vid: int) -> "CVarInfo":
-        if vid in self.cfileglobals.global_varinfo_vids:
-            return self.cfileglobals.global_varinfo_vids[vid]
-        else:
-            raise UF.CHCError(f"Global variable with vid: {vid} not found")
-
-    def has_global_varinfo_by_name(self, name: str) -> bool:
-        return name in self.cfileglobals.global_varinfo_names
-
-    def get_global_varinfo_by_name(self, name: str) -> "CVarInfo":
-        if name in self.cfileglobals.global_varinfo_names:
-            return self.cfileglobals.global_varinfo_names[name]
-        else:
-            raise UF.CHCError(
-                f"Varinfo with name {name} not found in {self.name}")
-
-    @property
-    def functions(self) -> Dict[int, CFunction]:
-        if self._functions is None:
-            self._functions = {}
-            for (vid, gf) in self.gfunctions.items():
-                fnname = gf.vname
-                xnode = UF.get_cfun_xnode(
-                    self.targetpath,
-                    self.projectname,
-                    self.cfilepath,
-                    self.cfilename,
-                    fnname)
-                if xnode is not None:
-                    cfunction = CFunction(self, xnode, fnname)
-                    self._functions[vid] = cfunction
-                else:
-                    chklogger.logger.warning("Function {fnname} not found")
-        return self._functions
-
-    @property
-    def functionxref(self) -> Dict[str, int]:
-        """Returns a map from function names to vid's."""
-
-        return {cfun.name: index for (index, cfun) in self.functions.items()}
-
-    @property
-    def functionnames(self) -> List[str]:
-        return [cfun.name for cfun in self.functions.values()]
-
-    @property
-    def sourcefile(self) -> "CSrcFile":
-        if self._sourcefile is None:
-            srcpath = UF.get_savedsource_path(self.targetpath, self.projectname)
-            srcfile = os.path.join(srcpath, self.name + ".c")
-            chklogger.logger.info("Source file: %s", srcfile)
-            self._sourcefile = CSrcFile(self.capp, srcfile)
-        return self._sourcefile
-
-    @property
-    def dictionary(self) -> CFileDictionary:
-        if self._dictionary is None:
-            xnode = UF.get_cfile_dictionary_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename)
-            if xnode is None:
-                raise UF.CHCError("File dictionary file not found")
-            xdict = xnode.find("c-dictionary")
-            if xdict is None:
-                raise UF.CHCError("File dictionary node not found")
-            self._dictionary = CFileDictionary(self, xdict)
-        return self._dictionary
-
-    def reset_dictionary(self) -> None:
-        self._dictionary = None
-
-    @property
-    def contextdictionary(self) -> CContextDictionary:
-        if self._contextdictionary is None:
-            xnode = UF.get_cfile_contexttable_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename)
-            if xnode is None:
-                raise UF.CHCError("Context table file not found")
-            self._contextdictionary = CContextDictionary(self, xnode)
-        return self._contextdictionary
-
-    def reset_contextdictionary(self) -> None:
-        self._contextdictionary = None
-
-    @property
-    def declarations(self) -> CFileDeclarations:
-        d = self.dictionary
-        if self._declarations is None:
-            xnode = UF.get_cfile_dictionary_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename)
-            if xnode is None:
-                raise UF.CHCError("File dictionary file not found")
-            xdecls = xnode.find("c-declarations")
-            if xdecls is None:
-                raise UF.CHCError("File declarations node not found")
-            self._declarations = CFileDeclarations(self, xdecls)
-        return self._declarations
-
-    def reset_declarations(self) -> None:
-        self._declarations = None
-
-    @property
-    def interfacedictionary(self) -> InterfaceDictionary:
-        if self._interfacedictionary is None:
-            xnode = UF.get_cfile_interface_dictionary_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename)
-            self._interfacedictionary = InterfaceDictionary(self, xnode)
-        return self._interfacedictionary
-
-    def reset_interfacedictionary(self) -> None:
-        self._interfacedictionary = None
-
-    @property
-    def assigndictionary(self) -> CFileAssignmentDictionary:
-        if self._assigndictionary is None:
-            xnode = UF.get_cfile_assignment_dictionary_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename)
-            self._assigndictionary = CFileAssignmentDictionary(self, xnode)
-        return self._assigndictionary
-
-    @property
-    def predicatedictionary(self) -> CFilePredicateDictionary:
-        if self._predicatedictionary is None:
-            xnode = UF.get_cfile_predicate_dictionary_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename)
-            self._predicatedictionary = CFilePredicateDictionary(self, xnode)
-        return self._predicatedictionary
-
-    def reset_predicatedictionary(self) -> None:
-        self._predicatedictionary = None
-
     def collect_post_assumes(self) -> None:
         """Collect callsite postconditions from callee's contracts and add as assume."""
 
@@ -379,12 +133,6 @@ class CFile(chc_rust.app.c_file.CFile):
         self.save_predicate_dictionary()
         self.save_declarations()
 
-    @property
-    def contracts(self) -> CFileContracts:
-        if self._contracts is None:
-            self._contracts = CFileContracts(self, self.contractpath)
-        return self._contracts
-
     def has_file_contracts(self) -> bool:
         return not (self.contracts is None)
 
@@ -422,26 +170,6 @@ class CFile(chc_rust.app.c_file.CFile):
         for fn in self.get_functions():
             fn.reinitialize_tables()
 
-    def has_function_by_name(self, fnname: str) -> bool:
-        return fnname in self.functionxref
-
-    def get_function_by_name(self, fnname: str) -> CFunction:
-        if fnname in self.functionxref:
-            fvid = self.functionxref[fnname]
-            return self.functions[fvid]
-        else:
-            raise CFunctionNotFoundException(self, fnname)
-
-    def get_function_by_index(self, index: int) -> CFunction:
-        if index in self.functions:
-            return self.functions[index]
-        else:
-            raise Exception(
-                'Unable to find function with global vid ' + str(index))
-
-    def has_function_by_index(self, index: int) -> bool:
-        return index in self.functions
-
     def get_functions(self) -> Iterable[CFunction]:
         return self.functions.values()
 
@@ -575,11 +303,12 @@ class CFile(chc_rust.app.c_file.CFile):
         xroot = UX.get_xml_header("cfile", "cfile")
         xnode = ET.Element("cfile")
         xroot.append(xnode)
-        self.declarations.write_xml(xnode)
+        splices: UX.Splices = {}
+        self.declarations.write_xml(xnode, splices)
         filename = UF.get_cfile_dictionaryname(
             self.targetpath, self.projectname, self.cfilepath, self.cfilename)
         with open(filename, "w") as fp:
-            fp.write(UX.doc_to_pretty(ET.ElementTree(xroot)))
+            fp.write(UX.doc_to_pretty(ET.ElementTree(xroot), splices))
         chklogger.logger.info("Saved file declarations: %s", filename)
 
     def save_user_assumptions(self, userdata, assumptions):
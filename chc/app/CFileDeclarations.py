@@ -37,6 +37,7 @@ from typing import (
     List,
     Mapping,
     NoReturn,
+    Optional,
     Tuple,
     TYPE_CHECKING)
 import xml.etree.ElementTree as ET
@@ -59,7 +60,6 @@ from chc.app.CEnumInfo import CEnumInfo
 from chc.app.CEnumItem import CEnumItem
 from chc.app.CFieldInfo import CFieldInfo
 from chc.app.CVarInfo import CVarInfo
-from chc.app.CTyp import CTyp
 
 import chc.util.fileutil as UF
 from chc.util.IndexedTable import IndexedTable, IndexedTableValue
@@ -114,7 +114,9 @@ class CFileDeclarations(chc_rust.app.c_file_declarations.CFileDeclarations):
     Declarations are dependent on CFileDictionary
     """
 
-    def __new__(cls, cfile: "CFile", xnode: ET.Element) -> "CFileDeclarations":
+    def __new__(
+            cls, cfile: "CFile", xnode: Optional[ET.Element]
+    ) -> "CFileDeclarations":
         self = super().__new__(cls)
         self._cfile = cfile
 
@@ -153,7 +155,8 @@ class CFileDeclarations(chc_rust.app.c_file_declarations.CFileDeclarations):
             "varinfo": self.get_varinfo_map}
 
         # self.string_table = SI.StringIndexedTable("string-table")
-        self._initialize(xnode)
+        if xnode is not None:
+            self._initialize(xnode)
 
         return self
 
@@ -301,9 +304,6 @@ class CFileDeclarations(chc_rust.app.c_file_declarations.CFileDeclarations):
 
     # ------------------- Miscellaneous other services -----------------------
 
-    def expand(self, name: str) -> CTyp:
-        return self.cfile.cfileglobals.expand(name)
-
     def get_max_line(self) -> int:
         findex = self.index_filename(self.cfile.name + ".c")
         maxline = 0
@@ -348,9 +348,19 @@ class CFileDeclarations(chc_rust.app.c_file_declarations.CFileDeclarations):
 
     # ------------------------ Saving ----------------------------------------
 
-    def write_xml(self, node: ET.Element) -> None:
+    def write_xml(
+            self, node: ET.Element, splices: Optional[UX.Splices] = None
+    ) -> None:
+        """Write declarations and dictionary to node.
+
+        If splices is given, the dictionary is written by the Rust writer
+        when the document is rendered with UX.doc_to_pretty(tree, splices).
+        """
         dictnode = ET.Element("c-dictionary")
-        self.dictionary.write_xml(dictnode)
+        if splices is None:
+            self.dictionary.write_xml(dictnode)
+        else:
+            self.dictionary.splice_xml(dictnode, splices)
         declsnode = ET.Element("c-declarations")
 
         def f(n: ET.Element, r: IndexedTableValue) -> None:
@@ -385,3 +395,18 @@ class CFileDeclarations(chc_rust.app.c_file_declarations.CFileDeclarations):
         else:
             raise UF.CHCError(
                 "Filename table not found in file declarations")
+
+    def initialize_from_tables(
+            self, xtables: chc_rust.util.dictionary_xml.XmlTables) -> None:
+        """Fill the tables from tables already read from a dictionary file."""
+        for t in self.tables:
+            if not xtables.has_table(t.name):
+                raise UF.CHCError(
+                    "Table " + t.name + " not found in file declarations")
+            t.read_xml_tables(xtables)
+        entries = xtables.string_table(self.filename_table.name)
+        if entries is None:
+            raise UF.CHCError(
+                "Filename table not found in file declarations")
+        self.filename_table.reset()
+        self.filename_table.read_entries(entries)
@@ -30,7 +30,7 @@
 
 import xml.etree.ElementTree as ET
 
-from typing import Any, cast, Dict, List, TYPE_CHECKING
+from typing import Any, cast, Dict, List, Optional, TYPE_CHECKING
 
 from chc.app.CCompInfo import CCompInfo
 from chc.app.CExp import (CExp, CExpLval)
@@ -74,10 +74,14 @@ class CFileDictionary(CDictionary):
     All other indexing is handled by the superclass.
     """
 
-    def __new__(cls, cfile: "CFile", xnode: ET.Element) -> "CFileDictionary":
+    def __new__(
+            cls, cfile: "CFile", xnode: Optional[ET.Element]
+    ) -> "CFileDictionary":
+        """Tables are left empty if xnode is None (see initialize_from_file)."""
         self = super().__new__(cls)
         self._cfile = cfile
-        self._initialize(xnode)
+        if xnode is not None:
+            self._initialize(xnode)
         return self
 
     @property
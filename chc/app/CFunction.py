@@ -30,27 +30,19 @@
 
 import xml.etree.ElementTree as ET
 
-from typing import Any, Callable, Dict, List, Optional, Tuple, TYPE_CHECKING
+from typing import Any, Callable, List, Optional, Tuple, TYPE_CHECKING
 
 
 from chc.api.CFunctionApi import CFunctionApi
 
 from chc.app.CLocation import CLocation
-from chc.app.CFunDeclarations import CFunDeclarations
-from chc.app.CInstr import CCallInstr
-from chc.app.CStmt import CFunctionBody
 from chc.app.IndexManager import FileVarReference
 
-from chc.invariants.CFunVarDictionary import CFunVarDictionary
-from chc.invariants.CFunInvDictionary import CFunInvDictionary
-from chc.invariants.CFunInvariantTable import CFunInvariantTable
 from chc.invariants.CInvariantFact import CInvariantFact
 
-from chc.proof.CFunPODictionary import CFunPODictionary
 from chc.proof.CFunctionCallsiteSPOs import CFunctionCallsiteSPOs
 from chc.proof.CFunctionPO import CFunctionPO
 from chc.proof.CFunctionPPO import CFunctionPPO
-from chc.proof.CFunctionProofs import CFunctionProofs
 from chc.proof.CFunctionSPOs import CFunctionSPOs
 
 import chc.util.fileutil as UF
@@ -60,305 +52,16 @@ import chc_rust
 
 if TYPE_CHECKING:
     from chc.api.CFunctionContract import CFunctionContract
-    from chc.api.InterfaceDictionary import InterfaceDictionary
-    from chc.app.CApplication import CApplication
     from chc.app.CFile import CFile
-    from chc.app.CFileDeclarations import CFileDeclarations
-    from chc.app.CTyp import CTyp
     from chc.app.CVarInfo import CVarInfo
 
 
 class CFunction(chc_rust.app.c_function.CFunction):
     """Function implementation."""
 
-    def __new__(cls, cfile: "CFile", xnode: ET.Element, fname: str) -> "CFunction":
-        self = super().__new__(cls)
-        self.xnode = xnode
-        self._cfile = cfile
-        self._name = fname
-        self._cfundecls: Optional[CFunDeclarations] = None
-        self._svar: Optional["CVarInfo"] = None
-        self._formals: Dict[int, "CVarInfo"] = {}  # vid -> CVarInfo
-        self._locals: Dict[int, "CVarInfo"] = {}  # vid -> CVarInfo
-        self._sbody: Optional[CFunctionBody] = None
-        self._podictionary: Optional[CFunPODictionary] = None
-        self._api: Optional[CFunctionApi] = None
-        self._proofs: Optional[CFunctionProofs] = None
-        self._vard: Optional[CFunVarDictionary] = None
-        self._invd: Optional[CFunInvDictionary] = None
-        self._invarianttable: Optional[CFunInvariantTable] = None
-        return self
-
-    def xmsg(self, txt: str) -> str:
-        return "Function " + self.name + ": " + txt
-
-    @property
-    def name(self) -> str:
-        return self._name
-
-    @property
-    def cfile(self) -> "CFile":
-        return self._cfile
-
-    @property
-    def capp(self) -> "CApplication":
-        return self.cfile.capp
-
-    @property
-    def targetpath(self) -> str:
-        return self.cfile.targetpath
-
-    @property
-    def projectname(self) -> str:
-        return self.cfile.projectname
-
-    @property
-    def cfilepath(self) -> Optional[str]:
-        return self.cfile.cfilepath
-
-    @property
-    def cfilename(self) -> str:
-        return self.cfile.cfilename
-
-    @property
-    def formals(self) -> Dict[int, "CVarInfo"]:
-        if len(self._formals) == 0:
-            for (vid, vinfo) in self.cfundecls.varinfos.items():
-                if vinfo.is_param:
-                    self._formals[vid] = vinfo
-        return self._formals
-
-    @property
-    def locals(self) -> Dict[int, "CVarInfo"]:
-        if len(self._locals) == 0:
-            for (vid, vinfo) in self.cfundecls.varinfos.items():
-                if not vinfo.is_param:
-                    self._locals[vid] = vinfo
-        return self._locals
-
-    @property
-    def ftype(self) -> "CTyp":
-        return self.svar.vtype
-
-    @property
-    def cfiledecls(self) -> "CFileDeclarations":
-        return self.cfile.declarations
-
-    @property
-    def interfacedictionary(self) -> "InterfaceDictionary":
-        return self.cfile.interfacedictionary
-
-    @property
-    def api(self) -> CFunctionApi:
-        if self._api is None:
-            axnode = UF.get_api_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename,
-                self.name)
-            if axnode is not None:
-                apinode = axnode.find("api")
-                if apinode is not None:
-                    self._api = CFunctionApi(self, apinode)
-                else:
-                    raise UF.CHCError(self.xmsg("api file has no api node"))
-            else:
-                raise UF.CHCError(self.xmsg("api file not found"))
-        return self._api
-
     def has_outstanding_api_requests(self) -> bool:
         return self.api.has_outstanding_requests()
 
-    @property
-    def svar(self) -> "CVarInfo":
-        if self._svar is None:
-            xsvar = self.xnode.find("svar")
-            if xsvar is not None:
-                xivinfo = xsvar.get("ivinfo")
-                if xivinfo is not None:
-                    self._svar = self.cfiledecls.get_varinfo(int(xivinfo))
-                else:
-                    raise UF.CHCError(
-                        self.xmsg(
-                            "ivinfo attribute "
-                            + "is missing from svar element in cfun file"))
-            else:
-                raise UF.CHCError(
-                    self.xmsg("svar element is missing from cfun file"))
-        return self._svar
-
-    @property
-    def sbody(self) -> CFunctionBody:
-        if self._sbody is None:
-            xsbody = self.xnode.find("sbody")
-            if xsbody is not None:
-                self._sbody = CFunctionBody(self, xsbody)
-            else:
-                raise UF.CHCError(
-                    self.xmsg("sbody element is missing from cfun file"))
-        return self._sbody
-
-    @property
-    def cfundecls(self) -> CFunDeclarations:
-        if self._cfundecls is None:
-            dxnode = self.xnode.find("declarations")
-            if dxnode is not None:
-                self._cfundecls = CFunDeclarations(self, dxnode)
-            else:
-                raise UF.CHCError(
-                    self.xmsg("declarations are missing from cfun file"))
-        return self._cfundecls
-
-    @property
-    def vardictionary(self) -> CFunVarDictionary:
-        if self._vard is None:
-            vxnode = UF.get_vars_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename,
-                self.name)
-            if vxnode is not None:
-                xvard = vxnode.find("var-dictionary")
-                if xvard is not None:
-                    self._vard = CFunVarDictionary(self, xvard)
-                else:
-                    raise UF.CHCError(
-                        self.xmsg("var-dictionary missing from cfun-vars file"))
-            else:
-                raise UF.CHCError(
-                    self.xmsg(
-                        "var-dictionary file not found for function "
-                        + self.name
-                        + " in file "
-                        + self.cfile.name))
-        return self._vard
-
-    @property
-    def invdictionary(self) -> CFunInvDictionary:
-        if self._invd is None:
-            ixnode = UF.get_invs_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename,
-                self.name)
-            if ixnode is not None:
-                xinvs = ixnode.find("inv-dictionary")
-                if xinvs is not None:
-                    self._invd = CFunInvDictionary(self, xinvs)
-                else:
-                    raise UF.CHCError(
-                        self.xmsg("inv-dictionary missing from cfun-invs file"))
-            else:
-                raise UF.CHCError(
-                    self.xmsg("inv-dictionary file not found"))
-        return self._invd
-
-    @property
-    def invarianttable(self) -> CFunInvariantTable:
-        if self._invarianttable is None:
-            ixnode = UF.get_invs_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename,
-                self.name)
-            if ixnode is not None:
-                xinvs = ixnode.find("location-invariants")
-                if xinvs is not None:
-                    self._invarianttable = CFunInvariantTable(self, xinvs)
-                else:
-                    raise UF.CHCError(
-                        self.xmsg("inv-table missing from cfun-invs file"))
-            else:
-                raise UF.CHCError(
-                    self.xmsg("inv-dictionary file not found"))
-        return self._invarianttable
-
-    @property
-    def podictionary(self) -> CFunPODictionary:
-        if self._podictionary is None:
-            pxnode = UF.get_pod_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename,
-                self.name)
-            if pxnode is None:
-                raise UF.CHCError(self.xmsg("pod file not found"))
-            self._podictionary = CFunPODictionary(self, pxnode)
-        return self._podictionary
-
-    @property
-    def proofs(self) -> CFunctionProofs:
-        if self._proofs is None:
-            xpponode = UF.get_ppo_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename,
-                self.name)
-            if xpponode is None:
-                raise UF.CHCError(self.xmsg("ppo file is missing"))
-            xxpponode = xpponode.find("ppos")
-            if xxpponode is None:
-                raise UF.CHCError(self.xmsg("_ppo file has no ppos element"))
-            xsponode = UF.get_spo_xnode(
-                self.targetpath,
-                self.projectname,
-                self.cfilepath,
-                self.cfilename,
-                self.name)
-            if xsponode is None:
-                raise UF.CHCError(self.xmsg("spo file is missing"))
-            xxsponode = xsponode.find("spos")
-            if xxsponode is None:
-                raise UF.CHCError(self.xmsg("_spo file has no spos element"))
-            self._proofs = CFunctionProofs(self, xxpponode, xxsponode)
-        return self._proofs
-
-    def reinitialize_tables(self) -> None:
-        self._api = None
-        self._podictionary = None
-        self._vardictionary = None
-        self._proofs = None
-
-    def get_formal_vid(self, name: str) -> int:
-        for v in self.formals:
-            if self.formals[v].vname == name:
-                return v
-        else:
-            raise UF.CHCError(
-                "Formals: " + ",".join([str(v) for v in self.formals.values()]))
-
-    def get_variable_vid(self, vname: str) -> int:
-        for v in self.formals:
-            if self.formals[v].vname == vname:
-                return self.formals[v].vid
-        for v in self.locals:
-            if self.locals[v].vname == vname:
-                return self.locals[v].vid
-        raise UF.CHCError("Could not find vid for variable \"" + vname + "\"")
-
-    def has_variable_vid(self, vname: str) -> bool:
-        for v in self.formals:
-            if self.formals[v].vname == vname:
-                return True
-        for v in self.locals:
-            if self.locals[v].vname == vname:
-                return True
-        return False
-
-    @property
-    def strings(self) -> List[str]:
-        return self.sbody.strings
-
-    # returns the number of occurrences of vid in expressions and lhs
-    def get_variable_uses(self, vid: int) -> int:
-        return self.sbody.get_variable_uses(vid)
-
     def has_function_contract(self) -> bool:
         return self.cfile.has_function_contract(self.name)
 
@@ -382,9 +85,6 @@ class CFunction(chc_rust.app.c_function.CFunction):
     def iter_callsites(self, f: Callable[[CFunctionCallsiteSPOs], None]) -> None:
         self.proofs.iter_callsites(f)
 
-    def get_vid(self) -> int:
-        return self.svar.vid
-
     def get_api(self) -> CFunctionApi:
         return self.api
 
@@ -425,22 +125,6 @@ class CFunction(chc_rust.app.c_function.CFunction):
     def get_locals(self) -> List["CVarInfo"]:
         return list(self.locals.values())
 
-    @property
-    def block_count(self) -> int:
-        return self.sbody.block_count
-
-    @property
-    def stmt_count(self) -> int:
-        return self.sbody.stmt_count
-
-    @property
-    def instr_count(self) -> int:
-        return self.sbody.instr_count
-
-    @property
-    def call_instrs(self) -> List[CCallInstr]:
-        return self.sbody.call_instrs
-
     def violates_contract_conditions(self) -> bool:
         return len(self.api.contract_condition_failures) > 0
 
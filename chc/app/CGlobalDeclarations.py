@@ -36,6 +36,7 @@ import xml.etree.ElementTree as ET
 
 import chc.util.fileutil as UF
 import chc.util.IndexedTable as IT
+import chc.util.xmlutil as UX
 import chc.app.CInitInfo as CI
 
 from chc.app.CCompInfo import CCompInfo
@@ -739,9 +740,19 @@ class CGlobalDeclarations(chc_rust.app.c_global_declarations.CGlobalDeclarations
 
     # -------------------- Writing xml ---------------------------------------
 
-    def write_xml(self, node: ET.Element) -> None:
+    def write_xml(
+            self, node: ET.Element, splices: Optional[UX.Splices] = None
+    ) -> None:
+        """Write declarations and dictionary to node.
+
+        If splices is given, the dictionary is written by the Rust writer
+        when the document is rendered with UX.doc_to_pretty(tree, splices).
+        """
         dnode = ET.Element("dictionary")
-        self.dictionary.write_xml(dnode)
+        if splices is None:
+            self.dictionary.write_xml(dnode)
+        else:
+            self.dictionary.splice_xml(dnode, splices)
         node.append(dnode)
 
         def f(n: ET.Element, r: IndexedTableValue) -> None:
@@ -78,81 +78,7 @@ CTypPtr = chc_rust.app.c_typ.CTypPtr
 CTypArray = chc_rust.app.c_typ.CTypArray
 
 
-@cdregistry.register_tag("tfun", CTyp)
-class CTypFun(CTyp):
-    """Function type
-
-    * args[0]: index of return type in cdictionary
-    * args[1]: index of argument types list in cdictionary (optional
-    * args[2]: 1 = varargs
-    * args[3]: index of attributes in cdictionary
-    """
-
-    def __new__(cls, cd: "CDictionary", ixval: IT.IndexedTableValue) -> "CTypFun":
-        return super().__new__(cls, cd, ixval)
-
-    @property
-    def return_type(self) -> CTyp:
-        return self.get_typ(self.args[0])
-
-    @property
-    def funargs(self) -> Optional["CFunArgs"]:
-        return self.cd.get_funargs_opt(self.args[1])
-
-    @property
-    def size(self) -> int:
-        return 4
-
-    @property
-    def is_function(self) -> bool:
-        return True
-
-    def get_opaque_type(self) -> CTyp:
-        tags = ["tvoid"]
-        args: List[int] = []
-        return self.cd.get_typ(self.cd.mk_typ_index(tags, args))
-
-    @property
-    def is_default_function_prototype(self) -> bool:
-        funargs = self.funargs
-        if funargs is None:
-            return True
-        else:
-            args = funargs.arguments
-            return len(args) > 0 and all(
-                [x.name.startswith("$par$") for x in args]
-            )
-
-    @property
-    def is_vararg(self) -> bool:
-        return self.args[2] == 1
-
-    def strip_attributes(self) -> CTyp:
-        rtype = self.return_type.strip_attributes()
-        if rtype.index != self.return_type.index:
-            newargs = self.args[:]
-            newargs[0] = rtype.index
-            newtypix = self.cd.mk_typ_index(self.tags, newargs)
-            newtyp = self.cd.get_typ(newtypix)
-            chklogger.logger.info(
-                "Change function type from %s to %s", str(self), str(newtyp))
-            return newtyp
-        else:
-            return self
-
-    def to_dict(self) -> Dict[str, Any]:
-        result: Dict[str, Any] = {
-            "base": "fun", "rvtype": self.return_type.to_dict()}
-        if self.is_default_function_prototype:
-            result["default"] = "true"
-        elif self.funargs is not None:
-            result["args"] = self.funargs.to_dict()
-        return result
-
-    def __str__(self) -> str:
-        rtyp = self.return_type
-        args = self.funargs
-        return "(" + str(args) + "):" + str(rtyp)
+CTypFun = chc_rust.app.c_typ.CTypFun
 
 
 class CFunArg(CDictionaryRecord):
@@ -69,110 +69,6 @@ class IndexManager(chc_rust.app.index_manager.IndexManager):
     def __new__(cls, issinglefile: bool) -> "IndexManager":
         return super().__new__(cls, issinglefile)
 
-    """return a list of (fid,vid) pairs that refer to the same global variable."""
-
-    """return a list of (fid,vid) pairs that refer to the same variable."""
-
-    """return the vid in the file with index fidtgt for vid in fidsrc.
-
-    If the target file does not map the gvid then create a new vid in this
-    file to map the gvid.
-    """
-
-    def convert_vid(
-            self, varref: FileVarReference, tgtfid: int) -> Optional[int]:
-        """Returns the vid of the var reference in (another) file tgtfid."""
-
-        if varref.fid == tgtfid:
-            # same file
-            return varref.vid
-
-        gvid = self.get_gvid(varref)
-        if gvid is not None:
-            if gvid in self.gvid2vid:
-                if tgtfid in self.gvid2vid[gvid]:
-                    return self.gvid2vid[gvid][tgtfid]
-                else:
-                    chklogger.logger.warning(
-                        "failed to convert %s for file %d (found gvid: %d)",
-                        str(varref), tgtfid, gvid)
-                    return None
-        return None
-
-    def get_vid(self, fid: int, gvid: int) -> Optional[int]:
-        """Returns the vid of the gvid in the file with index fid."""
-
-        if self.is_single_file:
-            return gvid
-        if gvid in self.gvid2vid:
-            if fid in self.gvid2vid[gvid]:
-                return self.gvid2vid[gvid][fid]
-        return None
-
-    def get_gckey(self, filekey: FileKeyReference) -> Optional[int]:
-        """Returns the global ckey index for a file ckey reference."""
-
-        if self.is_single_file:
-            # for a single file the global ckey is the same the file ckey
-            return filekey.ckey
-
-        if filekey.fid in self.ckey2gckey:
-            if filekey.ckey in self.ckey2gckey[filekey.fid]:
-                return self.ckey2gckey[filekey.fid][filekey.ckey]
-
-        chklogger.logger.warning(
-            "No global key found for file key %s", str(filekey))
-        return None
-
-    def convert_ckey(
-            self, filekey: FileKeyReference, tgtfid: int) -> Optional[int]:
-        """Returns the ckey of filekey of the same struct in the file tgtfid."""
-
-        if filekey.fid == tgtfid:
-            # same file
-            return filekey.ckey
-
-        gckey = self.get_gckey(filekey)
-        if gckey is not None:
-            if gckey in self.gckey2ckey:
-                if tgtfid in self.gckey2ckey[gckey]:
-                    return self.gckey2ckey[gckey][tgtfid]
-                else:
-                    chklogger.logger.warning(
-                        "Target fid %d not found for global key %d",
-                        tgtfid, gckey)
-                    return None
-            else:
-                chklogger.logger.warning(
-                    "Global key %d not found in converter", gckey)
-                return None
-        else:
-            chklogger.logger.warning(
-                "No global key found for file key %s", str(filekey))
-            return None
-
-    def add_ckey2gckey(self, filekey: FileKeyReference, gckey: int) -> None:
-        """Registers a local file ckey with a global ckey."""
-
-        # add forward conversion to global ckey
-        self.ckey2gckey.setdefault(filekey.fid, {})
-        self.ckey2gckey[filekey.fid][filekey.ckey] = gckey
-
-        # add reverse conversion from global ckey
-        self.gckey2ckey.setdefault(gckey, {})
-        self.gckey2ckey[gckey][filekey.fid] = filekey.ckey
-
-    def add_vid2gvid(self, filevar: FileVarReference, gvid: int) -> None:
-        """Registers a local file vid with a global vid."""
-
-        # add forward conversion to global vid
-        self.vid2gvid.setdefault(filevar.fid, {})
-        self.vid2gvid[filevar.fid][filevar.vid] = gvid
-
-        # add reverse conversion from global vid
-        self.gvid2vid.setdefault(gvid, {})
-        self.gvid2vid[gvid][filevar.fid] = filevar.vid
-
     def add_file(self, cfile: "CFile") -> None:
         fid = cfile.index
         if not self.is_single_file:
@@ -184,7 +80,7 @@ class IndexManager(chc_rust.app.index_manager.IndexManager):
             if xxreffile is not None:
                 self._add_xrefs(xxreffile, fid)
             self._add_globaldefinitions(cfile, fid)
-        self.fidvidmax[fid] = fidvidmax_initial_value
+        self.set_fidvidmax(fid, fidvidmax_initial_value)
 
     def save_xrefs(
             self,
@@ -200,19 +96,17 @@ class IndexManager(chc_rust.app.index_manager.IndexManager):
         vxrefsnode = ET.Element("varinfo-xrefs")
         xrefsnode.extend([cxrefsnode, vxrefsnode])
 
-        if fid in self.ckey2gckey:
-            for ckey in sorted(self.ckey2gckey[fid]):
-                xref = ET.Element("cxref")
-                xref.set("ckey", str(ckey))
-                xref.set("gckey", str(self.ckey2gckey[fid][ckey]))
-                cxrefsnode.append(xref)
+        for (ckey, gckey) in sorted(self.get_ckey_gckey_subst(fid).items()):
+            xref = ET.Element("cxref")
+            xref.set("ckey", str(ckey))
+            xref.set("gckey", str(gckey))
+            cxrefsnode.append(xref)
 
-        if fid in self.vid2gvid:
-            for vid in sorted(self.vid2gvid[fid]):
-                xref = ET.Element("vxref")
-                xref.set("vid", str(vid))
-                xref.set("gvid", str(self.vid2gvid[fid][vid]))
-                vxrefsnode.append(xref)
+        for (vid, gvid) in sorted(self.get_vid_gvid_subst(fid).items()):
+            xref = ET.Element("vxref")
+            xref.set("vid", str(vid))
+            xref.set("gvid", str(gvid))
+            vxrefsnode.append(xref)
 
         xreffilename = UF.get_cxreffile_filename(
             targetpath, projectname, cfilepath, cfilename)
@@ -220,9 +114,6 @@ class IndexManager(chc_rust.app.index_manager.IndexManager):
         xreffile.write(UX.doc_to_pretty(ET.ElementTree(xrefroot)))
 
     def _add_xrefs(self, xnode: ET.Element, fid: int) -> None:
-        if fid not in self.ckey2gckey:
-            self.ckey2gckey[fid] = {}
-
         xcompinfoxrefs = xnode.find("compinfo-xrefs")
         if xcompinfoxrefs is not None:
             for cxref in xcompinfoxrefs.findall("cxref"):
@@ -232,20 +123,13 @@ class IndexManager(chc_rust.app.index_manager.IndexManager):
                     xgckey = cxref.get("gckey")
                     if xgckey is not None:
                         gckey = int(xgckey)
-                        self.ckey2gckey[fid][ckey] = gckey
-                        self.gckey2ckey.setdefault(gckey, {})
-                        # if gckey not in self.gckey2ckey:
-                        #    self.gckey2ckey[gckey] = {}
-                        self.gckey2ckey[gckey][fid] = ckey
+                        self.add_ckey2gckey(FileKeyReference(fid, ckey), gckey)
                     else:
                         raise UF.CHCError(
                             "Compinfo xref without gckey attribute")
                 else:
                     raise UF.CHCError("Compinfo xref without ckey attribute")
 
-        if fid not in self.vid2gvid:
-            self.vid2gvid[fid] = {}
-
         xvarinfoxrefs = xnode.find("varinfo-xrefs")
         if xvarinfoxrefs is not None:
             for vxref in xvarinfoxrefs.findall("vxref"):
@@ -255,11 +139,7 @@ class IndexManager(chc_rust.app.index_manager.IndexManager):
                     xgvid = vxref.get("gvid")
                     if xgvid is not None:
                         gvid = int(xgvid)
-                        self.vid2gvid[fid][vid] = gvid
-                        self.gvid2vid.setdefault(gvid, {})
-                        # if gvid not in self.gvid2vid:
-                        # self.gvid2vid[gvid] = {}
-                        self.gvid2vid[gvid][fid] = vid
+                        self.add_vid2gvid(FileVarReference(fid, vid), gvid)
                     else:
                         raise UF.CHCError(
                             "Varinfo xref without gvid attribute")
@@ -271,7 +151,7 @@ class IndexManager(chc_rust.app.index_manager.IndexManager):
             filevar = FileVarReference(fid, gvar.varinfo.vid)
             gvid = self.get_gvid(filevar)
             if gvid is not None:
-                self.gviddefs[gvid] = fid
+                self.add_gviddef(gvid, fid)
 
         for gfun in cfile.gfunctions.values():
             filevar = FileVarReference(fid, gfun.varinfo.vid)
@@ -280,4 +160,4 @@ class IndexManager(chc_rust.app.index_manager.IndexManager):
                 chklogger.logger.info(
                     "set function %s (%s) to file %s",
                     gfun.varinfo.vname, str(gvid), str(fid))
-                self.gviddefs[gvid] = fid
+                self.add_gviddef(gvid, fid)
@@ -264,6 +264,7 @@ def cproject_report(args: argparse.Namespace) -> NoReturn:
     contractpath = os.path.join(targetpath, "chc_contracts")
     capp = CApplication(
         projectpath, projectname, targetpath, contractpath)
+    capp.load_files()
 
     timestamp = os.stat(UF.get_cchpath(targetpath, projectname)).st_ctime
     fresult = RP.project_proofobligation_stats_to_dict(capp)
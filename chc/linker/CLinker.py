@@ -208,8 +208,9 @@ class CLinker(chc_rust.linker.c_linker.CLinker):
         xroot = UX.get_xml_header("globals", "globals")
         xnode = ET.Element("globals")
         xroot.append(xnode)
-        self.declarations.write_xml(xnode)
+        splices: UX.Splices = {}
+        self.declarations.write_xml(xnode, splices)
         filename = UF.get_global_definitions_filename(path, self.capp.projectname)
         chklogger.logger.info("Saving global compinfos to %s", filename)
         with open(filename, "w") as fp:
-            fp.write(UX.doc_to_pretty(ET.ElementTree(xroot)))
+            fp.write(UX.doc_to_pretty(ET.ElementTree(xroot), splices))
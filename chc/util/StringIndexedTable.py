@@ -27,7 +27,7 @@
 # SOFTWARE.
 # ------------------------------------------------------------------------------
 
-from typing import Any, Dict, Optional, Tuple
+from typing import Any, Dict, List, Optional, Tuple
 import xml.etree.ElementTree as ET
 
 import chc.util.IndexedTable as IT
@@ -155,6 +155,18 @@ class StringIndexedTable:
             if index >= self.next:
                 self.next = index + 1
 
+    def read_entries(self, entries: List[Tuple[int, str]]) -> None:
+        """Add (index, string) pairs that were already decoded."""
+        for (index, s) in entries:
+            self.stringtable[s] = index
+            self.indextable[index] = s
+            if index >= self.next:
+                self.next = index + 1
+
+    def entries(self) -> List[Tuple[int, str]]:
+        """Return the (index, string) pairs in index order."""
+        return [(index, self.indextable[index]) for index in sorted(self.indextable)]
+
     def write_xml(self, node: ET.Element) -> None:
         for index in sorted(self.indextable):
             s = self.indextable[index]
@@ -184,6 +184,13 @@ class CHCGuiNotFoundError(CHCError):
             return "CodeHawk C Gui executable not found at " + self.location
 
 
+class CHCUnknownTagError(CHCError):
+    def __init__(self, table: str, tag: str) -> None:
+        CHCError.__init__(self, "Unknown tag " + tag + " in table " + table)
+        self.table = table
+        self.tag = tag
+
+
 class CHCFileNotFoundError(CHCError):
     def __init__(self, filename: str) -> None:
         CHCError.__init__(self, "File " + filename + " not found")
@@ -620,6 +627,13 @@ def get_analysisresults_path(targetpath: str, projectname: str) -> str:
     return os.path.join(cchpath, "a")
 
 
+def get_dictionary_cache_path(targetpath: str, projectname: str) -> str:
+    """Returns the directory with binary snapshots of dictionary files."""
+
+    return os.path.join(
+        get_analysisresults_path(targetpath, projectname), ".cache")
+
+
 def has_analysisresults_path(targetpath: str, projectname: str) -> bool:
     path = get_analysisresults_path(targetpath, projectname)
     return os.path.isdir(path)
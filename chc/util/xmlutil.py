@@ -25,7 +25,7 @@
 # SOFTWARE.
 # ------------------------------------------------------------------------------
 
-from typing import Dict, List
+from typing import Callable, Dict, List, Optional
 import xml.etree.ElementTree as ET
 import datetime
 import os
@@ -46,6 +46,12 @@ def sanitize(s: str) -> str:
     return s
 
 
+# Pretty-printed text to write in place of an element, given its indentation;
+# used for large subtrees (e.g., dictionaries) that are serialized directly
+# rather than built as ElementTree nodes.
+Splices = Dict[ET.Element, Callable[[int], str]]
+
+
 def attributes_to_pretty(attr: Dict[str, str], indent: int = 0) -> str:
     if len(attr) == 0:
         return ""
@@ -62,8 +68,13 @@ def attributes_to_pretty(attr: Dict[str, str], indent: int = 0) -> str:
         )
 
 
-def element_to_pretty(e: ET.Element, indent: int = 0) -> List[str]:
+def element_to_pretty(
+        e: ET.Element, indent: int = 0, splices: Optional[Splices] = None
+) -> List[str]:
     lines: List[str] = []
+    if splices is not None and e in splices:
+        lines.append(splices[e](indent))
+        return lines
     attrs = attributes_to_pretty(e.attrib, indent)
     ind = " " * indent
     if e.text is None:
@@ -74,7 +85,7 @@ def element_to_pretty(e: ET.Element, indent: int = 0) -> List[str]:
         else:
             lines.append(ind + "<" + e.tag + attrs + ">\n")
             for c in children:
-                lines.extend(element_to_pretty(c, indent + 2))
+                lines.extend(element_to_pretty(c, indent + 2, splices))
             lines.append(ind + "</" + e.tag + ">\n")
             return lines
     else:
@@ -82,9 +93,13 @@ def element_to_pretty(e: ET.Element, indent: int = 0) -> List[str]:
     return lines
 
 
-def doc_to_pretty(t: ET.ElementTree) -> str:
+def doc_to_pretty(t: ET.ElementTree, splices: Optional[Splices] = None) -> str:
+    """Return the pretty-printed document.
+
+    Elements in splices are replaced by the text their entry produces.
+    """
     lines = ['<?xml version="1.0" encoding="UTF-8"?>\n']
-    lines.extend(element_to_pretty(t.getroot()))
+    lines.extend(element_to_pretty(t.getroot(), 0, splices))
     return "".join(lines)
 
 
@@ -23,6 +23,13 @@ chc_test () {(
 	chkc kendra test-sets
 )}
 
+chc_unittest () {(
+	set -e
+	chc_build
+	cd "$PROJECT_DIR"
+	python3 -m unittest discover -s tests/python
+)}
+
 chc_test_parallel () {(
 	set -e
 	chc_build
@@ -28,9 +28,30 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 ------------------------------------------------------------------------------
 */
-use pyo3::{intern, prelude::*};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fs,
+    path::{Path, PathBuf},
+    sync::{Condvar, Mutex, MutexGuard},
+    thread,
+};
 
-use crate::app::index_manager::IndexManager;
+use pyo3::{intern, prelude::*, types::PyDict};
+use quick_xml::{events::Event, Reader};
+
+use crate::{
+    app::{
+        c_file::{chklogger, CFile},
+        c_function::{fileutil, python_class},
+        index_manager::IndexManager,
+    },
+    util::{
+        dictionary_cache, dictionary_xml::XmlTables, file_fingerprint::Fingerprint,
+        xml_element::XmlElement,
+    },
+};
+
+pyo3::import_exception!(chc.util.fileutil, CHCError);
 
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "c_application")?;
@@ -38,6 +59,154 @@ pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     Ok(module)
 }
 
+/// Default upper bound, in bytes of xml on disk, on the files that are being
+/// parsed or wait to have their python objects built.
+const DEFAULT_MEMORY_BUDGET: u64 = 512 * 1024 * 1024;
+
+/// A file listed in target_files.xml, with the locations of its dictionary,
+/// globals and function files.
+struct TargetFile {
+    index: isize,
+    cfilename: String,
+    cfilepath: Option<String>,
+    /// The results directory, which holds the files below.
+    results: PathBuf,
+    dictionary: PathBuf,
+    /// The _cfile.xml file, if present.
+    globals: Option<PathBuf>,
+    /// Function files by function name.
+    functions: Vec<(String, PathBuf)>,
+    /// Total size of the files above.
+    size: u64,
+}
+
+/// The files of a target file, parsed on a worker thread, with the
+/// fingerprint of its results directory taken before.
+struct ParsedFile {
+    fingerprint: std::io::Result<Fingerprint>,
+    tables: PyResult<XmlTables>,
+    globals: Option<PyResult<XmlElement>>,
+    functions: Vec<PyResult<XmlElement>>,
+}
+
+/// The files of a load that are admitted for parsing and not yet built,
+/// bounded by the total on-disk size of their xml files.
+///
+/// Files are admitted in order, so the next file to be built is always
+/// admitted before any file after it; a file larger than the budget is
+/// admitted when no other file is in flight.
+struct ParseWindow {
+    budget: u64,
+    state: Mutex<WindowState>,
+    /// Signalled when in-flight bytes are released or the window is closed.
+    room: Condvar,
+    /// Signalled when a file is parsed or the window is closed.
+    parsed: Condvar,
+}
+
+struct WindowState {
+    /// Position of the next file to admit.
+    next: usize,
+    /// Total size of the files admitted and not yet released.
+    in_flight: u64,
+    closed: bool,
+    parsed: HashMap<usize, ParsedFile>,
+}
+
+impl ParseWindow {
+    fn new(budget: u64) -> Self {
+        ParseWindow {
+            budget,
+            state: Mutex::new(WindowState {
+                next: 0,
+                in_flight: 0,
+                closed: false,
+                parsed: HashMap::new(),
+            }),
+            room: Condvar::new(),
+            parsed: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, WindowState> {
+        self.state.lock().unwrap_or_else(|err| err.into_inner())
+    }
+
+    /// Waits until the next file of `targets` fits in the window and returns
+    /// its position, or None when all files are admitted or the window is
+    /// closed.
+    fn admit(&self, targets: &[TargetFile]) -> Option<usize> {
+        let mut state = self.lock();
+        loop {
+            let next = targets.get(state.next).filter(|_| !state.closed)?;
+            if state.in_flight == 0 || state.in_flight + next.size <= self.budget {
+                state.in_flight += next.size;
+                state.next += 1;
+                return Some(state.next - 1);
+            }
+            state = self.room.wait(state).unwrap_or_else(|err| err.into_inner());
+        }
+    }
+
+    fn put(&self, position: usize, file: ParsedFile) {
+        self.lock().parsed.insert(position, file);
+        self.parsed.notify_all();
+    }
+
+    /// Waits for the file at `position` to be parsed and takes it, or returns
+    /// None if the window is closed first.
+    fn take(&self, position: usize) -> Option<ParsedFile> {
+        let mut state = self.lock();
+        loop {
+            if let Some(file) = state.parsed.remove(&position) {
+                return Some(file);
+            }
+            if state.closed {
+                return None;
+            }
+            state = self
+                .parsed
+                .wait(state)
+                .unwrap_or_else(|err| err.into_inner());
+        }
+    }
+
+    /// Makes room for other files once `size` bytes of parsed xml are built.
+    fn release(&self, size: u64) {
+        self.lock().in_flight -= size;
+        self.room.notify_all();
+    }
+
+    /// Stops the admission of files, and wakes up everyone waiting.
+    fn close(&self) {
+        self.lock().closed = true;
+        self.room.notify_all();
+        self.parsed.notify_all();
+    }
+}
+
+/// Closes the window when dropped, so that waiting workers stop when the
+/// loading loop returns, also on an error.
+struct CloseWindow<'a>(&'a ParseWindow);
+
+impl Drop for CloseWindow<'_> {
+    fn drop(&mut self) {
+        self.0.close()
+    }
+}
+
+/// Closes the window if a worker panics, so that the loading loop does not
+/// wait for the file it was parsing.
+struct CloseWindowOnPanic<'a>(&'a ParseWindow);
+
+impl Drop for CloseWindowOnPanic<'_> {
+    fn drop(&mut self) {
+        if thread::panicking() {
+            self.0.close()
+        }
+    }
+}
+
 /// Primary access point for source code and analysis results.
 ///
 /// An application can consist of a single file, or of multiple files managed
@@ -79,6 +248,11 @@ pub struct CApplication {
     excludefiles: Vec<String>,
     #[pyo3(get)]
     indexmanager: Py<IndexManager>,
+    /// Results directory and its fingerprint by file index.
+    fingerprints: BTreeMap<isize, (PathBuf, Fingerprint)>,
+    /// Fingerprints of changed files by file index, that replace the recorded
+    /// ones once the files are reloaded.
+    refreshed: BTreeMap<isize, Fingerprint>,
 }
 
 #[pymethods]
@@ -111,6 +285,334 @@ impl CApplication {
             is_singlefile: singlefile.unwrap_or(false),
             excludefiles: excludefiles.unwrap_or_else(|| Vec::new()),
             indexmanager,
+            fingerprints: BTreeMap::new(),
+            refreshed: BTreeMap::new(),
         })
     }
+
+    /// Records the current state of the analysis results of `cfile`, to be
+    /// compared against by changed_files.
+    ///
+    /// The xml files are not hashed, so that opening a project only reads
+    /// their metadata; changed_files then takes a file whose modification
+    /// time changed for changed. load_files records fingerprints with hashes.
+    fn record_fingerprint(&mut self, py: Python, cfile: &Bound<PyAny>) -> PyResult<()> {
+        let index: isize = cfile.getattr(intern!(py, "index"))?.extract()?;
+        let dir: PathBuf = fileutil(py)?
+            .call_method1(
+                intern!(py, "get_cfile_filepath"),
+                (
+                    &self.targetpath,
+                    &self.projectname,
+                    cfile.getattr(intern!(py, "cfilepath"))?,
+                    cfile.getattr(intern!(py, "cfilename"))?,
+                ),
+            )?
+            .extract()?;
+        let fingerprint = py.allow_threads(|| Fingerprint::of_dir_unhashed(&dir))?;
+        self.fingerprints.insert(index, (dir, fingerprint));
+        self.refreshed.remove(&index);
+        Ok(())
+    }
+
+    /// Indices of the files whose analysis results changed on disk since
+    /// they were recorded or last reloaded, in increasing order.
+    ///
+    /// The fingerprints of the changed files are kept until the files are
+    /// reloaded, see commit_fingerprint; a file thus counts as changed until
+    /// then.
+    fn changed_files(&mut self, py: Python) -> PyResult<Vec<isize>> {
+        let CApplication {
+            fingerprints,
+            refreshed,
+            ..
+        } = self;
+        Ok(py.allow_threads(|| -> std::io::Result<_> {
+            let mut changed = Vec::new();
+            for (index, (dir, fingerprint)) in fingerprints.iter_mut() {
+                let (current, is_changed) = fingerprint.refresh(dir)?;
+                if is_changed {
+                    refreshed.insert(*index, current);
+                    changed.push(*index);
+                } else {
+                    // Only modification times of unchanged files differ
+                    *fingerprint = current;
+                    refreshed.remove(index);
+                }
+            }
+            Ok(changed)
+        })?)
+    }
+
+    /// Records the fingerprint that changed_files found for file `index`,
+    /// once the file is reloaded.
+    fn commit_fingerprint(&mut self, index: isize) {
+        if let Some(fingerprint) = self.refreshed.remove(&index) {
+            if let Some((_, recorded)) = self.fingerprints.get_mut(&index) {
+                *recorded = fingerprint;
+            }
+        }
+    }
+
+    /// (id, name) of the c-files listed in target_files.xml, except those in
+    /// excludefiles.
+    fn target_files(&self, py: Python) -> PyResult<Vec<(isize, String)>> {
+        let filename: String = fileutil(py)?
+            .call_method1(
+                intern!(py, "get_targetfiles_filename"),
+                (&self.targetpath, &self.projectname),
+            )?
+            .extract()?;
+        if !Path::new(&filename).is_file() {
+            return Err(xml_parse_error(py, &filename));
+        }
+        let mut reader =
+            Reader::from_file(&filename).map_err(|_| xml_parse_error(py, &filename))?;
+        let mut result = Vec::new();
+        let mut buf = Vec::new();
+        loop {
+            let event = reader
+                .read_event_into(&mut buf)
+                .map_err(|_| xml_parse_error(py, &filename))?;
+            match event {
+                Event::Start(e) | Event::Empty(e) if e.name().as_ref() == b"c-file" => {
+                    let mut name = None;
+                    let mut id = None;
+                    for attr in e.attributes() {
+                        let attr = attr.map_err(|_| xml_parse_error(py, &filename))?;
+                        let text = attr
+                            .unescape_value()
+                            .map_err(|_| xml_parse_error(py, &filename))?;
+                        match attr.key.as_ref() {
+                            b"name" => name = Some(text.into_owned()),
+                            b"id" => id = Some(text.into_owned()),
+                            _ => (),
+                        }
+                    }
+                    let Some(name) = name else {
+                        return Err(xml_parse_error(py, &filename));
+                    };
+                    if self.excludefiles.contains(&name) {
+                        continue;
+                    }
+                    match id {
+                        Some(id) => {
+                            let id = id.parse().map_err(|_| xml_parse_error(py, &filename))?;
+                            result.push((id, name));
+                        }
+                        None => {
+                            chklogger(py, "error", format!("No id found for target file {name}"))?
+                        }
+                    }
+                }
+                Event::Eof => break,
+                _ => (),
+            }
+            buf.clear();
+        }
+        Ok(result)
+    }
+
+    /// Loads every file listed in target_files.xml, with its dictionary,
+    /// declarations, globals and functions, and returns the files by file
+    /// index.
+    ///
+    /// The dictionary, _cfile.xml and function files of each file are parsed
+    /// on one of `workers` threads (by default the available parallelism),
+    /// while the python objects are built on the calling thread, in the order
+    /// of target_files.xml. A worker starts on a file only while the files
+    /// being parsed or waiting to be built, that one included, take at most
+    /// `memory_budget` bytes of xml on disk; a larger file is parsed when no
+    /// other file is in flight. This bounds the parsed xml only: the loaded
+    /// files are all kept. The fingerprints of the results directories are
+    /// taken, with hashes, on the workers as well.
+    #[pyo3(signature = (workers=None, memory_budget=None))]
+    fn load_files<'a>(
+        slf: &Bound<'a, Self>,
+        workers: Option<usize>,
+        memory_budget: Option<u64>,
+    ) -> PyResult<Bound<'a, PyDict>> {
+        let py = slf.py();
+        let (targets, cachedir, indexmanager) = {
+            let this = slf.borrow();
+            let fileutil = fileutil(py)?;
+            let cachedir: PathBuf = fileutil
+                .call_method1(
+                    intern!(py, "get_dictionary_cache_path"),
+                    (&this.targetpath, &this.projectname),
+                )?
+                .extract()?;
+            let mut targets = Vec::new();
+            for (index, name) in this.target_files(py)? {
+                let path = Path::new(&name);
+                let cfilepath = path
+                    .parent()
+                    .map(|p| p.to_string_lossy().into_owned())
+                    .filter(|p| !p.is_empty());
+                let basename = path
+                    .file_name()
+                    .map(|n| n.to_string_lossy().into_owned())
+                    .unwrap_or_default();
+                let cfilename = basename.strip_suffix(".c").unwrap_or(&basename).to_string();
+                let dictionary: PathBuf = fileutil
+                    .call_method1(
+                        intern!(py, "get_cfile_dictionaryname"),
+                        (
+                            &this.targetpath,
+                            &this.projectname,
+                            cfilepath.clone(),
+                            &cfilename,
+                        ),
+                    )?
+                    .extract()?;
+                let Ok(metadata) = fs::metadata(&dictionary) else {
+                    return Err(CHCError::new_err(format!(
+                        "File dictionary file not found for {name}"
+                    )));
+                };
+                let mut size = metadata.len();
+                let args = (
+                    &this.targetpath,
+                    &this.projectname,
+                    cfilepath.clone(),
+                    &cfilename,
+                );
+                let results: PathBuf = fileutil
+                    .call_method1(intern!(py, "get_cfile_filepath"), args.clone())?
+                    .extract()?;
+                let globals: PathBuf = fileutil
+                    .call_method1(intern!(py, "get_cfile_cfile"), args.clone())?
+                    .extract()?;
+                let globals = match fs::metadata(&globals) {
+                    Ok(metadata) => {
+                        size += metadata.len();
+                        Some(globals)
+                    }
+                    Err(_) => None,
+                };
+                // Each function has a directory of its own under functions
+                let fnspath: PathBuf = fileutil
+                    .call_method1(intern!(py, "get_cfile_fnspath"), args)?
+                    .extract()?;
+                let mut functions = Vec::new();
+                for entry in fs::read_dir(&fnspath).into_iter().flatten().flatten() {
+                    let fnname = entry.file_name().to_string_lossy().into_owned();
+                    let cfun: PathBuf = fileutil
+                        .call_method1(
+                            intern!(py, "get_cfun_filename"),
+                            (
+                                &this.targetpath,
+                                &this.projectname,
+                                cfilepath.clone(),
+                                &cfilename,
+                                &fnname,
+                            ),
+                        )?
+                        .extract()?;
+                    if let Ok(metadata) = fs::metadata(&cfun) {
+                        size += metadata.len();
+                        functions.push((fnname, cfun));
+                    }
+                }
+                targets.push(TargetFile {
+                    index,
+                    cfilename,
+                    cfilepath,
+                    results,
+                    dictionary,
+                    globals,
+                    functions,
+                    size,
+                });
+            }
+            (targets, cachedir, this.indexmanager.clone_ref(py))
+        };
+        let workers = workers
+            .unwrap_or_else(|| thread::available_parallelism().map_or(1, |n| n.get()))
+            .max(1);
+        let window = ParseWindow::new(memory_budget.unwrap_or(DEFAULT_MEMORY_BUDGET));
+        let cfile_type = python_class(py, "chc.app.CFile", "CFile")?;
+        let files = PyDict::new_bound(py);
+        thread::scope(|scope| -> PyResult<()> {
+            for _ in 0..workers.min(targets.len()) {
+                scope.spawn(|| {
+                    let _close = CloseWindowOnPanic(&window);
+                    while let Some(position) = window.admit(&targets) {
+                        window.put(position, parse_file(&targets[position], &cachedir));
+                    }
+                });
+            }
+            let _close = CloseWindow(&window);
+            for (position, target) in targets.iter().enumerate() {
+                let parsed = py
+                    .allow_threads(|| window.take(position))
+                    .expect("a worker stopped while parsing");
+                let cfile = cfile_type.call1((
+                    slf,
+                    target.index,
+                    &target.cfilename,
+                    target.cfilepath.clone(),
+                ))?;
+                let cfile_ref = cfile.downcast::<CFile>()?;
+                CFile::set_dictionary_tables(cfile_ref, parsed.tables?)?;
+                if let Some(globals) = parsed.globals {
+                    if let Some(xcfile) = globals?.find("c-file") {
+                        CFile::set_cfileglobals(cfile_ref, xcfile.to_element(py)?)?;
+                    }
+                }
+                let mut xnodes = HashMap::new();
+                for ((fnname, _), function) in target.functions.iter().zip(parsed.functions) {
+                    if let Some(xnode) = function?.find("function") {
+                        xnodes.insert(fnname.clone(), xnode.to_element(py)?);
+                    }
+                }
+                CFile::set_functions(cfile_ref, xnodes)?;
+                window.release(target.size);
+                // add_file reads the globals and declarations set above
+                indexmanager.call_method1(py, intern!(py, "add_file"), (&cfile,))?;
+                {
+                    let mut this = slf.borrow_mut();
+                    let fingerprint = parsed.fingerprint?;
+                    this.fingerprints
+                        .insert(target.index, (target.results.clone(), fingerprint));
+                    this.refreshed.remove(&target.index);
+                }
+                files.set_item(target.index, cfile)?;
+            }
+            Ok(())
+        })?;
+        chklogger(py, "info", format!("Loaded {} files", files.len()))?;
+        Ok(files)
+    }
+}
+
+fn xml_parse_error(py: Python, filename: &str) -> PyErr {
+    match python_class(py, "chc.util.fileutil", "CHCXmlParseError")
+        .and_then(|class| class.call1((filename, 0, (0, 0))))
+    {
+        Ok(err) => PyErr::from_value_bound(err),
+        Err(err) => err,
+    }
+}
+
+/// Parses the dictionary, _cfile.xml and function files of `target`, after
+/// taking the fingerprint of its results, so that changes made while parsing
+/// show up in changed_files.
+fn parse_file(target: &TargetFile, cachedir: &Path) -> ParsedFile {
+    ParsedFile {
+        fingerprint: Fingerprint::of_dir(&target.results),
+        tables: dictionary_cache::read_xml_tables_cached(
+            target.dictionary.clone(),
+            cachedir.to_path_buf(),
+        ),
+        globals: target
+            .globals
+            .as_ref()
+            .map(|globals| XmlElement::read(globals)),
+        functions: target
+            .functions
+            .iter()
+            .map(|(_, cfun)| XmlElement::read(cfun))
+            .collect(),
+    }
 }
@@ -30,26 +30,43 @@ SOFTWARE.
 */
 use std::borrow::Cow;
 
-use pyo3::{intern, prelude::*};
+use pyo3::{intern, prelude::*, types::PyString};
 
 use crate::{
     app::{
+        c_data_model::{CDataModel, LayoutAttributes},
         c_dictionary::CDictionary,
         c_dictionary_record::{
             CDictionaryRecord, CDictionaryRecordTrait, CDictionaryRegistryEntry,
         },
+        c_typ::CTyp,
     },
     util::indexed_table::{
         inherit_indexed_table_value_trait, IndexedTableValue, IndexedTableValueTrait,
     },
 };
 
+pyo3::import_exception!(chc.util.fileutil, CHCError);
+
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "c_attributes")?;
     module.add_class::<CAttr>()?;
+    module.add_class::<CAttrAddrOf>()?;
+    module.add_class::<CAttrAlignOf>()?;
+    module.add_class::<CAttrAlignOfE>()?;
+    module.add_class::<CAttrAlignOfS>()?;
+    module.add_class::<CAttrBinOp>()?;
     module.add_class::<CAttrCons>()?;
+    module.add_class::<CAttrDot>()?;
+    module.add_class::<CAttrIndex>()?;
     module.add_class::<CAttrInt>()?;
+    module.add_class::<CAttrQuestion>()?;
+    module.add_class::<CAttrSizeOf>()?;
+    module.add_class::<CAttrSizeOfE>()?;
+    module.add_class::<CAttrSizeOfS>()?;
+    module.add_class::<CAttrStar>()?;
     module.add_class::<CAttrStr>()?;
+    module.add_class::<CAttrUnOp>()?;
     module.add_class::<CAttribute>()?;
     module.add_class::<CAttributes>()?;
     Ok(module)
@@ -154,6 +171,43 @@ impl CAttr {
     }
 }
 
+impl CAttr {
+    /// Argument `n`, or CHCError if the record is too short.
+    fn arg(slf: &PyRef<Self>, n: usize) -> PyResult<isize> {
+        slf.args().get(n).copied().ok_or_else(|| {
+            CHCError::new_err(format!(
+                "Attribute parameter {} has no argument {n}",
+                slf.tags()[0]
+            ))
+        })
+    }
+
+    /// Attribute parameter with the index in argument `n`.
+    fn param<'a>(slf: PyRef<Self>, py: Python<'a>, n: usize) -> PyResult<Bound<'a, CAttr>> {
+        let ix = Self::arg(&slf, n)?;
+        CDictionary::get_attrparam(slf.into_super().cd().bind(py), ix)
+    }
+
+    /// Result of the cdictionary `method` for the index in argument `n`.
+    fn lookup<'a>(
+        slf: PyRef<Self>,
+        py: Python<'a>,
+        method: &Bound<'a, PyString>,
+        n: usize,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let ix = Self::arg(&slf, n)?;
+        slf.into_super().cd().bind(py).call_method1(method, (ix,))
+    }
+
+    fn typ<'a>(slf: PyRef<Self>, py: Python<'a>, n: usize) -> PyResult<Bound<'a, CTyp>> {
+        Ok(Self::lookup(slf, py, intern!(py, "get_typ"), n)?.downcast_into()?)
+    }
+
+    fn typsig<'a>(slf: PyRef<Self>, py: Python<'a>, n: usize) -> PyResult<Bound<'a, PyAny>> {
+        Self::lookup(slf, py, intern!(py, "get_typsig"), n)
+    }
+}
+
 /// Integer attribute.
 ///
 /// args[0]: integer value
@@ -185,7 +239,16 @@ impl CAttrInt {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CAttr, CAttrInt>("aint") }
+impl CDictionaryRecordTrait for CAttrInt {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrInt>("aint") }
 
 /// String attribute.
 ///
@@ -225,7 +288,16 @@ impl CAttrStr {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CAttr, CAttrStr>("astr") }
+impl CDictionaryRecordTrait for CAttrStr {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrStr>("astr") }
 
 /// Constructed attributes.
 ///
@@ -274,7 +346,620 @@ impl CAttrCons {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CAttr, CAttrCons>("acons") }
+impl CDictionaryRecordTrait for CAttrCons {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrCons>("acons") }
+
+/// Attribute that describes the size of a type.
+///
+/// * args[0]: index of target type in cdictionary
+#[pyclass(extends = CAttr, frozen, subclass)]
+struct CAttrSizeOf {}
+
+#[pymethods]
+impl CAttrSizeOf {
+    #[new]
+    fn new(cd: Py<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CAttr::new(cd, ixval)).add_subclass(CAttrSizeOf {})
+    }
+
+    #[getter]
+    fn typ<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
+        CAttr::typ(slf.into_super(), py, 0)
+    }
+
+    #[getter]
+    fn is_sizeof(&self) -> bool {
+        true
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: PyRef<Self>, py: Python) -> PyResult<String> {
+        Ok(format!("asizeof({})", Self::typ(slf, py)?.str()?))
+    }
+}
+
+impl CDictionaryRecordTrait for CAttrSizeOf {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrSizeOf>("asizeof") }
+
+/// Size of an attribute parameter.
+///
+/// * args[0]: index of attribute parameter in cdictionary
+#[pyclass(extends = CAttr, frozen, subclass)]
+struct CAttrSizeOfE {}
+
+#[pymethods]
+impl CAttrSizeOfE {
+    #[new]
+    fn new(cd: Py<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CAttr::new(cd, ixval)).add_subclass(CAttrSizeOfE {})
+    }
+
+    #[getter]
+    fn param<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CAttr>> {
+        CAttr::param(slf.into_super(), py, 0)
+    }
+
+    #[getter]
+    fn is_sizeofe(&self) -> bool {
+        true
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: PyRef<Self>, py: Python) -> PyResult<String> {
+        Ok(format!("asizeofe({})", Self::param(slf, py)?.str()?))
+    }
+}
+
+impl CDictionaryRecordTrait for CAttrSizeOfE {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrSizeOfE>("asizeofe") }
+
+/// Replacement ASizeOf in type signatures.
+///
+/// * args[0]: index of target typsig in cdictionary
+#[pyclass(extends = CAttr, frozen, subclass)]
+struct CAttrSizeOfS {}
+
+#[pymethods]
+impl CAttrSizeOfS {
+    #[new]
+    fn new(cd: Py<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CAttr::new(cd, ixval)).add_subclass(CAttrSizeOfS {})
+    }
+
+    #[getter]
+    fn typsig<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        CAttr::typsig(slf.into_super(), py, 0)
+    }
+
+    #[getter]
+    fn is_sizeofs(&self) -> bool {
+        true
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: PyRef<Self>, py: Python) -> PyResult<String> {
+        Ok(format!("asizeofs({})", Self::typsig(slf, py)?.str()?))
+    }
+}
+
+impl CDictionaryRecordTrait for CAttrSizeOfS {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrSizeOfS>("asizeofs") }
+
+/// Alignment of a type.
+///
+/// * args[0]: index of target type in cdictionary
+#[pyclass(extends = CAttr, frozen, subclass)]
+struct CAttrAlignOf {}
+
+#[pymethods]
+impl CAttrAlignOf {
+    #[new]
+    fn new(cd: Py<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CAttr::new(cd, ixval)).add_subclass(CAttrAlignOf {})
+    }
+
+    #[getter]
+    fn typ<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
+        CAttr::typ(slf.into_super(), py, 0)
+    }
+
+    #[getter]
+    fn is_alignof(&self) -> bool {
+        true
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: PyRef<Self>, py: Python) -> PyResult<String> {
+        Ok(format!("aalignof({})", Self::typ(slf, py)?.str()?))
+    }
+}
+
+impl CDictionaryRecordTrait for CAttrAlignOf {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrAlignOf>("aalignof") }
+
+/// Alignment of an attribute parameter.
+///
+/// * args[0]: index of attribute parameter in cdictionary
+#[pyclass(extends = CAttr, frozen, subclass)]
+struct CAttrAlignOfE {}
+
+#[pymethods]
+impl CAttrAlignOfE {
+    #[new]
+    fn new(cd: Py<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CAttr::new(cd, ixval)).add_subclass(CAttrAlignOfE {})
+    }
+
+    #[getter]
+    fn param<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CAttr>> {
+        CAttr::param(slf.into_super(), py, 0)
+    }
+
+    #[getter]
+    fn is_alignofe(&self) -> bool {
+        true
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: PyRef<Self>, py: Python) -> PyResult<String> {
+        Ok(format!("aalignofe({})", Self::param(slf, py)?.str()?))
+    }
+}
+
+impl CDictionaryRecordTrait for CAttrAlignOfE {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrAlignOfE>("aalignofe") }
+
+/// Alignment of a type signature.
+///
+/// * args[0]: index of target typsig in cdictionary
+#[pyclass(extends = CAttr, frozen, subclass)]
+struct CAttrAlignOfS {}
+
+#[pymethods]
+impl CAttrAlignOfS {
+    #[new]
+    fn new(cd: Py<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CAttr::new(cd, ixval)).add_subclass(CAttrAlignOfS {})
+    }
+
+    #[getter]
+    fn typsig<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        CAttr::typsig(slf.into_super(), py, 0)
+    }
+
+    #[getter]
+    fn is_alignofs(&self) -> bool {
+        true
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: PyRef<Self>, py: Python) -> PyResult<String> {
+        Ok(format!("aalignofs({})", Self::typsig(slf, py)?.str()?))
+    }
+}
+
+impl CDictionaryRecordTrait for CAttrAlignOfS {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrAlignOfS>("aalignofs") }
+
+/// Unary attribute parameter operation.
+///
+/// * tags[1]: operator
+/// * args[0]: index of attribute parameter in cdictionary
+#[pyclass(extends = CAttr, frozen, subclass)]
+struct CAttrUnOp {}
+
+#[pymethods]
+impl CAttrUnOp {
+    #[new]
+    fn new(cd: Py<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CAttr::new(cd, ixval)).add_subclass(CAttrUnOp {})
+    }
+
+    #[getter]
+    fn op(slf: PyRef<Self>) -> String {
+        slf.into_super().tags()[1].to_string()
+    }
+
+    #[getter]
+    fn param<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CAttr>> {
+        CAttr::param(slf.into_super(), py, 0)
+    }
+
+    #[getter]
+    fn is_unop(&self) -> bool {
+        true
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: &Bound<Self>) -> PyResult<String> {
+        let py = slf.py();
+        Ok(format!(
+            "aunop({},{})",
+            Self::op(slf.borrow()),
+            Self::param(slf.borrow(), py)?.str()?
+        ))
+    }
+}
+
+impl CDictionaryRecordTrait for CAttrUnOp {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrUnOp>("aunop") }
+
+/// Binary attribute parameter operation.
+///
+/// * tags[1]: operator
+/// * args[0]: index of first attribute parameter in cdictionary
+/// * args[1]: index of second attribute parameter in cdictionary
+#[pyclass(extends = CAttr, frozen, subclass)]
+struct CAttrBinOp {}
+
+#[pymethods]
+impl CAttrBinOp {
+    #[new]
+    fn new(cd: Py<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CAttr::new(cd, ixval)).add_subclass(CAttrBinOp {})
+    }
+
+    #[getter]
+    fn op(slf: PyRef<Self>) -> String {
+        slf.into_super().tags()[1].to_string()
+    }
+
+    #[getter]
+    fn param1<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CAttr>> {
+        CAttr::param(slf.into_super(), py, 0)
+    }
+
+    #[getter]
+    fn param2<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CAttr>> {
+        CAttr::param(slf.into_super(), py, 1)
+    }
+
+    #[getter]
+    fn is_binop(&self) -> bool {
+        true
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: &Bound<Self>) -> PyResult<String> {
+        let py = slf.py();
+        Ok(format!(
+            "abinop({} {} {})",
+            Self::param1(slf.borrow(), py)?.str()?,
+            Self::op(slf.borrow()),
+            Self::param2(slf.borrow(), py)?.str()?
+        ))
+    }
+}
+
+impl CDictionaryRecordTrait for CAttrBinOp {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrBinOp>("abinop") }
+
+/// Dot operator on attributes.
+///
+/// * tags[1]: string suffix
+/// * args[0]: index of attribute parameter in cdictionary
+#[pyclass(extends = CAttr, frozen, subclass)]
+struct CAttrDot {}
+
+#[pymethods]
+impl CAttrDot {
+    #[new]
+    fn new(cd: Py<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CAttr::new(cd, ixval)).add_subclass(CAttrDot {})
+    }
+
+    #[getter]
+    fn suffix(slf: PyRef<Self>) -> String {
+        slf.into_super().tags()[1].to_string()
+    }
+
+    #[getter]
+    fn param<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CAttr>> {
+        CAttr::param(slf.into_super(), py, 0)
+    }
+
+    #[getter]
+    fn is_dot(&self) -> bool {
+        true
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: &Bound<Self>) -> PyResult<String> {
+        let py = slf.py();
+        Ok(format!(
+            "adot({}.{})",
+            Self::param(slf.borrow(), py)?.str()?,
+            Self::suffix(slf.borrow())
+        ))
+    }
+}
+
+impl CDictionaryRecordTrait for CAttrDot {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrDot>("adot") }
+
+/// Star operation on attribute.
+///
+/// * args[0]: index of attribute parameter in cdictionary; a record that
+///   refers to itself stands for `*` without operand.
+#[pyclass(extends = CAttr, frozen, subclass)]
+struct CAttrStar {}
+
+#[pymethods]
+impl CAttrStar {
+    #[new]
+    fn new(cd: Py<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CAttr::new(cd, ixval)).add_subclass(CAttrStar {})
+    }
+
+    #[getter]
+    fn param<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CAttr>> {
+        if Self::is_self_referential(slf)? {
+            return Ok(slf.clone().into_any().downcast_into()?);
+        }
+        CAttr::param(slf.borrow().into_super(), slf.py(), 0)
+    }
+
+    #[getter]
+    fn is_star(&self) -> bool {
+        true
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: &Bound<Self>) -> PyResult<String> {
+        if Self::is_self_referential(slf)? {
+            Ok("astar()".to_string())
+        } else {
+            Ok(format!("astar({})", Self::param(slf)?.str()?))
+        }
+    }
+}
+
+impl CAttrStar {
+    fn is_self_referential(slf: &Bound<Self>) -> PyResult<bool> {
+        let attr = slf.borrow().into_super();
+        Ok(CAttr::arg(&attr, 0)? == attr.index())
+    }
+}
+
+impl CDictionaryRecordTrait for CAttrStar {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrStar>("astar") }
+
+/// Addressof operator on attribute.
+///
+/// * args[0]: index of attribute parameter in cdictionary
+#[pyclass(extends = CAttr, frozen, subclass)]
+struct CAttrAddrOf {}
+
+#[pymethods]
+impl CAttrAddrOf {
+    #[new]
+    fn new(cd: Py<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CAttr::new(cd, ixval)).add_subclass(CAttrAddrOf {})
+    }
+
+    #[getter]
+    fn param<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CAttr>> {
+        CAttr::param(slf.into_super(), py, 0)
+    }
+
+    #[getter]
+    fn is_addrof(&self) -> bool {
+        true
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: PyRef<Self>, py: Python) -> PyResult<String> {
+        Ok(format!("aaddrof({})", Self::param(slf, py)?.str()?))
+    }
+}
+
+impl CDictionaryRecordTrait for CAttrAddrOf {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrAddrOf>("aaddrof") }
+
+/// Index operation on attributes.
+///
+/// * args[0]: index of first attribute parameter in cdictionary
+/// * args[1]: index of second attribute parameter in cdictionary
+#[pyclass(extends = CAttr, frozen, subclass)]
+struct CAttrIndex {}
+
+#[pymethods]
+impl CAttrIndex {
+    #[new]
+    fn new(cd: Py<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CAttr::new(cd, ixval)).add_subclass(CAttrIndex {})
+    }
+
+    #[getter]
+    fn param1<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CAttr>> {
+        CAttr::param(slf.into_super(), py, 0)
+    }
+
+    #[getter]
+    fn param2<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CAttr>> {
+        CAttr::param(slf.into_super(), py, 1)
+    }
+
+    #[getter]
+    fn is_index(&self) -> bool {
+        true
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: &Bound<Self>) -> PyResult<String> {
+        let py = slf.py();
+        Ok(format!(
+            "aindex({},{})",
+            Self::param1(slf.borrow(), py)?.str()?,
+            Self::param2(slf.borrow(), py)?.str()?
+        ))
+    }
+}
+
+impl CDictionaryRecordTrait for CAttrIndex {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrIndex>("aindex") }
+
+/// Question operator on attributes.
+///
+/// * args[0]: index of first attribute parameter in cdictionary
+/// * args[1]: index of second attribute parameter in cdictionary
+/// * args[2]: index of third attribute parameter in cdictionary
+#[pyclass(extends = CAttr, frozen, subclass)]
+struct CAttrQuestion {}
+
+#[pymethods]
+impl CAttrQuestion {
+    #[new]
+    fn new(cd: Py<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CAttr::new(cd, ixval)).add_subclass(CAttrQuestion {})
+    }
+
+    #[getter]
+    fn param1<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CAttr>> {
+        CAttr::param(slf.into_super(), py, 0)
+    }
+
+    #[getter]
+    fn param2<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CAttr>> {
+        CAttr::param(slf.into_super(), py, 1)
+    }
+
+    #[getter]
+    fn param3<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CAttr>> {
+        CAttr::param(slf.into_super(), py, 2)
+    }
+
+    #[getter]
+    fn is_question(&self) -> bool {
+        true
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: &Bound<Self>) -> PyResult<String> {
+        let py = slf.py();
+        Ok(format!(
+            "aquestion({},{},{})",
+            Self::param1(slf.borrow(), py)?.str()?,
+            Self::param2(slf.borrow(), py)?.str()?,
+            Self::param3(slf.borrow(), py)?.str()?
+        ))
+    }
+}
+
+impl CDictionaryRecordTrait for CAttrQuestion {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CAttr, CAttrQuestion>("aquestion") }
 
 #[pyclass(extends = CDictionaryRecord, frozen)]
 pub struct CAttribute {
@@ -321,8 +1006,11 @@ impl CAttribute {
 }
 
 impl CDictionaryRecordTrait for CAttribute {
-    fn new(cd: &Bound<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
-        Self::new(cd, ixval)
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd, ixval))
     }
 }
 
@@ -345,7 +1033,7 @@ impl CAttributes {
     }
 
     #[getter]
-    fn attributes<'a>(&self, py: Python<'a>) -> PyResult<Vec<Bound<'a, CAttribute>>> {
+    pub fn attributes<'a>(&self, py: Python<'a>) -> PyResult<Vec<Bound<'a, CAttribute>>> {
         self.args
             .iter()
             .map(|i| CDictionary::get_attribute(self.cd.bind(py), *i))
@@ -357,6 +1045,12 @@ impl CAttributes {
         self.args.len()
     }
 
+    /// Indices of the attributes in cdictionary.
+    #[getter]
+    pub fn attribute_indices(&self) -> Vec<isize> {
+        self.args.clone()
+    }
+
     #[pyo3(name = "__str__")]
     fn str(&self, py: Python) -> PyResult<String> {
         let attributes = self
@@ -368,8 +1062,46 @@ impl CAttributes {
     }
 }
 
+impl CAttributes {
+    /// The `packed` and `aligned` attributes among these.
+    pub fn layout_attributes(&self, py: Python, model: &CDataModel) -> PyResult<LayoutAttributes> {
+        Self::layout_attributes_in(py, &self.attributes(py)?, model)
+    }
+
+    /// The `packed` and `aligned` attributes among `attributes`; `aligned`
+    /// without argument asks for the largest alignment of `model`. Alignments
+    /// that are not integer constants are ignored.
+    pub fn layout_attributes_in(
+        py: Python,
+        attributes: &[Bound<CAttribute>],
+        model: &CDataModel,
+    ) -> PyResult<LayoutAttributes> {
+        let mut result = LayoutAttributes::default();
+        for attribute in attributes {
+            let attribute = attribute.get();
+            match attribute.name.trim_matches('_') {
+                "packed" => result.packed = true,
+                "aligned" => match attribute.args.first() {
+                    None => result.add_aligned(model.largest_align()),
+                    Some(ix) => {
+                        let param = CDictionary::get_attrparam(attribute.cd.bind(py), *ix)?;
+                        if let Ok(int) = param.downcast::<CAttrInt>() {
+                            result.add_aligned(CAttrInt::intvalue(int.borrow()));
+                        }
+                    }
+                },
+                _ => {}
+            }
+        }
+        Ok(result)
+    }
+}
+
 impl CDictionaryRecordTrait for CAttributes {
-    fn new(cd: &Bound<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self> {
-        Self::new(cd, ixval)
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd, ixval))
     }
 }
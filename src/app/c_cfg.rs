@@ -0,0 +1,430 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Control flow graph of a function.
+//!
+//! The nodes of the graph are the statements of the function body, with the
+//! successor relation recorded for each statement. The entry node is the
+//! first statement of the body. Dominators are computed with the algorithm of
+//! Cooper, Harvey and Kennedy ("A Simple, Fast Dominance Algorithm"); loops
+//! are the natural loops of the back edges.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+
+use pyo3::{intern, prelude::*};
+
+use crate::app::{c_context::CfgContext, c_stmt::CStmt};
+
+pyo3::import_exception!(chc.util.fileutil, CHCError);
+
+pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
+    let module = PyModule::new_bound(py, "c_cfg")?;
+    module.add_class::<Cfg>()?;
+    module.add_class::<CfgLoop>()?;
+    Ok(module)
+}
+
+/// Natural loop: the header and all nodes that can reach a back edge to the
+/// header without passing through it.
+#[pyclass(frozen, get_all)]
+#[derive(Clone, Debug)]
+pub struct CfgLoop {
+    header: isize,
+    /// Sources of the back edges to the header.
+    latches: Vec<isize>,
+    body: BTreeSet<isize>,
+}
+
+#[pymethods]
+impl CfgLoop {
+    fn contains(&self, sid: isize) -> bool {
+        self.body.contains(&sid)
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self) -> String {
+        format!(
+            "loop {} (latches: {}): {}",
+            self.header,
+            join(&self.latches),
+            join(self.body.iter())
+        )
+    }
+}
+
+#[pyclass(frozen)]
+pub struct Cfg {
+    /// Statement ids in program order.
+    nodes: Vec<isize>,
+    succs: HashMap<isize, Vec<isize>>,
+    preds: HashMap<isize, Vec<isize>>,
+    /// Nodes reachable from the entry in reverse postorder.
+    rpo: Vec<isize>,
+    /// Immediate dominator of each reachable node; the entry maps to itself.
+    idoms: HashMap<isize, isize>,
+    loops: Vec<CfgLoop>,
+}
+
+#[pymethods]
+impl Cfg {
+    /// Graph of the statements in `body` (usually a CFunctionBody).
+    #[new]
+    pub fn new(body: &Bound<CStmt>) -> PyResult<Self> {
+        let py = body.py();
+        let mut nodes = Vec::new();
+        let mut succs = HashMap::new();
+        for stmt in CStmt::all_nested(body)?.into_iter().skip(1) {
+            let sid = stmt.get().sid(py)?;
+            nodes.push(sid);
+            succs.insert(sid, stmt.get().succs(py)?);
+        }
+        Ok(Self::from_edges(nodes, succs))
+    }
+
+    #[getter]
+    fn entry(&self) -> Option<isize> {
+        self.nodes.first().copied()
+    }
+
+    #[getter]
+    fn nodes(&self) -> Vec<isize> {
+        self.nodes.clone()
+    }
+
+    /// Sources and targets of all edges.
+    #[getter]
+    fn edges(&self) -> Vec<(isize, isize)> {
+        self.nodes
+            .iter()
+            .flat_map(|src| self.succs[src].iter().map(move |dst| (*src, *dst)))
+            .collect()
+    }
+
+    fn successors(&self, sid: isize) -> PyResult<Vec<isize>> {
+        self.check(sid)?;
+        Ok(self.succs[&sid].clone())
+    }
+
+    fn predecessors(&self, sid: isize) -> PyResult<Vec<isize>> {
+        self.check(sid)?;
+        Ok(self.preds[&sid].clone())
+    }
+
+    /// Whether `sid` can be reached from the entry.
+    fn is_reachable(&self, sid: isize) -> PyResult<bool> {
+        self.check(sid)?;
+        Ok(self.idoms.contains_key(&sid))
+    }
+
+    /// Nodes that are not reachable from the entry, in program order.
+    #[getter]
+    fn unreachable(&self) -> Vec<isize> {
+        self.nodes
+            .iter()
+            .filter(|sid| !self.idoms.contains_key(sid))
+            .copied()
+            .collect()
+    }
+
+    /// Nodes reachable from `sid` (including `sid` itself).
+    fn reachable_from(&self, sid: isize) -> PyResult<BTreeSet<isize>> {
+        self.check(sid)?;
+        Ok(self.reach(sid, &self.succs, None))
+    }
+
+    /// Whether there is a path from `src` to `dst`.
+    fn has_path(&self, src: isize, dst: isize) -> PyResult<bool> {
+        self.check(dst)?;
+        Ok(self.reachable_from(src)?.contains(&dst))
+    }
+
+    /// Immediate dominator of `sid`, or None for the entry and for nodes that
+    /// are not reachable.
+    fn immediate_dominator(&self, sid: isize) -> PyResult<Option<isize>> {
+        self.check(sid)?;
+        Ok(self.idoms.get(&sid).filter(|idom| **idom != sid).copied())
+    }
+
+    /// Dominators of `sid` from the entry down to `sid` itself; empty if
+    /// `sid` is not reachable.
+    fn dominators(&self, sid: isize) -> PyResult<Vec<isize>> {
+        self.check(sid)?;
+        if !self.idoms.contains_key(&sid) {
+            return Ok(Vec::new());
+        }
+        let mut result = self.dominator_chain(sid);
+        result.reverse();
+        Ok(result)
+    }
+
+    /// Whether `dominator` dominates `sid` (every node dominates itself).
+    fn dominates(&self, dominator: isize, sid: isize) -> PyResult<bool> {
+        self.check(dominator)?;
+        Ok(self.dominators(sid)?.contains(&dominator))
+    }
+
+    /// Natural loops, outer loops before the loops nested in them.
+    #[getter]
+    fn loops(&self) -> Vec<CfgLoop> {
+        self.loops.clone()
+    }
+
+    /// Loops that contain `sid`, outermost first.
+    fn loops_containing(&self, sid: isize) -> PyResult<Vec<CfgLoop>> {
+        self.check(sid)?;
+        Ok(self
+            .loops
+            .iter()
+            .filter(|cfg_loop| cfg_loop.body.contains(&sid))
+            .cloned()
+            .collect())
+    }
+
+    /// Statement id of the innermost statement node in `context`, if it is a
+    /// node of this graph.
+    fn context_node(&self, context: &Bound<CfgContext>) -> PyResult<Option<isize>> {
+        let py = context.py();
+        for node in context
+            .getattr(intern!(py, "nodes"))?
+            .iter()?
+            .collect::<PyResult<Vec<_>>>()?
+            .iter()
+            .rev()
+        {
+            let name: String = node.getattr(intern!(py, "name"))?.extract()?;
+            if name == "stmt" {
+                let sid: isize = node.getattr(intern!(py, "data_id"))?.extract()?;
+                return Ok(self.succs.contains_key(&sid).then_some(sid));
+            }
+        }
+        Ok(None)
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self) -> String {
+        self.nodes
+            .iter()
+            .map(|sid| {
+                format!(
+                    "{sid:>4}: [{}] -> [{}]",
+                    join(&self.preds[sid]),
+                    join(&self.succs[sid])
+                )
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl Cfg {
+    /// Graph with the nodes `nodes`, the first of which is the entry, and
+    /// successors `succs`; edges to unknown nodes are dropped.
+    pub fn from_edges(nodes: Vec<isize>, mut succs: HashMap<isize, Vec<isize>>) -> Self {
+        let known: BTreeSet<isize> = nodes.iter().copied().collect();
+        let mut preds: HashMap<isize, Vec<isize>> =
+            nodes.iter().map(|sid| (*sid, Vec::new())).collect();
+        for src in &nodes {
+            let targets = succs.entry(*src).or_default();
+            targets.retain(|dst| known.contains(dst));
+            for dst in targets.iter() {
+                preds.get_mut(dst).into_iter().for_each(|p| p.push(*src));
+            }
+        }
+        let mut cfg = Cfg {
+            nodes,
+            succs,
+            preds,
+            rpo: Vec::new(),
+            idoms: HashMap::new(),
+            loops: Vec::new(),
+        };
+        cfg.rpo = cfg.reverse_postorder();
+        cfg.idoms = cfg.compute_dominators();
+        cfg.loops = cfg.natural_loops();
+        cfg
+    }
+
+    fn check(&self, sid: isize) -> PyResult<()> {
+        if self.succs.contains_key(&sid) {
+            Ok(())
+        } else {
+            Err(CHCError::new_err(format!(
+                "No statement with id {sid} in the control flow graph"
+            )))
+        }
+    }
+
+    fn reverse_postorder(&self) -> Vec<isize> {
+        let Some(entry) = self.nodes.first().copied() else {
+            return Vec::new();
+        };
+        let mut visited = BTreeSet::from([entry]);
+        let mut postorder = Vec::new();
+        // Iterative depth-first search; each frame holds a node and the
+        // position of the next successor to visit
+        let mut stack = vec![(entry, 0)];
+        while let Some((node, next)) = stack.pop() {
+            match self.succs[&node].get(next) {
+                Some(succ) => {
+                    stack.push((node, next + 1));
+                    if visited.insert(*succ) {
+                        stack.push((*succ, 0));
+                    }
+                }
+                None => postorder.push(node),
+            }
+        }
+        postorder.reverse();
+        postorder
+    }
+
+    fn compute_dominators(&self) -> HashMap<isize, isize> {
+        let Some(entry) = self.rpo.first().copied() else {
+            return HashMap::new();
+        };
+        let order: HashMap<isize, usize> = self
+            .rpo
+            .iter()
+            .enumerate()
+            .map(|(i, sid)| (*sid, i))
+            .collect();
+        let mut idoms = HashMap::from([(entry, entry)]);
+        let intersect = |idoms: &HashMap<isize, isize>, mut a: isize, mut b: isize| {
+            while a != b {
+                while order[&a] > order[&b] {
+                    a = idoms[&a];
+                }
+                while order[&b] > order[&a] {
+                    b = idoms[&b];
+                }
+            }
+            a
+        };
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for node in self.rpo.iter().skip(1) {
+                let mut new_idom = None;
+                for pred in &self.preds[node] {
+                    if idoms.contains_key(pred) {
+                        new_idom = Some(match new_idom {
+                            None => *pred,
+                            Some(idom) => intersect(&idoms, *pred, idom),
+                        });
+                    }
+                }
+                if let Some(new_idom) = new_idom {
+                    if idoms.insert(*node, new_idom) != Some(new_idom) {
+                        changed = true;
+                    }
+                }
+            }
+        }
+        idoms
+    }
+
+    /// Nodes reachable from `start` along `edges` without passing through
+    /// `barrier`.
+    fn reach(
+        &self,
+        start: isize,
+        edges: &HashMap<isize, Vec<isize>>,
+        barrier: Option<isize>,
+    ) -> BTreeSet<isize> {
+        let mut result = BTreeSet::from([start]);
+        let mut worklist = vec![start];
+        while let Some(node) = worklist.pop() {
+            if Some(node) == barrier {
+                continue;
+            }
+            for next in &edges[&node] {
+                if result.insert(*next) {
+                    worklist.push(*next);
+                }
+            }
+        }
+        result
+    }
+
+    fn natural_loops(&self) -> Vec<CfgLoop> {
+        let mut latches: BTreeMap<usize, (isize, Vec<isize>)> = BTreeMap::new();
+        for (position, header) in self.rpo.iter().enumerate() {
+            for pred in &self.preds[header] {
+                if self.idoms.contains_key(pred) && self.dominator_chain(*pred).contains(header) {
+                    latches
+                        .entry(position)
+                        .or_insert((*header, Vec::new()))
+                        .1
+                        .push(*pred);
+                }
+            }
+        }
+        latches
+            .into_values()
+            .map(|(header, latches)| {
+                let mut body = BTreeSet::from([header]);
+                for latch in &latches {
+                    // Unreachable predecessors can reach the latch too
+                    body.extend(
+                        self.reach(*latch, &self.preds, Some(header))
+                            .into_iter()
+                            .filter(|node| {
+                                self.idoms.contains_key(node)
+                                    && self.dominator_chain(*node).contains(&header)
+                            }),
+                    );
+                }
+                CfgLoop {
+                    header,
+                    latches,
+                    body,
+                }
+            })
+            .collect()
+    }
+
+    /// `sid` and its dominators up to the entry; `sid` must be reachable.
+    fn dominator_chain(&self, sid: isize) -> Vec<isize> {
+        let mut result = vec![sid];
+        let mut node = sid;
+        while self.idoms[&node] != node {
+            node = self.idoms[&node];
+            result.push(node);
+        }
+        result
+    }
+}
+
+fn join<'a>(ids: impl IntoIterator<Item = &'a isize>) -> String {
+    ids.into_iter()
+        .map(|id| id.to_string())
+        .collect::<Vec<_>>()
+        .join(",")
+}
@@ -30,12 +30,19 @@ SOFTWARE.
 */
 //! Definition of a struct/union.
 
+use std::fmt::Write;
+
 use pyo3::{intern, prelude::*};
 
 use crate::{
     app::{
-        c_attributes::CAttributes, c_declarations::CDeclarations,
-        c_dictionary_record::CDeclarationsRecord, c_field_info::CFieldInfo,
+        c_attributes::CAttributes,
+        c_data_model::{
+            data_model, CDataModel, CompLayout, FieldShape, LayoutAttributes, TypeLayout,
+        },
+        c_declarations::CDeclarations,
+        c_dictionary_record::CDeclarationsRecord,
+        c_field_info::CFieldInfo,
         c_global_declarations::CGlobalDeclarations,
     },
     util::indexed_table::IndexedTableValue,
@@ -44,6 +51,8 @@ use crate::{
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "c_comp_info")?;
     module.add_class::<CCompInfo>()?;
+    module.add_class::<CCompLayout>()?;
+    module.add_class::<CFieldLayout>()?;
     Ok(module)
 }
 
@@ -66,7 +75,7 @@ impl CCompInfo {
     }
 
     #[getter]
-    fn fields<'a, 'b>(slf: &'a Bound<'b, Self>) -> PyResult<Vec<Bound<'b, CFieldInfo>>> {
+    pub fn fields<'a, 'b>(slf: &'a Bound<'b, Self>) -> PyResult<Vec<Bound<'b, CFieldInfo>>> {
         let decls = slf.borrow().into_super().decls().bind(slf.py()).clone();
         slf.borrow().into_super().into_super().args()[3..]
             .into_iter()
@@ -122,15 +131,61 @@ impl CCompInfo {
         }
     }
 
-    // Unvalidated
+    /// Size in bytes in the current data model, including padding, or None
+    /// if a field has no size.
     #[getter]
-    pub fn size(slf: &Bound<Self>) -> PyResult<isize> {
-        Ok(CCompInfo::fields(slf)?
+    pub fn size(slf: &Bound<Self>) -> PyResult<Option<isize>> {
+        Ok(Self::layout(slf, &data_model())?.map(|layout| layout.size))
+    }
+
+    /// Alignment in bytes in the current data model, or None if a field has
+    /// no size.
+    #[getter]
+    fn alignment(slf: &Bound<Self>) -> PyResult<Option<isize>> {
+        Ok(Self::layout(slf, &data_model())?.map(|layout| layout.align))
+    }
+
+    /// Placement of the fields in `model` (default: the current data model),
+    /// or None if a field has no size.
+    #[pyo3(signature = (model=None))]
+    fn get_layout(slf: &Bound<Self>, model: Option<CDataModel>) -> PyResult<Option<CCompLayout>> {
+        let model = model.unwrap_or_else(data_model);
+        let Some(layout) = Self::comp_layout(slf, &model)? else {
+            return Ok(None);
+        };
+        let fields = Self::fields(slf)?
             .iter()
-            .map(|field| CFieldInfo::size(field))
-            .collect::<PyResult<Vec<isize>>>()?
-            .into_iter()
-            .sum())
+            .zip(&layout.fields)
+            .map(|(field, placement)| CFieldLayout {
+                fname: CFieldInfo::fname(field.borrow()),
+                offset: placement.offset(),
+                size: placement.extent(),
+                alignment: placement.align,
+                bit_offset: placement.bit_width.map(|_| placement.bit_offset),
+                bit_width: placement.bit_width,
+            })
+            .collect();
+        Ok(Some(CCompLayout {
+            name: Self::name(slf)?,
+            is_struct: Self::is_struct(slf.borrow()),
+            size: layout.size,
+            alignment: layout.align,
+            fields,
+        }))
+    }
+
+    /// Fields that (partly) occupy the byte at `offset`; several for unions
+    /// and bitfields that share a byte, none for padding.
+    #[pyo3(signature = (offset, model=None))]
+    fn fields_at_offset(
+        slf: &Bound<Self>,
+        offset: isize,
+        model: Option<CDataModel>,
+    ) -> PyResult<Vec<CFieldLayout>> {
+        Ok(match Self::get_layout(slf, model)? {
+            Some(layout) => layout.iter_fields_at(offset).cloned().collect(),
+            None => vec![],
+        })
     }
 
     #[getter]
@@ -164,11 +219,156 @@ impl CCompInfo {
     fn str(slf: &Bound<Self>) -> PyResult<String> {
         let mut lines = Vec::new();
         lines.push(format!("struct {}", CCompInfo::name(slf)?));
-        let mut offset = 0;
-        for f in CCompInfo::fields(slf)? {
+        let placements = match Self::comp_layout(slf, &data_model())? {
+            Some(layout) => layout.fields,
+            None => vec![],
+        };
+        for (i, f) in CCompInfo::fields(slf)?.iter().enumerate() {
+            let offset = match placements.get(i) {
+                Some(placement) => placement.offset().to_string(),
+                None => "?".to_string(),
+            };
             lines.push(format!("{}{offset:>4} {}", " ".repeat(5), f.str()?));
-            offset += CFieldInfo::size(&f)?;
         }
         Ok(lines.join(""))
     }
 }
+
+impl CCompInfo {
+    /// Placement of the fields in `model`, in declaration order.
+    pub fn comp_layout(slf: &Bound<Self>, model: &CDataModel) -> PyResult<Option<CompLayout>> {
+        let attributes = Self::layout_attributes(slf, model)?;
+        let shapes = Self::fields(slf)?
+            .iter()
+            .map(|field| CFieldInfo::shape(field, model, attributes.packed))
+            .collect::<PyResult<Vec<FieldShape>>>()?;
+        Ok(model.comp_layout(Self::is_struct(slf.borrow()), &shapes, attributes.aligned))
+    }
+
+    /// The `packed` and `aligned` attributes of the struct or union.
+    fn layout_attributes(slf: &Bound<Self>, model: &CDataModel) -> PyResult<LayoutAttributes> {
+        if slf.borrow().into_super().into_super().args()[2] < 0 {
+            return Ok(LayoutAttributes::default());
+        }
+        Self::cattr(slf)?.get().layout_attributes(slf.py(), model)
+    }
+
+    /// Size and alignment in `model`.
+    pub fn layout(slf: &Bound<Self>, model: &CDataModel) -> PyResult<Option<TypeLayout>> {
+        Ok(Self::comp_layout(slf, model)?.map(|layout| layout.type_layout()))
+    }
+}
+
+/// Placement of a field in a struct or union (offsets and sizes in bytes).
+///
+/// For bitfields, `size` is the number of bytes the bits span and
+/// `bit_offset` is counted from the start of the struct.
+#[pyclass(frozen, get_all)]
+#[derive(Clone)]
+pub struct CFieldLayout {
+    fname: String,
+    offset: isize,
+    size: isize,
+    alignment: isize,
+    bit_offset: Option<isize>,
+    bit_width: Option<isize>,
+}
+
+#[pymethods]
+impl CFieldLayout {
+    /// True if the field (partly) occupies the byte at `offset`.
+    fn contains(&self, offset: isize) -> bool {
+        self.offset <= offset && offset < self.offset + self.size
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self) -> String {
+        match (self.bit_offset, self.bit_width) {
+            (Some(bit_offset), Some(width)) => format!(
+                "{:>6} {:>4} {} (bits {}..{})",
+                self.offset,
+                self.size,
+                self.fname,
+                bit_offset,
+                bit_offset + width
+            ),
+            _ => format!("{:>6} {:>4} {}", self.offset, self.size, self.fname),
+        }
+    }
+}
+
+/// Layout of a struct or union in a data model.
+#[pyclass(frozen, get_all)]
+pub struct CCompLayout {
+    name: String,
+    is_struct: bool,
+    size: isize,
+    alignment: isize,
+    fields: Vec<CFieldLayout>,
+}
+
+#[pymethods]
+impl CCompLayout {
+    /// Bytes after the last field.
+    #[getter]
+    fn trailing_padding(&self) -> isize {
+        self.size - self.end()
+    }
+
+    /// (offset, size) of the bytes that are not occupied by any field,
+    /// including the trailing padding.
+    fn padding(&self) -> Vec<(isize, isize)> {
+        let mut result = Vec::new();
+        let mut end = 0;
+        let mut fields = self.fields.iter().collect::<Vec<_>>();
+        fields.sort_by_key(|f| f.offset);
+        for field in fields {
+            if field.offset > end {
+                result.push((end, field.offset - end));
+            }
+            end = end.max(field.offset + field.size);
+        }
+        if self.size > end {
+            result.push((end, self.size - end));
+        }
+        result
+    }
+
+    /// Fields that (partly) occupy the byte at `offset`.
+    fn fields_at(&self, offset: isize) -> Vec<CFieldLayout> {
+        self.iter_fields_at(offset).cloned().collect()
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self) -> String {
+        let kind = if self.is_struct { "struct" } else { "union" };
+        let mut out = format!(
+            "{kind} {} (size: {}, alignment: {})",
+            self.name, self.size, self.alignment
+        );
+        for field in &self.fields {
+            let _ = write!(out, "\n{}", field.str());
+        }
+        let padding = self.trailing_padding();
+        if padding > 0 {
+            let _ = write!(out, "\n{:>6} {:>4} (padding)", self.end(), padding);
+        }
+        out
+    }
+}
+
+impl CCompLayout {
+    fn end(&self) -> isize {
+        self.fields
+            .iter()
+            .map(|field| field.offset + field.size)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn iter_fields_at(&self, offset: isize) -> impl Iterator<Item = &CFieldLayout> {
+        self.fields
+            .iter()
+            .filter(move |field| field.contains(offset))
+    }
+}
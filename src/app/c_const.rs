@@ -34,7 +34,9 @@ use pyo3::{intern, prelude::*};
 use crate::{
     app::{
         c_dictionary::CDictionary,
-        c_dictionary_record::{CDictionaryRecord, CDictionaryRegistryEntry},
+        c_dictionary_record::{
+            CDictionaryRecord, CDictionaryRecordTrait, CDictionaryRegistryEntry,
+        },
     },
     util::indexed_table::IndexedTableValue,
 };
@@ -123,8 +125,10 @@ impl CConstInt {
         PyClassInitializer::from(CConst::new(cd, ixval)).add_subclass(CConstInt {})
     }
 
+    /// Value of the constant; unsigned long long constants do not fit in
+    /// isize.
     #[getter]
-    pub fn intvalue(slf: PyRef<Self>) -> PyResult<isize> {
+    pub fn intvalue(slf: PyRef<Self>) -> PyResult<i128> {
         Ok(slf.into_super().into_super().into_super().tags()[1].parse()?)
     }
 
@@ -145,7 +149,16 @@ impl CConstInt {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CConst, CConstInt>("int") }
+impl CDictionaryRecordTrait for CConstInt {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CConst, CConstInt>("int") }
 
 /// Constant string
 ///
@@ -190,7 +203,16 @@ impl CConstStr {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CConst, CConstStr>("str") }
+impl CDictionaryRecordTrait for CConstStr {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CConst, CConstStr>("str") }
 
 /// Constant wide string (represented as a sequence of int64 integers)
 ///
@@ -223,7 +245,16 @@ impl CConstWStr {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CConst, CConstWStr>("wstr") }
+impl CDictionaryRecordTrait for CConstWStr {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CConst, CConstWStr>("wstr") }
 
 /// Constant character.
 ///
@@ -254,7 +285,16 @@ impl CConstChr {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CConst, CConstChr>("chr") }
+impl CDictionaryRecordTrait for CConstChr {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CConst, CConstChr>("chr") }
 
 /// Constant real number.
 ///
@@ -291,7 +331,16 @@ impl CConstReal {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CConst, CConstReal>("real") }
+impl CDictionaryRecordTrait for CConstReal {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CConst, CConstReal>("real") }
 
 /// Constant enumeration value.
 ///
@@ -340,7 +389,16 @@ impl CConstEnum {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CConst, CConstEnum>("enum") }
+impl CDictionaryRecordTrait for CConstEnum {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CConst, CConstEnum>("enum") }
 
 // Seems unused
 /// Constant string value
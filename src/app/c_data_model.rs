@@ -0,0 +1,543 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Sizes and alignments of the C base types on the analysis target.
+
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
+    let module = PyModule::new_bound(py, "c_data_model")?;
+    module.add_class::<CDataModel>()?;
+    module.add_function(wrap_pyfunction!(data_model, &module)?)?;
+    module.add_function(wrap_pyfunction!(set_data_model, &module)?)?;
+    Ok(module)
+}
+
+/// Size and alignment (in bytes) of the base types of a target.
+///
+/// Scalars are aligned to their size, up to `max_align`. Unions and structs
+/// are laid out the way gcc does, or the way msvc does if `ms_bitfields` is
+/// set (bitfields then only share a storage unit with bitfields of the same
+/// size).
+#[pyclass(frozen, get_all)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CDataModel {
+    name: String,
+    short_size: isize,
+    int_size: isize,
+    long_size: isize,
+    long_long_size: isize,
+    pointer_size: isize,
+    float_size: isize,
+    double_size: isize,
+    long_double_size: isize,
+    va_list_size: isize,
+    max_align: isize,
+    ms_bitfields: bool,
+}
+
+#[pymethods]
+impl CDataModel {
+    /// Custom data model; the defaults are those of ILP32.
+    #[new]
+    #[pyo3(signature = (name = "custom", short_size = 2, int_size = 4, long_size = 4, long_long_size = 8, pointer_size = 4, float_size = 4, double_size = 8, long_double_size = 12, va_list_size = 4, max_align = 4, ms_bitfields = false))]
+    #[allow(clippy::too_many_arguments)]
+    fn new(
+        name: &str,
+        short_size: isize,
+        int_size: isize,
+        long_size: isize,
+        long_long_size: isize,
+        pointer_size: isize,
+        float_size: isize,
+        double_size: isize,
+        long_double_size: isize,
+        va_list_size: isize,
+        max_align: isize,
+        ms_bitfields: bool,
+    ) -> PyResult<CDataModel> {
+        let sizes = [
+            short_size,
+            int_size,
+            long_size,
+            long_long_size,
+            pointer_size,
+            float_size,
+            double_size,
+            long_double_size,
+            va_list_size,
+            max_align,
+        ];
+        if sizes.iter().any(|size| *size <= 0) {
+            return Err(PyValueError::new_err(format!(
+                "Data model {name}: sizes must be positive"
+            )));
+        }
+        Ok(CDataModel {
+            name: name.to_string(),
+            short_size,
+            int_size,
+            long_size,
+            long_long_size,
+            pointer_size,
+            float_size,
+            double_size,
+            long_double_size,
+            va_list_size,
+            max_align,
+            ms_bitfields,
+        })
+    }
+
+    /// 32-bit unix (i386 System V).
+    #[staticmethod]
+    pub fn ilp32() -> CDataModel {
+        CDataModel {
+            name: "ILP32".to_string(),
+            short_size: 2,
+            int_size: 4,
+            long_size: 4,
+            long_long_size: 8,
+            pointer_size: 4,
+            float_size: 4,
+            double_size: 8,
+            long_double_size: 12,
+            va_list_size: 4,
+            max_align: 4,
+            ms_bitfields: false,
+        }
+    }
+
+    /// 64-bit unix (x86-64 System V).
+    #[staticmethod]
+    pub fn lp64() -> CDataModel {
+        CDataModel {
+            name: "LP64".to_string(),
+            long_size: 8,
+            pointer_size: 8,
+            long_double_size: 16,
+            va_list_size: 24,
+            max_align: 16,
+            ..CDataModel::ilp32()
+        }
+    }
+
+    /// 64-bit windows.
+    #[staticmethod]
+    pub fn llp64() -> CDataModel {
+        CDataModel {
+            name: "LLP64".to_string(),
+            pointer_size: 8,
+            long_double_size: 8,
+            va_list_size: 8,
+            max_align: 8,
+            ms_bitfields: true,
+            ..CDataModel::ilp32()
+        }
+    }
+
+    /// 16-bit target with 32-bit longs.
+    #[staticmethod]
+    pub fn ip16() -> CDataModel {
+        CDataModel {
+            name: "IP16".to_string(),
+            int_size: 2,
+            pointer_size: 2,
+            long_double_size: 8,
+            va_list_size: 2,
+            max_align: 2,
+            ..CDataModel::ilp32()
+        }
+    }
+
+    /// Data model for the wordsize given to the analyzer (0 is unspecified,
+    /// which the analyzer treats as 32), or None if there is no predefined
+    /// model for it.
+    #[staticmethod]
+    pub fn for_wordsize(wordsize: isize) -> Option<CDataModel> {
+        match wordsize {
+            0 | 32 => Some(CDataModel::ilp32()),
+            64 => Some(CDataModel::lp64()),
+            16 => Some(CDataModel::ip16()),
+            _ => None,
+        }
+    }
+
+    /// Size of an integer kind (`ichar`, `iuint`, ...), or None if the kind
+    /// is not known.
+    pub fn ikind_size(&self, ikind: &str) -> Option<isize> {
+        match ikind {
+            "ichar" | "ischar" | "iuchar" | "ibool" => Some(1),
+            "ishort" | "iushort" => Some(self.short_size),
+            "iint" | "iuint" => Some(self.int_size),
+            "ilong" | "iulong" => Some(self.long_size),
+            "ilonglong" | "iulonglong" => Some(self.long_long_size),
+            "iint128" | "iuint128" => Some(16),
+            _ => None,
+        }
+    }
+
+    /// Size of a float kind (`ffloat`, `fdouble`, `flongdouble`), or None
+    /// if the kind is not known.
+    pub fn fkind_size(&self, fkind: &str) -> Option<isize> {
+        match fkind {
+            "ffloat" | "float" => Some(self.float_size),
+            "fdouble" => Some(self.double_size),
+            "flongdouble" => Some(self.long_double_size),
+            "fcomplexfloat" => Some(2 * self.float_size),
+            "fcomplexdouble" => Some(2 * self.double_size),
+            "fcomplexlongdouble" => Some(2 * self.long_double_size),
+            _ => None,
+        }
+    }
+
+    /// Alignment of a scalar of the given size.
+    pub fn scalar_align(&self, size: isize) -> isize {
+        size.clamp(1, self.max_align)
+    }
+
+    /// Largest alignment of any type; also the alignment that `aligned`
+    /// without argument asks for.
+    pub fn largest_align(&self) -> isize {
+        self.max_align
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self) -> String {
+        format!(
+            "{}: short {}, int {}, long {}, long long {}, pointer {}, \
+             float {}, double {}, long double {}",
+            self.name,
+            self.short_size,
+            self.int_size,
+            self.long_size,
+            self.long_long_size,
+            self.pointer_size,
+            self.float_size,
+            self.double_size,
+            self.long_double_size
+        )
+    }
+}
+
+impl CDataModel {
+    pub fn pointer_layout(&self) -> TypeLayout {
+        self.scalar_layout(self.pointer_size)
+    }
+
+    pub fn int_layout(&self) -> TypeLayout {
+        self.scalar_layout(self.int_size)
+    }
+
+    pub fn va_list_layout(&self) -> TypeLayout {
+        TypeLayout {
+            size: self.va_list_size,
+            align: self.va_list_size.clamp(1, self.pointer_size),
+        }
+    }
+
+    pub fn ikind_layout(&self, ikind: &str) -> Option<TypeLayout> {
+        self.ikind_size(ikind).map(|size| self.scalar_layout(size))
+    }
+
+    pub fn fkind_layout(&self, fkind: &str) -> Option<TypeLayout> {
+        // complex types are aligned as their components
+        let component = match fkind {
+            "fcomplexfloat" => "ffloat",
+            "fcomplexdouble" => "fdouble",
+            "fcomplexlongdouble" => "flongdouble",
+            _ => fkind,
+        };
+        Some(TypeLayout {
+            size: self.fkind_size(fkind)?,
+            align: self.scalar_align(self.fkind_size(component)?),
+        })
+    }
+
+    fn scalar_layout(&self, size: isize) -> TypeLayout {
+        TypeLayout {
+            size,
+            align: self.scalar_align(size),
+        }
+    }
+
+    /// Layout of a struct or union with the given fields, in declaration
+    /// order, and the alignment requested for the struct or union itself.
+    /// Returns None if any field has no size, except for a flexible array
+    /// member at the end of a struct, or if the size in bits overflows.
+    pub fn comp_layout(
+        &self,
+        is_struct: bool,
+        fields: &[FieldShape],
+        aligned: Option<isize>,
+    ) -> Option<CompLayout> {
+        let mut bits = 0;
+        let mut align = aligned.unwrap_or(1).max(1);
+        let mut unit: Option<BitfieldUnit> = None;
+        let mut placements = Vec::with_capacity(fields.len());
+        for (i, field) in fields.iter().enumerate() {
+            let layout = match field.layout {
+                Some(layout) => layout,
+                None if is_struct && i + 1 == fields.len() => TypeLayout {
+                    size: 0,
+                    align: field.flexible_align?,
+                },
+                None => return None,
+            };
+            let layout = field.attributes.field_layout(layout);
+            let size_bits = layout.size.checked_mul(8)?;
+            let align_bits = layout.align.checked_mul(8)?;
+            let width = field.bitfield;
+            if !is_struct {
+                let size_bits = if width > 0 {
+                    round_up(width, align_bits)?
+                } else {
+                    size_bits
+                };
+                bits = bits.max(size_bits);
+                if width != 0 {
+                    align = align.max(layout.align);
+                }
+                placements.push(FieldPlacement::new(0, &layout, width));
+                continue;
+            }
+            let start = if width < 0 {
+                if let Some(u) = unit.take() {
+                    bits = u.end()?;
+                }
+                let start = round_up(bits, align_bits)?;
+                bits = start.checked_add(size_bits)?;
+                align = align.max(layout.align);
+                start
+            } else if width == 0 {
+                if self.ms_bitfields {
+                    if let Some(u) = unit.take() {
+                        bits = u.end()?;
+                    }
+                } else {
+                    bits = round_up(bits, align_bits)?;
+                }
+                bits
+            } else if self.ms_bitfields {
+                align = align.max(layout.align);
+                match unit.as_mut() {
+                    Some(u) if u.bits == size_bits && width <= u.bits - u.used => {
+                        u.used += width;
+                        u.start + u.used - width
+                    }
+                    _ => {
+                        if let Some(u) = unit.take() {
+                            bits = u.end()?;
+                        }
+                        let start = round_up(bits, align_bits)?;
+                        unit = Some(BitfieldUnit {
+                            start,
+                            bits: size_bits,
+                            used: width,
+                        });
+                        start
+                    }
+                }
+            } else if field.attributes.packed {
+                // packed bitfields are not kept within a unit of their type
+                bits = bits.checked_add(width)?;
+                bits - width
+            } else {
+                // a bitfield does not straddle an aligned unit of its type
+                let unit_start = bits - bits.rem_euclid(align_bits);
+                if bits.checked_add(width)? > unit_start.checked_add(size_bits)? {
+                    bits = round_up(bits, align_bits)?;
+                }
+                bits = bits.checked_add(width)?;
+                align = align.max(layout.align);
+                bits - width
+            };
+            placements.push(FieldPlacement::new(start, &layout, width));
+        }
+        if let Some(u) = unit {
+            bits = u.end()?;
+        }
+        Some(CompLayout {
+            size: round_up(round_up(bits, 8)? / 8, align)?,
+            align,
+            fields: placements,
+        })
+    }
+}
+
+/// Size and alignment of a type, in bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TypeLayout {
+    pub size: isize,
+    pub align: isize,
+}
+
+/// What the struct layout needs to know about a field.
+#[derive(Clone, Copy, Debug)]
+pub struct FieldShape {
+    /// None if the type has no size.
+    pub layout: Option<TypeLayout>,
+    /// Width in bits, 0 for an unnamed zero-width bitfield, -1 if the field
+    /// is not a bitfield.
+    pub bitfield: isize,
+    /// Element alignment if the field is an array without length.
+    pub flexible_align: Option<isize>,
+    /// `packed` and `aligned` of the field, with `packed` also set if the
+    /// struct is packed.
+    pub attributes: LayoutAttributes,
+}
+
+/// The attributes that change the layout of a type, struct or field.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LayoutAttributes {
+    pub packed: bool,
+    /// Largest alignment requested with `aligned`.
+    pub aligned: Option<isize>,
+}
+
+impl LayoutAttributes {
+    /// Adds the alignment requested by an `aligned` attribute.
+    pub fn add_aligned(&mut self, align: isize) {
+        self.aligned = Some(self.aligned.map_or(align, |a| a.max(align)));
+    }
+
+    /// Layout of a type with these attributes: `aligned` can only increase
+    /// the alignment.
+    pub fn type_layout(&self, layout: TypeLayout) -> TypeLayout {
+        TypeLayout {
+            size: layout.size,
+            align: layout.align.max(self.aligned.unwrap_or(1)),
+        }
+    }
+
+    /// Layout of a field with these attributes: `packed` drops the alignment
+    /// to one byte, `aligned` raises it again.
+    pub fn field_layout(&self, layout: TypeLayout) -> TypeLayout {
+        let align = if self.packed { 1 } else { layout.align };
+        TypeLayout {
+            size: layout.size,
+            align: align.max(self.aligned.unwrap_or(1)),
+        }
+    }
+}
+
+/// Layout of a struct or union.
+#[derive(Clone, Debug)]
+pub struct CompLayout {
+    pub size: isize,
+    pub align: isize,
+    /// In the order of the fields given to `comp_layout`.
+    pub fields: Vec<FieldPlacement>,
+}
+
+impl CompLayout {
+    pub fn type_layout(&self) -> TypeLayout {
+        TypeLayout {
+            size: self.size,
+            align: self.align,
+        }
+    }
+}
+
+/// Where a field is placed in a struct or union.
+#[derive(Clone, Copy, Debug)]
+pub struct FieldPlacement {
+    /// Offset in bits from the start of the struct.
+    pub bit_offset: isize,
+    /// Size of the field type (bytes).
+    pub size: isize,
+    pub align: isize,
+    /// Width in bits for bitfields.
+    pub bit_width: Option<isize>,
+}
+
+impl FieldPlacement {
+    fn new(bit_offset: isize, layout: &TypeLayout, bitfield: isize) -> FieldPlacement {
+        FieldPlacement {
+            bit_offset,
+            size: layout.size,
+            align: layout.align,
+            bit_width: (bitfield >= 0).then_some(bitfield),
+        }
+    }
+
+    /// Offset of the first byte of the field.
+    pub fn offset(&self) -> isize {
+        self.bit_offset.div_euclid(8)
+    }
+
+    /// Number of bytes the field occupies (part of) in the struct.
+    pub fn extent(&self) -> isize {
+        match self.bit_width {
+            // as (bit_offset % 8 + width + 7) / 8, without overflow
+            Some(width) => width / 8 + (self.bit_offset.rem_euclid(8) + width % 8 + 7) / 8,
+            None => self.size,
+        }
+    }
+}
+
+/// Storage unit that msvc packs consecutive bitfields into; all in bits.
+struct BitfieldUnit {
+    start: isize,
+    bits: isize,
+    used: isize,
+}
+
+impl BitfieldUnit {
+    fn end(&self) -> Option<isize> {
+        self.start.checked_add(self.bits)
+    }
+}
+
+/// Smallest multiple of `multiple` that is at least `n`, or None on overflow.
+fn round_up(n: isize, multiple: isize) -> Option<isize> {
+    Some(n.checked_add(multiple - 1)?.div_euclid(multiple) * multiple)
+}
+
+static DATA_MODEL: Lazy<RwLock<CDataModel>> = Lazy::new(|| RwLock::new(CDataModel::ilp32()));
+
+/// Data model that type sizes are computed in (ILP32 unless set otherwise).
+#[pyfunction]
+pub fn data_model() -> CDataModel {
+    DATA_MODEL
+        .read()
+        .map_or_else(|err| err.into_inner().clone(), |model| model.clone())
+}
+
+#[pyfunction]
+pub fn set_data_model(model: CDataModel) {
+    match DATA_MODEL.write() {
+        Ok(mut current) => *current = model,
+        Err(err) => *err.into_inner() = model,
+    }
+}
@@ -28,17 +28,24 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 ------------------------------------------------------------------------------
 */
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 
-use pyo3::{prelude::*, type_object::PyTypeInfo, types::PyType};
+use pyo3::{prelude::*, type_object::PyTypeInfo};
 
 use crate::{
     app::{
         c_attributes::{CAttr, CAttribute, CAttributes},
         c_const::CConst,
         c_dictionary_record::{cdregistry, CDictionaryRecordTrait},
+        c_dictionary_schema::{cdictionary_table_schema, StructuralKeys},
+        c_dictionary_view::CDictionaryView,
+    },
+    util::{
+        dictionary_xml::{self, XmlTables},
+        indexed_table::PyIndexedTable,
+        table_check::IntegrityReport,
+        table_diff::TableDiff,
     },
-    util::indexed_table::IndexedTable,
 };
 
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
@@ -55,19 +62,19 @@ pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
 /// - CGlobalDictionary: constructed in the python api
 #[pyclass(get_all, subclass)]
 pub struct CDictionary {
-    attrparam_table: Py<IndexedTable>,
-    attribute_table: Py<IndexedTable>,
-    attributes_table: Py<IndexedTable>,
-    constant_table: Py<IndexedTable>,
-    exp_table: Py<IndexedTable>,
-    funarg_table: Py<IndexedTable>,
-    funargs_table: Py<IndexedTable>,
-    lhost_table: Py<IndexedTable>,
-    lval_table: Py<IndexedTable>,
-    offset_table: Py<IndexedTable>,
-    typ_table: Py<IndexedTable>,
-    typsig_table: Py<IndexedTable>,
-    typsiglist_table: Py<IndexedTable>,
+    attrparam_table: Py<PyIndexedTable>,
+    attribute_table: Py<PyIndexedTable>,
+    attributes_table: Py<PyIndexedTable>,
+    constant_table: Py<PyIndexedTable>,
+    exp_table: Py<PyIndexedTable>,
+    funarg_table: Py<PyIndexedTable>,
+    funargs_table: Py<PyIndexedTable>,
+    lhost_table: Py<PyIndexedTable>,
+    lval_table: Py<PyIndexedTable>,
+    offset_table: Py<PyIndexedTable>,
+    typ_table: Py<PyIndexedTable>,
+    typsig_table: Py<PyIndexedTable>,
+    typsiglist_table: Py<PyIndexedTable>,
 }
 
 #[pymethods]
@@ -75,24 +82,24 @@ impl CDictionary {
     #[new]
     fn new(py: Python) -> PyResult<CDictionary> {
         Ok(CDictionary {
-            attrparam_table: Py::new(py, IndexedTable::new("attrparam-table".to_string()))?,
-            attribute_table: Py::new(py, IndexedTable::new("attribute-table".to_string()))?,
-            attributes_table: Py::new(py, IndexedTable::new("attributes-table".to_string()))?,
-            constant_table: Py::new(py, IndexedTable::new("constant-table".to_string()))?,
-            exp_table: Py::new(py, IndexedTable::new("exp-table".to_string()))?,
-            funarg_table: Py::new(py, IndexedTable::new("funarg-table".to_string()))?,
-            funargs_table: Py::new(py, IndexedTable::new("funargs-table".to_string()))?,
-            lhost_table: Py::new(py, IndexedTable::new("lhost-table".to_string()))?,
-            lval_table: Py::new(py, IndexedTable::new("lval-table".to_string()))?,
-            offset_table: Py::new(py, IndexedTable::new("offset-table".to_string()))?,
-            typ_table: Py::new(py, IndexedTable::new("typ-table".to_string()))?,
-            typsig_table: Py::new(py, IndexedTable::new("typsig-table".to_string()))?,
-            typsiglist_table: Py::new(py, IndexedTable::new("typsiglist-table".to_string()))?,
+            attrparam_table: Py::new(py, PyIndexedTable::new("attrparam-table".to_string()))?,
+            attribute_table: Py::new(py, PyIndexedTable::new("attribute-table".to_string()))?,
+            attributes_table: Py::new(py, PyIndexedTable::new("attributes-table".to_string()))?,
+            constant_table: Py::new(py, PyIndexedTable::new("constant-table".to_string()))?,
+            exp_table: Py::new(py, PyIndexedTable::new("exp-table".to_string()))?,
+            funarg_table: Py::new(py, PyIndexedTable::new("funarg-table".to_string()))?,
+            funargs_table: Py::new(py, PyIndexedTable::new("funargs-table".to_string()))?,
+            lhost_table: Py::new(py, PyIndexedTable::new("lhost-table".to_string()))?,
+            lval_table: Py::new(py, PyIndexedTable::new("lval-table".to_string()))?,
+            offset_table: Py::new(py, PyIndexedTable::new("offset-table".to_string()))?,
+            typ_table: Py::new(py, PyIndexedTable::new("typ-table".to_string()))?,
+            typsig_table: Py::new(py, PyIndexedTable::new("typsig-table".to_string()))?,
+            typsiglist_table: Py::new(py, PyIndexedTable::new("typsiglist-table".to_string()))?,
         })
     }
 
     #[getter]
-    fn tables(&self, py: Python) -> Vec<Py<IndexedTable>> {
+    fn tables(&self, py: Python) -> Vec<Py<PyIndexedTable>> {
         vec![
             self.attrparam_table.clone_ref(py),
             self.attribute_table.clone_ref(py),
@@ -110,6 +117,125 @@ impl CDictionary {
         ]
     }
 
+    /// Fills all tables from tables read by `read_xml_tables`.
+    ///
+    /// The string table is kept on the python side and is not included.
+    fn read_xml_tables(&self, py: Python, tables: &XmlTables) -> PyResult<()> {
+        for table in self.tables(py) {
+            table.borrow_mut(py).read_xml_tables(py, tables)?;
+        }
+        Ok(())
+    }
+
+    /// Renders the dictionary as element `tag` in the layout produced by
+    /// `xmlutil.doc_to_pretty`, i.e., in the format read by the canalyzer.
+    ///
+    /// The string table is kept on the python side; its (index, string)
+    /// entries are passed in as `strings`, in index order.
+    #[pyo3(signature = (tag, strings, indent=0))]
+    fn write_xml_text(
+        &self,
+        py: Python,
+        tag: &str,
+        strings: Vec<(isize, String)>,
+        indent: usize,
+    ) -> String {
+        let mut out = String::new();
+        out.push_str(&format!("{:indent$}<{tag}>\n", ""));
+        for table in self.tables(py) {
+            let table = table.borrow(py);
+            dictionary_xml::write_table(
+                &mut out,
+                table.table().name(),
+                table.table().values().map(|v| v.get()),
+                indent + 2,
+            );
+        }
+        dictionary_xml::write_string_table(&mut out, "string-table", &strings, indent + 2);
+        out.push_str(&format!("{:indent$}</{tag}>\n", ""));
+        out
+    }
+
+    /// Checks all tables for duplicate keys, gaps, uncommitted reserved
+    /// indices, args that refer to missing entries, records with too few args,
+    /// and unregistered tags.
+    ///
+    /// `strings` are the indices in the python-side string table; if absent,
+    /// references to the string table are not checked.
+    #[pyo3(signature = (strings=None))]
+    fn check(&self, py: Python, strings: Option<BTreeSet<isize>>) -> PyResult<IntegrityReport> {
+        let registered = cdregistry(py)?.registered_tags(py)?;
+        let tables = self.tables(py);
+        let tables = tables.iter().map(|t| t.borrow(py)).collect::<Vec<_>>();
+        let exists = |name: &str, ix: isize| {
+            if name == "string-table" {
+                return strings.as_ref().map(|s| s.contains(&ix));
+            }
+            tables
+                .iter()
+                .find(|t| t.table().name() == name)
+                .map(|t| t.table().get(ix).is_some())
+        };
+        let mut report = IntegrityReport::default();
+        for table in tables.iter() {
+            table.table().check(&mut report);
+            if let Some(schema) = cdictionary_table_schema(table.table().name()) {
+                schema.check(
+                    table.table(),
+                    exists,
+                    |anchor, tag| registered.contains(&(anchor.to_string(), tag.to_string())),
+                    &mut report,
+                );
+            }
+        }
+        Ok(report)
+    }
+
+    /// Entries added, removed, and re-indexed in `other` relative to this
+    /// dictionary, per table.
+    ///
+    /// Entries are matched by structural key: args that refer to other
+    /// entries are compared by what they refer to rather than by index.
+    /// `strings` and `other_strings` are the python-side string tables; if
+    /// absent, string indices are compared as they are.
+    #[pyo3(signature = (other, strings=None, other_strings=None))]
+    fn diff(
+        &self,
+        py: Python,
+        other: &Bound<CDictionary>,
+        strings: Option<BTreeMap<isize, String>>,
+        other_strings: Option<BTreeMap<isize, String>>,
+    ) -> Vec<TableDiff> {
+        let old_tables = self.tables(py);
+        let old_tables = old_tables.iter().map(|t| t.borrow(py)).collect::<Vec<_>>();
+        let new_tables = other.borrow().tables(py);
+        let new_tables = new_tables.iter().map(|t| t.borrow(py)).collect::<Vec<_>>();
+        let mut old_keys =
+            StructuralKeys::new(old_tables.iter().map(|t| t.table()), strings.as_ref());
+        let mut new_keys =
+            StructuralKeys::new(new_tables.iter().map(|t| t.table()), other_strings.as_ref());
+        old_tables
+            .iter()
+            .map(|t| {
+                let name = t.table().name();
+                TableDiff::new(name, old_keys.keys(name), new_keys.keys(name))
+            })
+            .collect()
+    }
+
+    /// Read-only snapshot of the tables that can be used without the GIL.
+    ///
+    /// `strings` are the entries of the python-side string table.
+    #[pyo3(signature = (strings=None))]
+    fn view(&self, py: Python, strings: Option<BTreeMap<isize, String>>) -> CDictionaryView {
+        let tables = self
+            .tables(py)
+            .iter()
+            .map(|t| t.borrow(py).table().map_values(|v| v.get().clone()))
+            .collect();
+        CDictionaryView::new(tables, strings)
+    }
+
     // -------------- Retrieve items from dictionary tables -------------------
 
     pub fn get_attrparam<'a>(slf: &Bound<'a, Self>, ix: isize) -> PyResult<Bound<'a, CAttr>> {
@@ -155,30 +281,27 @@ impl CDictionary {
 impl CDictionary {
     fn dict_to_registry<'a, T: PyTypeInfo>(
         slf: &Bound<'a, Self>,
-        dict: &Py<IndexedTable>,
+        dict: &Py<PyIndexedTable>,
         ix: isize,
     ) -> PyResult<Bound<'a, T>> {
         let py = slf.py();
         let ixval = dict.borrow(py).retrieve_bound(py, ix)?;
-        Ok(cdregistry(py)?
-            .mk_instance(slf, &ixval, &PyType::new_bound::<T>(py))?
-            .downcast()?
-            .clone())
+        cdregistry(py)?.mk_typed_instance::<T>(slf, &ixval)
     }
 
     fn dict_to_constructor<'a, T: CDictionaryRecordTrait>(
         slf: &Bound<'a, Self>,
-        dict: &Py<IndexedTable>,
+        dict: &Py<PyIndexedTable>,
         ix: isize,
     ) -> PyResult<Bound<'a, T>> {
         let py = slf.py();
         let ixval = dict.borrow(py).retrieve_bound(py, ix)?;
-        Bound::new(slf.py(), T::new(slf, ixval.borrow().clone()))
+        Bound::new(slf.py(), T::new(slf, ixval.borrow().clone())?)
     }
 
     fn object_map<'a, T, F>(
         slf: &Bound<'a, Self>,
-        dict: &Py<IndexedTable>,
+        dict: &Py<PyIndexedTable>,
         func: F,
     ) -> PyResult<BTreeMap<isize, Bound<'a, T>>>
     where
@@ -28,9 +28,13 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 ------------------------------------------------------------------------------
 */
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    collections::{BTreeSet, HashMap},
+    fmt,
+};
 
-use once_cell::sync::OnceCell;
+use once_cell::sync::{Lazy, OnceCell};
 use pyo3::{
     intern,
     prelude::*,
@@ -40,11 +44,16 @@ use pyo3::{
 };
 
 use crate::{
-    app::{c_declarations::CDeclarations, c_dictionary::CDictionary},
+    app::{
+        c_attributes::CAttr, c_const::CConst, c_declarations::CDeclarations,
+        c_dictionary::CDictionary, c_dictionary_schema::cdictionary_anchor_table, c_exp::CExp,
+        c_lval::CLval, c_offset::COffset, c_typ::CTyp,
+    },
     util::indexed_table::{inherit_indexed_table_value_trait, IndexedTableValue},
 };
 
 pyo3::import_exception!(chc.util.fileutil, CHCError);
+pyo3::import_exception!(chc.util.fileutil, CHCUnknownTagError);
 
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "c_dictionary_record")?;
@@ -58,7 +67,9 @@ pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
 }
 
 pub trait CDictionaryRecordTrait: PyClass {
-    fn new(cd: &Bound<CDictionary>, ixval: IndexedTableValue) -> PyClassInitializer<Self>;
+    /// Fails if `ixval` does not have the tags and args of the record type.
+    fn new(cd: &Bound<CDictionary>, ixval: IndexedTableValue)
+        -> PyResult<PyClassInitializer<Self>>;
 }
 
 /// Base class for all objects kept in the CDictionary
@@ -128,19 +139,83 @@ impl CDeclarationsRecord {
 
 #[pyclass]
 pub struct CDictionaryRegistry {
+    /// Record types that are only implemented in python, keyed by
+    /// (anchor, tag).
     #[pyo3(get)]
     register: Py<PyDict>,
-    // TODO figure out how to use `anchor` as a key
-    rust_register: Vec<CDictionaryRegistryEntry>,
 }
 
 impl CDictionaryRegistry {
-    pub fn new(py: Python, rust_register: Vec<CDictionaryRegistryEntry>) -> CDictionaryRegistry {
+    pub fn new(py: Python) -> CDictionaryRegistry {
         CDictionaryRegistry {
             register: PyDict::new_bound(py).unbind(),
-            rust_register,
         }
     }
+
+    /// (anchor class name, tag) of all registered record types, from both
+    /// rust and python.
+    pub fn registered_tags(&self, py: Python) -> PyResult<BTreeSet<(String, String)>> {
+        let mut result: BTreeSet<(String, String)> = CDDISPATCH
+            .keys()
+            .map(|(anchor, tag)| (anchor.to_string(), tag.to_string()))
+            .collect();
+        for key in self.register.bind(py).keys() {
+            let (anchor, tag): (Bound<PyType>, String) = key.extract()?;
+            result.insert((anchor.name()?.to_string(), tag));
+        }
+        Ok(result)
+    }
+
+    /// Creates the record for `ixval` as an instance of `Anchor`.
+    ///
+    /// Tags with a rust constructor are dispatched without going through
+    /// python; the python register is only consulted for the remaining tags.
+    pub fn mk_typed_instance<'a, Anchor: PyTypeInfo>(
+        &self,
+        cd: &Bound<'a, CDictionary>,
+        ixval: &Bound<'a, IndexedTableValue>,
+    ) -> PyResult<Bound<'a, Anchor>> {
+        let py = cd.py();
+        let tag = ixval.get().tags()[0].as_str();
+        let record = match CDDISPATCH.get(&(Anchor::NAME, tag)) {
+            Some(entry) => (entry.create)(cd, ixval.get().clone())?,
+            None => self.mk_python_instance(cd, ixval, &Anchor::type_object_bound(py))?,
+        };
+        Ok(record.into_any().downcast_into()?)
+    }
+
+    fn mk_python_instance<'a>(
+        &self,
+        cd: &Bound<'a, CDictionary>,
+        ixval: &Bound<'a, IndexedTableValue>,
+        anchor: &Bound<'a, PyType>,
+    ) -> PyResult<Bound<'a, CDictionaryRecord>> {
+        let tag = ixval.get().tags()[0].as_str();
+        if is_rust_anchor(anchor) {
+            return Err(UnknownTagError::new(&anchor.name()?.to_string(), tag).into());
+        }
+        let Some(item) = self.register.bind(cd.py()).get_item((anchor, tag))? else {
+            return Err(UnknownTagError::new(&anchor.name()?.to_string(), tag).into());
+        };
+        Ok(item.call1((cd, ixval))?.downcast_into()?)
+    }
+}
+
+/// True for the anchors whose records are all implemented in rust; tags
+/// without a rust constructor are unknown for these, rather than looked up in
+/// the python register.
+fn is_rust_anchor(anchor: &Bound<PyType>) -> bool {
+    let py = anchor.py();
+    [
+        CAttr::type_object_bound(py),
+        CConst::type_object_bound(py),
+        CExp::type_object_bound(py),
+        CLval::type_object_bound(py),
+        COffset::type_object_bound(py),
+        CTyp::type_object_bound(py),
+    ]
+    .iter()
+    .any(|rust_anchor| anchor.is(rust_anchor))
 }
 
 fn create_entry_python_types<Anchor: PyTypeInfo + 'static, T: PyTypeInfo + 'static>(
@@ -156,6 +231,15 @@ fn create_entry_anchor<Anchor: PyTypeInfo + 'static>(py: Python) -> Py<PyType> {
     PyType::new_bound::<Anchor>(py).unbind()
 }
 
+fn create_record<'a, T: CDictionaryRecordTrait>(
+    cd: &Bound<'a, CDictionary>,
+    ixval: IndexedTableValue,
+) -> PyResult<Bound<'a, CDictionaryRecord>> {
+    Ok(Bound::new(cd.py(), T::new(cd, ixval)?)?
+        .into_any()
+        .downcast_into()?)
+}
+
 #[pymethods]
 impl CDictionaryRegistry {
     fn register_tag<'a>(
@@ -164,6 +248,12 @@ impl CDictionaryRegistry {
         tag: String,
         anchor: Py<PyType>,
     ) -> PyResult<Bound<'a, PyCFunction>> {
+        if is_rust_anchor(anchor.bind(py)) {
+            return Err(CHCError::new_err(format!(
+                "Records of {} are implemented in rust; cannot register tag {tag}",
+                anchor.bind(py).name()?
+            )));
+        }
         let closure =
             move |tuple: &Bound<PyTuple>, _dict: Option<&Bound<PyDict>>| -> PyResult<Py<PyType>> {
                 let (t,): (Py<PyType>,) = tuple.extract()?;
@@ -186,50 +276,39 @@ impl CDictionaryRegistry {
         ixval: &'d Bound<'a, IndexedTableValue>,
         anchor: &'e Bound<'a, PyType>,
     ) -> PyResult<Bound<'a, CDictionaryRecord>> {
-        let tag = ixval.get().tags()[0].clone();
-        for entry in &self.rust_register {
-            if entry.matches(cd.py(), anchor, tag.as_str())? {
-                return entry.mk_instance(cd, ixval);
+        let py = cd.py();
+        let name = anchor.name()?.to_string();
+        let tag = ixval.get().tags()[0].as_str();
+        match CDDISPATCH.get(&(name.as_str(), tag)) {
+            // python subclasses may reuse the name of a rust anchor
+            Some(entry) if (entry.anchor)(py).bind(py).is(anchor) => {
+                (entry.create)(cd, ixval.get().clone())
             }
+            _ => self.mk_python_instance(cd, ixval, anchor),
         }
-        let Some(item) = self
-            .register
-            .bind(cd.py())
-            .get_item((anchor, tag.as_str()))?
-        else {
-            return Err(CHCError::new_err(format!(
-                "Unknown cdictionary type: {tag}"
-            )));
-        };
-        Ok(item.call1((cd, ixval))?.downcast()?.clone())
     }
 }
 
-#[derive(Clone)]
-pub enum CDictionaryRegistryEntry {
-    PythonType {
-        tag: &'static str,
-        create: &'static (dyn Sync + Fn(Python) -> (Py<PyType>, Py<PyType>)),
-    },
-    RustType {
-        tag: &'static str,
-        anchor: &'static (dyn Sync + Fn(Python) -> Py<PyType>),
-        create: &'static (dyn Sync
-                      + for<'a, 'b> Fn(
-            &'a Bound<'b, CDictionary>,
-            IndexedTableValue,
-        ) -> PyResult<Bound<'b, CDictionaryRecord>>),
-    },
+/// Rust constructor for the records with tag `tag` in the table whose
+/// records are instances of `anchor`.
+pub struct CDictionaryRegistryEntry {
+    tag: &'static str,
+    anchor_name: &'static str,
+    anchor: &'static (dyn Sync + Fn(Python) -> Py<PyType>),
+    create: &'static (dyn Sync
+                  + for<'a, 'b> Fn(
+        &'a Bound<'b, CDictionary>,
+        IndexedTableValue,
+    ) -> PyResult<Bound<'b, CDictionaryRecord>>),
 }
 
 impl CDictionaryRegistryEntry {
-    pub const fn python_type<Anchor: PyTypeInfo + 'static, T: PyTypeInfo + 'static>(
+    /// Entry for a record type that is created through its
+    /// `CDictionaryRecordTrait` constructor.
+    pub const fn rust_record<Anchor: PyTypeInfo + 'static, T: CDictionaryRecordTrait>(
         tag: &'static str,
     ) -> CDictionaryRegistryEntry {
-        CDictionaryRegistryEntry::PythonType {
-            tag,
-            create: &create_entry_python_types::<Anchor, T>,
-        }
+        CDictionaryRegistryEntry::rust_type::<Anchor>(tag, &create_record::<T>)
     }
 
     pub const fn rust_type<Anchor: PyTypeInfo + 'static>(
@@ -240,57 +319,69 @@ impl CDictionaryRegistryEntry {
             IndexedTableValue,
         ) -> PyResult<Bound<'b, CDictionaryRecord>>),
     ) -> CDictionaryRegistryEntry {
-        CDictionaryRegistryEntry::RustType {
+        CDictionaryRegistryEntry {
             tag,
+            anchor_name: Anchor::NAME,
             anchor: &create_entry_anchor::<Anchor>,
             create,
         }
     }
+}
 
-    fn matches(&self, py: Python, anchor_in: &Bound<PyType>, tag_in: &str) -> PyResult<bool> {
-        Ok(match self {
-            Self::PythonType { tag, create } => {
-                *tag == tag_in && create(py).0.bind(py).eq(anchor_in)?
-            }
-            Self::RustType { tag, anchor, .. } => {
-                *tag == tag_in && anchor(py).bind(py).eq(anchor_in)?
-            }
-        })
+inventory::collect!(CDictionaryRegistryEntry);
+
+/// All rust record constructors, keyed by (anchor class name, tag).
+static CDDISPATCH: Lazy<HashMap<(&'static str, &'static str), &'static CDictionaryRegistryEntry>> =
+    Lazy::new(|| {
+        let mut dispatch = HashMap::new();
+        for entry in inventory::iter::<CDictionaryRegistryEntry>() {
+            dispatch
+                .entry((entry.anchor_name, entry.tag))
+                .or_insert(entry);
+        }
+        dispatch
+    });
+
+/// Record tag without a constructor in the table that is being read.
+#[derive(Debug, Clone)]
+pub struct UnknownTagError {
+    pub table: String,
+    pub tag: String,
+}
+
+impl UnknownTagError {
+    /// `anchor` is the class name of the records in the table.
+    pub fn new(anchor: &str, tag: &str) -> UnknownTagError {
+        let table = match cdictionary_anchor_table(anchor) {
+            Some(table) => table.to_string(),
+            None => anchor.to_string(),
+        };
+        UnknownTagError {
+            table,
+            tag: tag.to_string(),
+        }
     }
+}
 
-    fn mk_instance<'a, 'b, 'c, 'd>(
-        &self,
-        cd: &'b Bound<'a, CDictionary>,
-        ixval: &'c Bound<'a, IndexedTableValue>,
-    ) -> PyResult<Bound<'a, CDictionaryRecord>> {
-        Ok(match self {
-            Self::PythonType { create, .. } => create(cd.py())
-                .1
-                .bind(cd.py())
-                .call1((cd, ixval))?
-                .downcast()?
-                .clone(),
-            Self::RustType { create, .. } => {
-                let ixval = ixval.get().clone();
-                create(cd, ixval)?
-            }
-        })
+impl fmt::Display for UnknownTagError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unknown tag {} in table {}", self.tag, self.table)
     }
 }
 
-inventory::collect!(CDictionaryRegistryEntry);
+impl std::error::Error for UnknownTagError {}
+
+impl From<UnknownTagError> for PyErr {
+    fn from(err: UnknownTagError) -> PyErr {
+        CHCUnknownTagError::new_err((err.table, err.tag))
+    }
+}
 
 static CDREGISTRY: OnceCell<Py<CDictionaryRegistry>> = OnceCell::new();
 
 pub fn cdregistry(py: Python) -> PyResult<PyRef<CDictionaryRegistry>> {
     CDREGISTRY
-        .get_or_try_init(|| {
-            let entries = inventory::iter::<CDictionaryRegistryEntry>()
-                .cloned()
-                .collect();
-            let registry = CDictionaryRegistry::new(py, entries);
-            Py::new(py, registry)
-        })
+        .get_or_try_init(|| Py::new(py, CDictionaryRegistry::new(py)))
         .map(|reg| reg.borrow(py))
 }
 
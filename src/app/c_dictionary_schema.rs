@@ -0,0 +1,472 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Layout of the records in the CDictionary tables.
+//!
+//! For each table, and for each tag in tables whose records are dispatched on
+//! tags[0], this lists which table each arg refers to. It mirrors the
+//! descriptions on the record classes (CTyp, CExp, ...).
+
+use std::collections::{BTreeMap, HashMap};
+
+use itertools::Itertools;
+
+use crate::util::{
+    indexed_table::{IndexedTable, IndexedTableValueTrait},
+    table_check::{IntegrityReport, TableIssue, DANGLING_ARG, TOO_FEW_ARGS, UNKNOWN_TAG},
+};
+
+/// What an arg of a record refers to.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ArgRef {
+    /// Index in the named table.
+    Table(&'static str),
+    /// Index in the named table, or -1 if absent.
+    Optional(&'static str),
+    /// Not an index, e.g. an integer value, ckey, vid, or line number.
+    Value,
+}
+
+impl ArgRef {
+    /// The table `arg` refers to, if any.
+    pub fn target(&self, arg: isize) -> Option<&'static str> {
+        match self {
+            ArgRef::Table(t) => Some(t),
+            ArgRef::Optional(t) if arg >= 0 => Some(t),
+            _ => None,
+        }
+    }
+}
+
+/// Args of one kind of record: fixed positions followed by any number of
+/// args of kind `rest`.
+pub struct RecordSchema {
+    pub tag: &'static str,
+    pub args: &'static [ArgRef],
+    pub rest: ArgRef,
+}
+
+impl RecordSchema {
+    pub fn arg(&self, position: usize) -> ArgRef {
+        self.args.get(position).copied().unwrap_or(self.rest)
+    }
+
+    /// Number of args a record must have: all fixed positions, except for a
+    /// trailing attributes index, which is omitted for empty attributes.
+    pub fn required_args(&self) -> usize {
+        match self.args.last() {
+            Some(&ATTRIBUTES) => self.args.len() - 1,
+            _ => self.args.len(),
+        }
+    }
+}
+
+pub enum Layout {
+    /// Records are created by the cdregistry from tags[0], with `anchor` as
+    /// the base class.
+    Tagged {
+        anchor: &'static str,
+        records: &'static [RecordSchema],
+    },
+    /// All records have the same layout.
+    Fixed(RecordSchema),
+}
+
+pub struct TableSchema {
+    pub name: &'static str,
+    pub layout: Layout,
+}
+
+impl TableSchema {
+    /// Layout of the record with `tags`, or None for an unknown tag.
+    pub fn record<S: AsRef<str>>(&self, tags: &[S]) -> Option<&RecordSchema> {
+        match &self.layout {
+            Layout::Fixed(record) => Some(record),
+            Layout::Tagged { records, .. } => {
+                let tag = tags.first()?.as_ref();
+                records.iter().find(|r| r.tag == tag)
+            }
+        }
+    }
+
+    pub fn anchor(&self) -> Option<&'static str> {
+        match &self.layout {
+            Layout::Tagged { anchor, .. } => Some(anchor),
+            Layout::Fixed(_) => None,
+        }
+    }
+
+    /// Reports args of entries in `table` that refer to indices missing from
+    /// their target table, entries with fewer args than their record
+    /// requires, and tags for which `registered` is false.
+    ///
+    /// `exists(table, index)` returns None if the target table is not
+    /// available, in which case the arg is not checked.
+    pub fn check<T: IndexedTableValueTrait>(
+        &self,
+        table: &IndexedTable<T>,
+        exists: impl Fn(&str, isize) -> Option<bool>,
+        registered: impl Fn(&str, &str) -> bool,
+        report: &mut IntegrityReport,
+    ) {
+        for (ix, value) in table.iter() {
+            let tags = value.tags();
+            if let Some(anchor) = self.anchor() {
+                let tag = tags.first().map_or("", |t| t.as_ref());
+                if !registered(anchor, tag) {
+                    report.push(TableIssue::new(
+                        UNKNOWN_TAG,
+                        self.name,
+                        Some(*ix),
+                        format!("tag {tag:?} is not registered for {anchor}"),
+                    ));
+                }
+            }
+            let Some(record) = self.record(&tags) else {
+                continue;
+            };
+            if value.args().len() < record.required_args() {
+                report.push(TableIssue::new(
+                    TOO_FEW_ARGS,
+                    self.name,
+                    Some(*ix),
+                    format!(
+                        "{} args, {} requires {}",
+                        value.args().len(),
+                        tags.iter().join(","),
+                        record.required_args()
+                    ),
+                ));
+            }
+            for (position, arg) in value.args().iter().enumerate() {
+                let Some(target) = record.arg(position).target(*arg) else {
+                    continue;
+                };
+                if exists(target, *arg) == Some(false) {
+                    report.push(TableIssue::new(
+                        DANGLING_ARG,
+                        self.name,
+                        Some(*ix),
+                        format!("args[{position}] = {arg} is not in {target}"),
+                    ));
+                }
+            }
+        }
+    }
+}
+
+const fn tagged(
+    name: &'static str,
+    anchor: &'static str,
+    records: &'static [RecordSchema],
+) -> TableSchema {
+    TableSchema {
+        name,
+        layout: Layout::Tagged { anchor, records },
+    }
+}
+
+const fn fixed(name: &'static str, args: &'static [ArgRef], rest: ArgRef) -> TableSchema {
+    TableSchema {
+        name,
+        layout: Layout::Fixed(RecordSchema {
+            tag: "",
+            args,
+            rest,
+        }),
+    }
+}
+
+const fn rec(tag: &'static str, args: &'static [ArgRef]) -> RecordSchema {
+    RecordSchema {
+        tag,
+        args,
+        rest: ArgRef::Value,
+    }
+}
+
+const fn rec_rest(tag: &'static str, args: &'static [ArgRef], rest: ArgRef) -> RecordSchema {
+    RecordSchema { tag, args, rest }
+}
+
+const ATTRPARAM: ArgRef = ArgRef::Table("attrparam-table");
+const ATTRIBUTE: ArgRef = ArgRef::Table("attribute-table");
+const ATTRIBUTES: ArgRef = ArgRef::Table("attributes-table");
+const CONSTANT: ArgRef = ArgRef::Table("constant-table");
+const EXP: ArgRef = ArgRef::Table("exp-table");
+const OPT_EXP: ArgRef = ArgRef::Optional("exp-table");
+const FUNARG: ArgRef = ArgRef::Table("funarg-table");
+const OPT_FUNARGS: ArgRef = ArgRef::Optional("funargs-table");
+const LHOST: ArgRef = ArgRef::Table("lhost-table");
+const LVAL: ArgRef = ArgRef::Table("lval-table");
+const OFFSET: ArgRef = ArgRef::Table("offset-table");
+const TYP: ArgRef = ArgRef::Table("typ-table");
+const TYPSIG: ArgRef = ArgRef::Table("typsig-table");
+const OPT_TYPSIGLIST: ArgRef = ArgRef::Optional("typsiglist-table");
+const STRING: ArgRef = ArgRef::Table("string-table");
+const VALUE: ArgRef = ArgRef::Value;
+
+/// The CDictionary tables, in the order of `CDictionary.tables`.
+pub static CDICTIONARY_SCHEMA: &[TableSchema] = &[
+    tagged(
+        "attrparam-table",
+        "CAttr",
+        &[
+            rec("aint", &[VALUE]),
+            rec("astr", &[STRING]),
+            rec_rest("acons", &[], ATTRPARAM),
+            rec("asizeof", &[TYP]),
+            rec("asizeofe", &[ATTRPARAM]),
+            rec("asizeofs", &[TYPSIG]),
+            rec("aalignof", &[TYP]),
+            rec("aalignofe", &[ATTRPARAM]),
+            rec("aalignofs", &[TYPSIG]),
+            rec("aunop", &[ATTRPARAM]),
+            rec("abinop", &[ATTRPARAM, ATTRPARAM]),
+            rec("adot", &[ATTRPARAM]),
+            rec("astar", &[ATTRPARAM]),
+            rec("aaddrof", &[ATTRPARAM]),
+            rec("aindex", &[ATTRPARAM, ATTRPARAM]),
+            rec("aquestion", &[ATTRPARAM, ATTRPARAM, ATTRPARAM]),
+        ],
+    ),
+    fixed("attribute-table", &[], ATTRPARAM),
+    fixed("attributes-table", &[], ATTRIBUTE),
+    tagged(
+        "constant-table",
+        "CConst",
+        &[
+            rec("int", &[]),
+            rec("str", &[STRING]),
+            rec("wstr", &[]),
+            rec("chr", &[VALUE]),
+            rec("real", &[]),
+            rec("enum", &[EXP]),
+        ],
+    ),
+    tagged(
+        "exp-table",
+        "CExp",
+        &[
+            rec("const", &[CONSTANT]),
+            rec("lval", &[LVAL]),
+            rec("sizeof", &[TYP]),
+            rec("sizeofe", &[EXP]),
+            rec("sizeofstr", &[STRING]),
+            rec("alignof", &[TYP]),
+            rec("alignofe", &[EXP]),
+            rec("unop", &[EXP, TYP]),
+            rec("binop", &[EXP, EXP, TYP]),
+            rec("question", &[EXP, EXP, EXP, TYP]),
+            rec("caste", &[TYP, EXP]),
+            rec("addrof", &[LVAL]),
+            rec("addroflabel", &[VALUE]),
+            rec("startof", &[LVAL]),
+            rec_rest("fnapp", &[VALUE, VALUE, EXP], OPT_EXP),
+            rec_rest("cnapp", &[TYP], OPT_EXP),
+        ],
+    ),
+    fixed("funarg-table", &[TYP, ATTRIBUTES], VALUE),
+    fixed("funargs-table", &[], FUNARG),
+    tagged(
+        "lhost-table",
+        "CLHost",
+        &[rec("var", &[VALUE]), rec("mem", &[EXP])],
+    ),
+    fixed("lval-table", &[LHOST, OFFSET], VALUE),
+    tagged(
+        "offset-table",
+        "COffset",
+        &[
+            rec("n", &[]),
+            rec("f", &[VALUE, OFFSET]),
+            rec("i", &[EXP, OFFSET]),
+        ],
+    ),
+    tagged(
+        "typ-table",
+        "CTyp",
+        &[
+            rec("tvoid", &[ATTRIBUTES]),
+            rec("tint", &[ATTRIBUTES]),
+            rec("tfloat", &[ATTRIBUTES]),
+            rec("tnamed", &[ATTRIBUTES]),
+            rec("tcomp", &[VALUE, ATTRIBUTES]),
+            rec("tenum", &[ATTRIBUTES]),
+            rec("tbuiltinvaargs", &[]),
+            rec("tbuiltin-va-list", &[]),
+            rec("tptr", &[TYP, ATTRIBUTES]),
+            rec("tarray", &[TYP, OPT_EXP, ATTRIBUTES]),
+            rec("tfun", &[TYP, OPT_FUNARGS, VALUE, ATTRIBUTES]),
+        ],
+    ),
+    tagged(
+        "typsig-table",
+        "CTypsig",
+        &[
+            rec("tsarray", &[TYPSIG, ATTRIBUTES]),
+            rec("tsptr", &[TYPSIG]),
+            rec("tscomp", &[]),
+            rec("tsfun", &[TYPSIG, OPT_TYPSIGLIST]),
+            rec("tsenum", &[]),
+            rec("tsbase", &[TYP]),
+        ],
+    ),
+    fixed("typsiglist-table", &[], TYPSIG),
+];
+
+pub fn cdictionary_table_schema(name: &str) -> Option<&'static TableSchema> {
+    CDICTIONARY_SCHEMA.iter().find(|t| t.name == name)
+}
+
+/// Name of the table whose records are instances of `anchor`.
+pub fn cdictionary_anchor_table(anchor: &str) -> Option<&'static str> {
+    CDICTIONARY_SCHEMA
+        .iter()
+        .find(|t| t.anchor() == Some(anchor))
+        .map(|t| t.name)
+}
+
+/// Keys of CDictionary entries in which every arg that refers to another
+/// table is replaced by the key of the entry it refers to, and every string
+/// index by the string itself.
+///
+/// Unlike the (tags, args) key, these do not depend on how the entries happen
+/// to be numbered, so they can be used to match the entries of dictionaries
+/// produced by different runs. A reference back to an entry that is being
+/// resolved, which only occurs in malformed dictionaries, is written as
+/// `@n`, where n is the number of levels up to that entry.
+pub struct StructuralKeys<'a, T> {
+    tables: BTreeMap<&'a str, &'a IndexedTable<T>>,
+    strings: Option<&'a BTreeMap<isize, String>>,
+    memo: HashMap<(&'a str, isize), String>,
+}
+
+/// Position in the resolution stack of the outermost entry referred back to,
+/// or None.
+type BackReference = Option<usize>;
+
+impl<'a, T: IndexedTableValueTrait> StructuralKeys<'a, T> {
+    pub fn new(
+        tables: impl IntoIterator<Item = &'a IndexedTable<T>>,
+        strings: Option<&'a BTreeMap<isize, String>>,
+    ) -> Self {
+        StructuralKeys {
+            tables: tables.into_iter().map(|t| (t.name(), t)).collect(),
+            strings,
+            memo: HashMap::new(),
+        }
+    }
+
+    /// (tags, args) key of entry `ix` of `table`, with resolved args.
+    pub fn key(&mut self, table: &'a str, ix: isize) -> Option<(String, String)> {
+        let value = self.tables.get(table)?.get(ix)?;
+        let (args, _) = self.resolve_args(table, value, &mut vec![(table, ix)]);
+        Some((value.tags().iter().join(","), args))
+    }
+
+    /// Structural keys of all entries of `table`.
+    pub fn keys(&mut self, table: &'a str) -> Vec<((String, String), isize)> {
+        let Some(t) = self.tables.get(table).copied() else {
+            return Vec::new();
+        };
+        t.keys()
+            .filter_map(|ix| Some((self.key(table, *ix)?, *ix)))
+            .collect()
+    }
+
+    fn resolve_args(
+        &mut self,
+        table: &'a str,
+        value: &'a T,
+        stack: &mut Vec<(&'a str, isize)>,
+    ) -> (String, BackReference) {
+        let tags = value.tags();
+        let record = cdictionary_table_schema(table).and_then(|s| s.record(&tags));
+        let mut outermost: BackReference = None;
+        let args = value
+            .args()
+            .iter()
+            .enumerate()
+            .map(
+                |(position, arg)| match record.and_then(|r| r.arg(position).target(*arg)) {
+                    Some(target) => {
+                        let (key, backref) = self.resolve(target, *arg, stack);
+                        outermost = match (outermost, backref) {
+                            (Some(a), Some(b)) => Some(a.min(b)),
+                            (a, b) => a.or(b),
+                        };
+                        key
+                    }
+                    None => arg.to_string(),
+                },
+            )
+            .join(",");
+        (args, outermost)
+    }
+
+    fn resolve(
+        &mut self,
+        table: &'a str,
+        ix: isize,
+        stack: &mut Vec<(&'a str, isize)>,
+    ) -> (String, BackReference) {
+        if table == "string-table" {
+            let key = match self.strings.and_then(|s| s.get(&ix)) {
+                Some(s) => format!("{s:?}"),
+                None => format!("s{ix}"),
+            };
+            return (key, None);
+        }
+        if let Some(key) = self.memo.get(&(table, ix)) {
+            return (key.clone(), None);
+        }
+        if let Some(position) = stack.iter().position(|e| *e == (table, ix)) {
+            return (format!("@{}", stack.len() - position), Some(position));
+        }
+        let Some(value) = self.tables.get(table).and_then(|t| t.get(ix)) else {
+            return (format!("?{ix}"), None);
+        };
+        let position = stack.len();
+        stack.push((table, ix));
+        let (args, backref) = self.resolve_args(table, value, stack);
+        stack.pop();
+        let key = format!("{}({args})", value.tags().iter().join(","));
+        // A key that refers back to an entry outside it depends on the path
+        // it was reached by, so it is not reused.
+        match backref {
+            Some(p) if p < position => (key, backref),
+            _ => {
+                self.memo.insert((table, ix), key.clone());
+                (key, None)
+            }
+        }
+    }
+}
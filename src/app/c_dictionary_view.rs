@@ -0,0 +1,190 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+use std::{collections::BTreeMap, sync::Arc};
+
+use pyo3::prelude::*;
+
+use crate::{
+    app::c_dictionary_schema::{cdictionary_table_schema, StructuralKeys},
+    util::{
+        indexed_table::{IndexedTable, IndexedTableError, IndexedTableValue},
+        table_check::IntegrityReport,
+        table_diff::TableDiff,
+    },
+};
+
+pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
+    let module = PyModule::new_bound(py, "c_dictionary_view")?;
+    module.add_class::<CDictionaryView>()?;
+    Ok(module)
+}
+
+/// Tables of a CDictionary, copied out of their python objects.
+pub struct DictionaryTables {
+    tables: Vec<IndexedTable<IndexedTableValue>>,
+    strings: Option<BTreeMap<isize, String>>,
+}
+
+/// Read-only snapshot of a loaded CDictionary (see `CDictionary.view`).
+///
+/// The snapshot holds no python objects, so it is Send + Sync: it can be
+/// shared with worker threads, and its queries run without the GIL. Later
+/// changes to the dictionary are not reflected in it.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct CDictionaryView {
+    inner: Arc<DictionaryTables>,
+}
+
+const _: () = {
+    const fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<CDictionaryView>();
+};
+
+#[pymethods]
+impl CDictionaryView {
+    #[getter]
+    fn table_names(&self) -> Vec<&str> {
+        self.inner.tables.iter().map(|t| t.name()).collect()
+    }
+
+    fn size(&self, table: &str) -> PyResult<isize> {
+        Ok(self.table_or_err(table)?.size())
+    }
+
+    #[pyo3(name = "retrieve")]
+    fn py_retrieve(&self, table: &str, index: isize) -> PyResult<IndexedTableValue> {
+        self.retrieve(table, index).cloned()
+    }
+
+    /// Index of the entry with `tags` and `args` in `table`, if present.
+    fn find(&self, table: &str, tags: Vec<String>, args: Vec<isize>) -> PyResult<Option<isize>> {
+        Ok(self.table_or_err(table)?.get_index_tags_args(&tags, &args))
+    }
+
+    #[pyo3(name = "get_string")]
+    fn py_get_string(&self, index: isize) -> PyResult<String> {
+        self.get_string(index).map(|s| s.to_string())
+    }
+
+    /// Checks the tables as `CDictionary.check` does, except for the tags,
+    /// which need the registry; the GIL is released while checking.
+    fn check(&self, py: Python) -> IntegrityReport {
+        py.allow_threads(|| self.check_tables())
+    }
+
+    /// Entries added, removed, and re-indexed in `other`, as
+    /// `CDictionary.diff`; the GIL is released while comparing.
+    fn diff(&self, py: Python, other: &CDictionaryView) -> Vec<TableDiff> {
+        py.allow_threads(|| self.diff_tables(other))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self) -> String {
+        self.inner
+            .tables
+            .iter()
+            .map(|t| format!("{:<20} {:>8}", t.name(), t.size()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl CDictionaryView {
+    pub fn new(
+        tables: Vec<IndexedTable<IndexedTableValue>>,
+        strings: Option<BTreeMap<isize, String>>,
+    ) -> CDictionaryView {
+        CDictionaryView {
+            inner: Arc::new(DictionaryTables { tables, strings }),
+        }
+    }
+
+    pub fn tables(&self) -> &[IndexedTable<IndexedTableValue>] {
+        &self.inner.tables
+    }
+
+    pub fn table(&self, name: &str) -> Option<&IndexedTable<IndexedTableValue>> {
+        self.inner.tables.iter().find(|t| t.name() == name)
+    }
+
+    fn table_or_err(&self, name: &str) -> PyResult<&IndexedTable<IndexedTableValue>> {
+        self.table(name)
+            .ok_or_else(|| IndexedTableError::new_err(format!("No table {name} in dictionary")))
+    }
+
+    pub fn get(&self, table: &str, index: isize) -> Option<&IndexedTableValue> {
+        self.table(table)?.get(index)
+    }
+
+    pub fn retrieve(&self, table: &str, index: isize) -> PyResult<&IndexedTableValue> {
+        self.table_or_err(table)?.retrieve(index)
+    }
+
+    pub fn get_string(&self, index: isize) -> PyResult<&str> {
+        self.inner
+            .strings
+            .as_ref()
+            .and_then(|s| s.get(&index))
+            .map(|s| s.as_str())
+            .ok_or_else(|| {
+                IndexedTableError::new_err(format!(
+                    "Unable to retrieve item {index} from table string-table"
+                ))
+            })
+    }
+
+    pub fn check_tables(&self) -> IntegrityReport {
+        let exists = |name: &str, ix: isize| {
+            if name == "string-table" {
+                return self.inner.strings.as_ref().map(|s| s.contains_key(&ix));
+            }
+            self.table(name).map(|t| t.get(ix).is_some())
+        };
+        let mut report = IntegrityReport::default();
+        for table in self.tables() {
+            table.check(&mut report);
+            if let Some(schema) = cdictionary_table_schema(table.name()) {
+                schema.check(table, exists, |_, _| true, &mut report);
+            }
+        }
+        report
+    }
+
+    pub fn diff_tables(&self, other: &CDictionaryView) -> Vec<TableDiff> {
+        let mut old_keys = StructuralKeys::new(self.tables(), self.inner.strings.as_ref());
+        let mut new_keys = StructuralKeys::new(other.tables(), other.inner.strings.as_ref());
+        self.tables()
+            .iter()
+            .map(|t| TableDiff::new(t.name(), old_keys.keys(t.name()), new_keys.keys(t.name())))
+            .collect()
+    }
+}
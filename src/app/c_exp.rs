@@ -28,15 +28,24 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 ------------------------------------------------------------------------------
 */
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
-use pyo3::{intern, prelude::*};
+use pyo3::{exceptions::PyException, intern, prelude::*};
 
 use crate::{
     app::{
         c_const::CConst,
+        c_data_model::{data_model, CDataModel},
         c_dictionary::CDictionary,
-        c_dictionary_record::{CDictionaryRecord, CDictionaryRegistryEntry},
+        c_dictionary_record::{
+            CDictionaryRecord, CDictionaryRecordTrait, CDictionaryRegistryEntry,
+        },
+        c_exp_eval,
+        c_exp_rewrite::{self, Normalization, Substitution},
+        c_lval::CLval,
+        c_pretty,
+        c_typ::CTyp,
+        c_visitor::{Dereferences, Node, PyVisitor, VariableIds},
     },
     util::indexed_table::IndexedTableValue,
 };
@@ -45,6 +54,21 @@ pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "c_exp")?;
     module.add_class::<CExp>()?;
     module.add_class::<CExpConst>()?;
+    module.add_class::<CExpLval>()?;
+    module.add_class::<CExpSizeOf>()?;
+    module.add_class::<CExpSizeOfE>()?;
+    module.add_class::<CExpSizeOfStr>()?;
+    module.add_class::<CExpAlignOf>()?;
+    module.add_class::<CExpAlignOfE>()?;
+    module.add_class::<CExpUnOp>()?;
+    module.add_class::<CExpBinOp>()?;
+    module.add_class::<CExpQuestion>()?;
+    module.add_class::<CExpCastE>()?;
+    module.add_class::<CExpAddrOf>()?;
+    module.add_class::<CExpAddrOfLabel>()?;
+    module.add_class::<CExpStartOf>()?;
+    module.add_class::<CExpFnApp>()?;
+    module.add_class::<CExpCnApp>()?;
     Ok(module)
 }
 
@@ -154,6 +178,61 @@ impl CExp {
         BTreeMap::from([("base".to_string(), "exp".to_string())])
     }
 
+    /// C syntax of this expression.
+    fn to_c(slf: &Bound<Self>) -> PyResult<String> {
+        c_pretty::to_c(slf.as_any())
+    }
+
+    /// Address expressions of the memory references in this expression,
+    /// outermost first.
+    fn get_dereferenced_exps<'a>(slf: &Bound<'a, Self>) -> PyResult<Vec<Bound<'a, CExp>>> {
+        Dereferences::of(&Node::Exp(slf.clone()))
+    }
+
+    /// Ids of the variables that occur in this expression.
+    fn get_variable_ids(slf: &Bound<Self>) -> PyResult<BTreeSet<isize>> {
+        Node::Exp(slf.clone()).fold(&mut VariableIds)
+    }
+
+    /// Calls `enter` and `leave` with this expression and with each of its
+    /// subterms, depth-first, before and after the children of the subterm.
+    ///
+    /// A hook that returns a value other than None stops the traversal, and
+    /// walk returns that value; walk returns None if all nodes are visited.
+    #[pyo3(signature = (enter=None, leave=None))]
+    fn walk<'a>(
+        slf: &Bound<'a, Self>,
+        enter: Option<Bound<'a, PyAny>>,
+        leave: Option<Bound<'a, PyAny>>,
+    ) -> PyResult<Option<Bound<'a, PyAny>>> {
+        PyVisitor::walk(&Node::Exp(slf.clone()), enter, leave)
+    }
+
+    /// This expression with the variables with ids in `subst` replaced by the
+    /// corresponding expressions, interned in the same cdictionary.
+    fn substitute<'a>(
+        slf: &Bound<'a, Self>,
+        subst: HashMap<isize, Bound<'a, CExp>>,
+    ) -> PyResult<Bound<'a, Self>> {
+        let cd = slf.as_any().getattr(intern!(slf.py(), "cd"))?;
+        let mut substitution = Substitution::new(&cd, subst)?;
+        c_exp_rewrite::rewrite_exp(&mut substitution, slf)
+    }
+
+    /// This expression with `*(&x)` simplified to `x`, `&(*p)` to `p`, and casts
+    /// to the type of the operand removed.
+    fn normalize<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, Self>> {
+        c_exp_rewrite::rewrite_exp(&mut Normalization, slf)
+    }
+
+    /// Value in `model` (default: the current data model) if this is an
+    /// integer constant expression whose evaluation is defined.
+    #[pyo3(signature = (model=None))]
+    fn evaluate(slf: &Bound<Self>, model: Option<CDataModel>) -> PyResult<Option<i128>> {
+        let model = model.unwrap_or_else(data_model);
+        Ok(c_exp_eval::evaluate(slf, &model)?.map(|value| value.value))
+    }
+
     fn to_idict(slf: &Bound<Self>) -> BTreeMap<String, Py<PyAny>> {
         let c_dict_record = slf.borrow().into_super().into_super();
         BTreeMap::from([
@@ -190,7 +269,7 @@ impl CExpConst {
     pub fn constant<'a, 'b>(slf: &'a Bound<'b, Self>) -> PyResult<Bound<'b, CConst>> {
         let c_dict_record = slf.borrow().into_super().into_super();
         let cd = c_dict_record.cd().bind(slf.py()).clone();
-        let arg_0 = c_dict_record.into_super().args()[0];
+        let arg_0 = c_dict_record.into_super().arg(0)?;
         Ok(cd
             .call_method1(intern!(slf.py(), "get_constant"), (arg_0,))?
             .downcast()?
@@ -220,4 +299,1279 @@ impl CExpConst {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CExp, CExpConst>("const") }
+impl CDictionaryRecordTrait for CExpConst {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd.clone().unbind(), ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpConst>("const") }
+
+/// Name of a binary operator in C.
+fn binop_string(op: &str) -> PyResult<&'static str> {
+    Ok(match op {
+        "band" => "&",
+        "bor" => "|",
+        "bxor" => "^",
+        "div" => "/",
+        "eq" => "==",
+        "ge" => ">=",
+        "gt" => ">",
+        "indexpi" => "+",
+        "land" => "&&",
+        "le" => "<=",
+        "lor" => "||",
+        "lt" => "<",
+        "minusa" => "-",
+        "minuspi" => "-",
+        "minuspp" => "-",
+        "mod" => "%",
+        "mult" => "*",
+        "ne" => "!=",
+        "plusa" => "+",
+        "pluspi" => "+",
+        "shiftlt" => "<<",
+        "shiftrt" => ">>",
+        _ => {
+            return Err(PyException::new_err(format!(
+                "unknown binary operator '{op}'"
+            )))
+        }
+    })
+}
+
+/// Name of a unary operator in C.
+fn unop_string(op: &str) -> PyResult<&'static str> {
+    Ok(match op {
+        "neg" => "-",
+        "bnot" => "~",
+        "lnot" => "!",
+        _ => {
+            return Err(PyException::new_err(format!(
+                "unknown unary operator '{op}'"
+            )))
+        }
+    })
+}
+
+fn get_exp<'a>(cd: &Py<CDictionary>, py: Python<'a>, ix: isize) -> PyResult<Bound<'a, CExp>> {
+    Ok(cd
+        .bind(py)
+        .call_method1(intern!(py, "get_exp"), (ix,))?
+        .downcast_into()?)
+}
+
+fn get_exp_opt<'a>(
+    cd: &Py<CDictionary>,
+    py: Python<'a>,
+    ix: isize,
+) -> PyResult<Option<Bound<'a, CExp>>> {
+    if ix >= 0 {
+        Ok(Some(get_exp(cd, py, ix)?))
+    } else {
+        Ok(None)
+    }
+}
+
+fn get_typ<'a>(cd: &Py<CDictionary>, py: Python<'a>, ix: isize) -> PyResult<Bound<'a, CTyp>> {
+    Ok(cd
+        .bind(py)
+        .call_method1(intern!(py, "get_typ"), (ix,))?
+        .downcast_into()?)
+}
+
+fn get_lval<'a>(cd: &Py<CDictionary>, py: Python<'a>, ix: isize) -> PyResult<Bound<'a, CLval>> {
+    Ok(cd
+        .bind(py)
+        .call_method1(intern!(py, "get_lval"), (ix,))?
+        .downcast_into()?)
+}
+
+// The queries below resolve with the python interpreter, because lhosts are
+// still implemented in python.
+
+fn has_variable<T>(item: &Bound<T>, vid: isize) -> PyResult<bool> {
+    item.as_any()
+        .call_method1(intern!(item.py(), "has_variable"), (vid,))?
+        .extract()
+}
+
+fn get_strings<T>(item: &Bound<T>) -> PyResult<Vec<String>> {
+    item.as_any()
+        .call_method0(intern!(item.py(), "get_strings"))?
+        .extract()
+}
+
+fn get_variable_uses<T>(item: &Bound<T>, vid: isize) -> PyResult<isize> {
+    item.as_any()
+        .call_method1(intern!(item.py(), "get_variable_uses"), (vid,))?
+        .extract()
+}
+
+fn to_dict<T>(item: &Bound<T>) -> PyResult<Py<PyAny>> {
+    Ok(item
+        .as_any()
+        .call_method0(intern!(item.py(), "to_dict"))?
+        .unbind())
+}
+
+/// Lvalue expression.
+///
+/// - args[0]: index of lval in cdictionary
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpLval {
+    cd: Py<CDictionary>,
+    lval_index: isize,
+}
+
+#[pymethods]
+impl CExpLval {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpLval {
+            cd: cd.clone().unbind(),
+            lval_index: ixval.arg(0)?,
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn lval<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CLval>> {
+        get_lval(&self.cd, py, self.lval_index)
+    }
+
+    #[getter]
+    fn is_lval(&self) -> bool {
+        true
+    }
+
+    fn has_variable(&self, py: Python, vid: isize) -> PyResult<bool> {
+        has_variable(&self.lval(py)?, vid)
+    }
+
+    fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
+        get_strings(&self.lval(py)?)
+    }
+
+    fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<isize> {
+        get_variable_uses(&self.lval(py)?, vid)
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        Ok(BTreeMap::from([
+            ("base", "lval".into_py(py)),
+            ("lval", to_dict(&self.lval(py)?)?),
+        ]))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(self.lval(py)?.str()?.to_string())
+    }
+}
+
+impl CDictionaryRecordTrait for CExpLval {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpLval>("lval") }
+
+/// Sizeof type expression.
+///
+/// - args[0]: index of target type in cdictionary
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpSizeOf {
+    cd: Py<CDictionary>,
+    typ_index: isize,
+}
+
+#[pymethods]
+impl CExpSizeOf {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpSizeOf {
+            cd: cd.clone().unbind(),
+            typ_index: ixval.arg(0)?,
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn typ<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
+        get_typ(&self.cd, py, self.typ_index)
+    }
+
+    #[getter]
+    fn is_sizeof(&self) -> bool {
+        true
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        Ok(BTreeMap::from([
+            ("base", "sizeof".into_py(py)),
+            ("type", to_dict(&self.typ(py)?)?),
+        ]))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(format!("sizeof({})", self.typ(py)?.str()?))
+    }
+}
+
+impl CDictionaryRecordTrait for CExpSizeOf {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpSizeOf>("sizeof") }
+
+/// Sizeof expression expression.
+///
+/// - args[0]: index of expression in cdictionary
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpSizeOfE {
+    cd: Py<CDictionary>,
+    exp_index: isize,
+}
+
+#[pymethods]
+impl CExpSizeOfE {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpSizeOfE {
+            cd: cd.clone().unbind(),
+            exp_index: ixval.arg(0)?,
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn exp<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CExp>> {
+        get_exp(&self.cd, py, self.exp_index)
+    }
+
+    #[getter]
+    fn is_sizeofe(&self) -> bool {
+        true
+    }
+
+    fn has_variable(&self, py: Python, vid: isize) -> PyResult<bool> {
+        has_variable(&self.exp(py)?, vid)
+    }
+
+    fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
+        get_strings(&self.exp(py)?)
+    }
+
+    fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<isize> {
+        get_variable_uses(&self.exp(py)?, vid)
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        Ok(BTreeMap::from([
+            ("base", "sizeofe".into_py(py)),
+            ("exp", to_dict(&self.exp(py)?)?),
+        ]))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(format!("sizeofe({})", self.exp(py)?.str()?))
+    }
+}
+
+impl CDictionaryRecordTrait for CExpSizeOfE {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpSizeOfE>("sizeofe") }
+
+/// Sizeof string expression.
+///
+/// - args[0]: index of string in the string table
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpSizeOfStr {
+    cd: Py<CDictionary>,
+    string_index: isize,
+}
+
+#[pymethods]
+impl CExpSizeOfStr {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpSizeOfStr {
+            cd: cd.clone().unbind(),
+            string_index: ixval.arg(0)?,
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn stringvalue(&self, py: Python) -> PyResult<String> {
+        self.cd
+            .bind(py)
+            .call_method1(intern!(py, "get_string"), (self.string_index,))?
+            .extract()
+    }
+
+    #[getter]
+    fn is_sizeofstr(&self) -> bool {
+        true
+    }
+
+    fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
+        Ok(vec![self.stringvalue(py)?])
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        Ok(BTreeMap::from([
+            ("base", "sizeofstr".into_py(py)),
+            ("string", self.stringvalue(py)?.into_py(py)),
+        ]))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(format!("sizeofstr({})", self.stringvalue(py)?))
+    }
+}
+
+impl CDictionaryRecordTrait for CExpSizeOfStr {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpSizeOfStr>("sizeofstr") }
+
+/// Alignof type expression.
+///
+/// - args[0]: index of type in cdictionary
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpAlignOf {
+    cd: Py<CDictionary>,
+    typ_index: isize,
+}
+
+#[pymethods]
+impl CExpAlignOf {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpAlignOf {
+            cd: cd.clone().unbind(),
+            typ_index: ixval.arg(0)?,
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn typ<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
+        get_typ(&self.cd, py, self.typ_index)
+    }
+
+    #[getter]
+    fn is_alignof(&self) -> bool {
+        true
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        Ok(BTreeMap::from([
+            ("base", "alignof".into_py(py)),
+            ("type", to_dict(&self.typ(py)?)?),
+        ]))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(format!("alignof({})", self.typ(py)?.str()?))
+    }
+}
+
+impl CDictionaryRecordTrait for CExpAlignOf {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpAlignOf>("alignof") }
+
+/// Alignof expression expression.
+///
+/// - args[0]: index of expression in cdictionary
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpAlignOfE {
+    cd: Py<CDictionary>,
+    exp_index: isize,
+}
+
+#[pymethods]
+impl CExpAlignOfE {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpAlignOfE {
+            cd: cd.clone().unbind(),
+            exp_index: ixval.arg(0)?,
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn exp<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CExp>> {
+        get_exp(&self.cd, py, self.exp_index)
+    }
+
+    #[getter]
+    fn is_alignofe(&self) -> bool {
+        true
+    }
+
+    fn has_variable(&self, py: Python, vid: isize) -> PyResult<bool> {
+        has_variable(&self.exp(py)?, vid)
+    }
+
+    fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
+        get_strings(&self.exp(py)?)
+    }
+
+    fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<isize> {
+        get_variable_uses(&self.exp(py)?, vid)
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        Ok(BTreeMap::from([
+            ("base", "alignofe".into_py(py)),
+            ("exp", to_dict(&self.exp(py)?)?),
+        ]))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(format!("alignofe({})", self.exp(py)?.str()?))
+    }
+}
+
+impl CDictionaryRecordTrait for CExpAlignOfE {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpAlignOfE>("alignofe") }
+
+/// Unary expression.
+///
+/// - tags[1]: unary operator
+///
+/// - args[0]: index of subexpression in cdictionary
+/// - args[1]: index of result type in cdictionary
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpUnOp {
+    cd: Py<CDictionary>,
+    #[pyo3(get)]
+    op: String,
+    exp_index: isize,
+    typ_index: isize,
+}
+
+#[pymethods]
+impl CExpUnOp {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpUnOp {
+            cd: cd.clone().unbind(),
+            op: ixval.tag(1)?.to_string(),
+            exp_index: ixval.arg(0)?,
+            typ_index: ixval.arg(1)?,
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn exp<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CExp>> {
+        get_exp(&self.cd, py, self.exp_index)
+    }
+
+    #[getter]
+    fn typ<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
+        get_typ(&self.cd, py, self.typ_index)
+    }
+
+    #[getter]
+    fn is_unop(&self) -> bool {
+        true
+    }
+
+    fn has_variable(&self, py: Python, vid: isize) -> PyResult<bool> {
+        has_variable(&self.exp(py)?, vid)
+    }
+
+    fn has_variable_op(&self, py: Python, vid: isize, op: &str) -> PyResult<bool> {
+        Ok(unop_string(&self.op)? == op && self.has_variable(py, vid)?)
+    }
+
+    fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
+        get_strings(&self.exp(py)?)
+    }
+
+    fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<isize> {
+        get_variable_uses(&self.exp(py)?, vid)
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        Ok(BTreeMap::from([
+            ("base", "unop".into_py(py)),
+            ("op", self.op.as_str().into_py(py)),
+            ("exp", to_dict(&self.exp(py)?)?),
+        ]))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "({} {})",
+            unop_string(&self.op)?,
+            self.exp(py)?.str()?
+        ))
+    }
+}
+
+impl CDictionaryRecordTrait for CExpUnOp {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpUnOp>("unop") }
+
+/// Binary expression.
+///
+/// - tags[1]: binary operator
+///
+/// - args[0]: index of exp1 in cdictionary
+/// - args[1]: index of exp2 in cdictionary
+/// - args[2]: index of result type in cdictionary
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpBinOp {
+    cd: Py<CDictionary>,
+    #[pyo3(get)]
+    op: String,
+    exp1_index: isize,
+    exp2_index: isize,
+    typ_index: isize,
+}
+
+#[pymethods]
+impl CExpBinOp {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpBinOp {
+            cd: cd.clone().unbind(),
+            op: ixval.tag(1)?.to_string(),
+            exp1_index: ixval.arg(0)?,
+            exp2_index: ixval.arg(1)?,
+            typ_index: ixval.arg(2)?,
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn exp1<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CExp>> {
+        get_exp(&self.cd, py, self.exp1_index)
+    }
+
+    #[getter]
+    fn exp2<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CExp>> {
+        get_exp(&self.cd, py, self.exp2_index)
+    }
+
+    #[getter]
+    fn typ<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
+        get_typ(&self.cd, py, self.typ_index)
+    }
+
+    #[getter]
+    fn is_binop(&self) -> bool {
+        true
+    }
+
+    fn has_variable(&self, py: Python, vid: isize) -> PyResult<bool> {
+        Ok(has_variable(&self.exp1(py)?, vid)? || has_variable(&self.exp2(py)?, vid)?)
+    }
+
+    fn has_variable_op(&self, py: Python, vid: isize, op: &str) -> PyResult<bool> {
+        Ok(binop_string(&self.op)? == op && self.has_variable(py, vid)?)
+    }
+
+    fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
+        let mut result = get_strings(&self.exp1(py)?)?;
+        result.extend(get_strings(&self.exp2(py)?)?);
+        Ok(result)
+    }
+
+    fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<isize> {
+        Ok(get_variable_uses(&self.exp1(py)?, vid)? + get_variable_uses(&self.exp2(py)?, vid)?)
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        Ok(BTreeMap::from([
+            ("base", "binop".into_py(py)),
+            ("op", self.op.as_str().into_py(py)),
+            ("exp1", to_dict(&self.exp1(py)?)?),
+            ("exp2", to_dict(&self.exp2(py)?)?),
+        ]))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "({} {} {}):{}",
+            self.exp1(py)?.str()?,
+            binop_string(&self.op)?,
+            self.exp2(py)?.str()?,
+            self.typ(py)?.str()?
+        ))
+    }
+}
+
+impl CDictionaryRecordTrait for CExpBinOp {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpBinOp>("binop") }
+
+/// Question expression.
+///
+/// - args[0]: index of conditional expression in cdictionary
+/// - args[1]: index of if-true expression in cdictionary
+/// - args[2]: index of if-false expression in cdictionary
+/// - args[3]: index of result type in cdictionary
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpQuestion {
+    cd: Py<CDictionary>,
+    condition_index: isize,
+    true_exp_index: isize,
+    false_exp_index: isize,
+    typ_index: isize,
+}
+
+#[pymethods]
+impl CExpQuestion {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpQuestion {
+            cd: cd.clone().unbind(),
+            condition_index: ixval.arg(0)?,
+            true_exp_index: ixval.arg(1)?,
+            false_exp_index: ixval.arg(2)?,
+            typ_index: ixval.arg(3)?,
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn condition<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CExp>> {
+        get_exp(&self.cd, py, self.condition_index)
+    }
+
+    #[getter]
+    fn true_exp<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CExp>> {
+        get_exp(&self.cd, py, self.true_exp_index)
+    }
+
+    #[getter]
+    fn false_exp<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CExp>> {
+        get_exp(&self.cd, py, self.false_exp_index)
+    }
+
+    #[getter]
+    fn typ<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
+        get_typ(&self.cd, py, self.typ_index)
+    }
+
+    #[getter]
+    fn is_question(&self) -> bool {
+        true
+    }
+
+    fn has_variable(&self, py: Python, vid: isize) -> PyResult<bool> {
+        Ok(has_variable(&self.condition(py)?, vid)?
+            || has_variable(&self.true_exp(py)?, vid)?
+            || has_variable(&self.false_exp(py)?, vid)?)
+    }
+
+    fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
+        let mut result = get_strings(&self.condition(py)?)?;
+        result.extend(get_strings(&self.true_exp(py)?)?);
+        result.extend(get_strings(&self.false_exp(py)?)?);
+        Ok(result)
+    }
+
+    fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<isize> {
+        Ok(get_variable_uses(&self.condition(py)?, vid)?
+            + get_variable_uses(&self.true_exp(py)?, vid)?
+            + get_variable_uses(&self.false_exp(py)?, vid)?)
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        Ok(BTreeMap::from([
+            ("base", "question".into_py(py)),
+            ("cond", to_dict(&self.condition(py)?)?),
+            ("true-exp", to_dict(&self.true_exp(py)?)?),
+            ("false-exp", to_dict(&self.false_exp(py)?)?),
+            ("type", to_dict(&self.typ(py)?)?),
+        ]))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "({} ? {} : {})",
+            self.condition(py)?.str()?,
+            self.true_exp(py)?.str()?,
+            self.false_exp(py)?.str()?
+        ))
+    }
+}
+
+impl CDictionaryRecordTrait for CExpQuestion {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpQuestion>("question") }
+
+/// Cast expression.
+///
+/// - args[0]: index of target type in cdictionary
+/// - args[1]: index of expression to be cast in cdictionary
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpCastE {
+    cd: Py<CDictionary>,
+    typ_index: isize,
+    exp_index: isize,
+}
+
+#[pymethods]
+impl CExpCastE {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpCastE {
+            cd: cd.clone().unbind(),
+            typ_index: ixval.arg(0)?,
+            exp_index: ixval.arg(1)?,
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn exp<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CExp>> {
+        get_exp(&self.cd, py, self.exp_index)
+    }
+
+    #[getter]
+    fn typ<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
+        get_typ(&self.cd, py, self.typ_index)
+    }
+
+    #[getter]
+    fn is_caste(&self) -> bool {
+        true
+    }
+
+    fn has_variable(&self, py: Python, vid: isize) -> PyResult<bool> {
+        has_variable(&self.exp(py)?, vid)
+    }
+
+    fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
+        get_strings(&self.exp(py)?)
+    }
+
+    fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<isize> {
+        get_variable_uses(&self.exp(py)?, vid)
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        Ok(BTreeMap::from([
+            ("base", "caste".into_py(py)),
+            ("exp", to_dict(&self.exp(py)?)?),
+            ("type", to_dict(&self.typ(py)?)?),
+        ]))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "caste({},{})",
+            self.typ(py)?.str()?,
+            self.exp(py)?.str()?
+        ))
+    }
+}
+
+impl CDictionaryRecordTrait for CExpCastE {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpCastE>("caste") }
+
+/// Address-of expression.
+///
+/// - args[0]: index of lval in cdictionary
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpAddrOf {
+    cd: Py<CDictionary>,
+    lval_index: isize,
+}
+
+#[pymethods]
+impl CExpAddrOf {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpAddrOf {
+            cd: cd.clone().unbind(),
+            lval_index: ixval.arg(0)?,
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn lval<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CLval>> {
+        get_lval(&self.cd, py, self.lval_index)
+    }
+
+    #[getter]
+    fn is_addrof(&self) -> bool {
+        true
+    }
+
+    fn has_variable(&self, py: Python, vid: isize) -> PyResult<bool> {
+        has_variable(&self.lval(py)?, vid)
+    }
+
+    fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
+        get_strings(&self.lval(py)?)
+    }
+
+    fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<isize> {
+        get_variable_uses(&self.lval(py)?, vid)
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        Ok(BTreeMap::from([
+            ("base", "addrof".into_py(py)),
+            ("lval", to_dict(&self.lval(py)?)?),
+        ]))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(format!("&({})", self.lval(py)?.str()?))
+    }
+}
+
+impl CDictionaryRecordTrait for CExpAddrOf {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpAddrOf>("addrof") }
+
+/// Address-of label expression.
+///
+/// - args[0]: statement sid
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpAddrOfLabel {
+    #[pyo3(get)]
+    label_sid: isize,
+}
+
+#[pymethods]
+impl CExpAddrOfLabel {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpAddrOfLabel {
+            label_sid: ixval.arg(0)?,
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    fn to_dict(&self, py: Python) -> BTreeMap<&'static str, Py<PyAny>> {
+        BTreeMap::from([
+            ("base", "addroflabel".into_py(py)),
+            ("label", self.label_sid.into_py(py)),
+        ])
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self) -> String {
+        format!("addroflabel({})", self.label_sid)
+    }
+}
+
+impl CDictionaryRecordTrait for CExpAddrOfLabel {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpAddrOfLabel>("addroflabel") }
+
+/// Start-of expression.
+///
+/// - args[0]: index of lval in cdictionary
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpStartOf {
+    cd: Py<CDictionary>,
+    lval_index: isize,
+}
+
+#[pymethods]
+impl CExpStartOf {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpStartOf {
+            cd: cd.clone().unbind(),
+            lval_index: ixval.arg(0)?,
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn lval<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CLval>> {
+        get_lval(&self.cd, py, self.lval_index)
+    }
+
+    #[getter]
+    fn is_startof(&self) -> bool {
+        true
+    }
+
+    fn has_variable(&self, py: Python, vid: isize) -> PyResult<bool> {
+        has_variable(&self.lval(py)?, vid)
+    }
+
+    fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
+        get_strings(&self.lval(py)?)
+    }
+
+    fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<isize> {
+        get_variable_uses(&self.lval(py)?, vid)
+    }
+
+    fn to_dict(&self, py: Python) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        Ok(BTreeMap::from([
+            ("base", "startof".into_py(py)),
+            ("lval", to_dict(&self.lval(py)?)?),
+        ]))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(format!("&({})", self.lval(py)?.str()?))
+    }
+}
+
+impl CDictionaryRecordTrait for CExpStartOf {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpStartOf>("startof") }
+
+/// Function application.
+///
+/// - tags[1]: filename
+///
+/// - args[0]: line number
+/// - args[1]: byte number
+/// - args[2]: index of target function expression in cdictionary
+/// - args[3..]: indices of arguments (optional) in cdictionary
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpFnApp {
+    cd: Py<CDictionary>,
+    exp_index: isize,
+    argument_indices: Vec<isize>,
+}
+
+#[pymethods]
+impl CExpFnApp {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpFnApp {
+            cd: cd.clone().unbind(),
+            exp_index: ixval.arg(2)?,
+            argument_indices: ixval.args_from(3)?.to_vec(),
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn exp<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CExp>> {
+        get_exp(&self.cd, py, self.exp_index)
+    }
+
+    #[getter]
+    fn arguments<'a>(&self, py: Python<'a>) -> PyResult<Vec<Option<Bound<'a, CExp>>>> {
+        self.argument_indices
+            .iter()
+            .map(|ix| get_exp_opt(&self.cd, py, *ix))
+            .collect()
+    }
+
+    #[getter]
+    fn is_fn_app(&self) -> bool {
+        true
+    }
+
+    fn has_variable(&self, py: Python, vid: isize) -> PyResult<bool> {
+        if has_variable(&self.exp(py)?, vid)? {
+            return Ok(true);
+        }
+        for argument in self.arguments(py)?.into_iter().flatten() {
+            if has_variable(&argument, vid)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
+        let mut result = get_strings(&self.exp(py)?)?;
+        for argument in self.arguments(py)?.into_iter().flatten() {
+            result.extend(get_strings(&argument)?);
+        }
+        Ok(result)
+    }
+
+    fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<isize> {
+        let mut result = get_variable_uses(&self.exp(py)?, vid)?;
+        for argument in self.arguments(py)?.into_iter().flatten() {
+            result += get_variable_uses(&argument, vid)?;
+        }
+        Ok(result)
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        let arguments = self
+            .arguments(py)?
+            .iter()
+            .map(|argument| match argument {
+                Some(argument) => Ok(argument.str()?.to_string()),
+                None => Ok("None".to_string()),
+            })
+            .collect::<PyResult<Vec<String>>>()?;
+        Ok(format!(
+            "fnapp({}({}))",
+            self.exp(py)?.str()?,
+            arguments.join(", ")
+        ))
+    }
+}
+
+impl CDictionaryRecordTrait for CExpFnApp {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpFnApp>("fnapp") }
+
+/// Constant function application.
+///
+/// - tags[1]: name
+///
+/// - args[0]: index of result type in cdictionary
+/// - args[1..]: indices of arguments (optional) in cdictionary
+#[pyclass(extends = CExp, frozen, subclass)]
+pub struct CExpCnApp {
+    cd: Py<CDictionary>,
+    #[pyo3(get)]
+    name: String,
+    typ_index: isize,
+    argument_indices: Vec<isize>,
+}
+
+#[pymethods]
+impl CExpCnApp {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let exp = CExpCnApp {
+            cd: cd.clone().unbind(),
+            name: ixval.tag(1)?.to_string(),
+            typ_index: ixval.arg(0)?,
+            argument_indices: ixval.args_from(1)?.to_vec(),
+        };
+        Ok(PyClassInitializer::from(CExp::new(cd.clone().unbind(), ixval)).add_subclass(exp))
+    }
+
+    #[getter]
+    fn typ<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
+        get_typ(&self.cd, py, self.typ_index)
+    }
+
+    #[getter]
+    fn arguments<'a>(&self, py: Python<'a>) -> PyResult<Vec<Option<Bound<'a, CExp>>>> {
+        self.argument_indices
+            .iter()
+            .map(|ix| get_exp_opt(&self.cd, py, *ix))
+            .collect()
+    }
+
+    #[getter]
+    fn is_cn_app(&self) -> bool {
+        true
+    }
+
+    fn has_variable(&self, py: Python, vid: isize) -> PyResult<bool> {
+        for argument in self.arguments(py)?.into_iter().flatten() {
+            if has_variable(&argument, vid)? {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
+        let mut result = Vec::new();
+        for argument in self.arguments(py)?.into_iter().flatten() {
+            result.extend(get_strings(&argument)?);
+        }
+        Ok(result)
+    }
+
+    fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<isize> {
+        let mut result = 0;
+        for argument in self.arguments(py)?.into_iter().flatten() {
+            result += get_variable_uses(&argument, vid)?;
+        }
+        Ok(result)
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        let arguments = self
+            .arguments(py)?
+            .iter()
+            .map(|argument| match argument {
+                Some(argument) => Ok(argument.str()?.to_string()),
+                None => Ok("None".to_string()),
+            })
+            .collect::<PyResult<Vec<String>>>()?;
+        Ok(format!("cnapp({}({})", self.name, arguments.join(",")))
+    }
+}
+
+impl CDictionaryRecordTrait for CExpCnApp {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CExp, CExpCnApp>("cnapp") }
@@ -0,0 +1,461 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Evaluation of integer constant expressions.
+//!
+//! Expressions are evaluated with the C rules for the integer kinds of the
+//! data model: operands are promoted and converted to a common kind, unsigned
+//! arithmetic wraps around, and conversions to a signed kind wrap around as
+//! gcc does. Signed overflow, division by zero and out-of-range shifts are
+//! undefined behavior, so such expressions have no value. Plain `char` is
+//! taken to be signed.
+
+use pyo3::{intern, prelude::*, types::PyString};
+
+use crate::app::{
+    c_data_model::CDataModel,
+    c_exp::CExp,
+    c_typ::{CTyp, CTypEnum, CTypInt},
+};
+
+/// Value of an integer constant expression with its integer kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IntValue {
+    pub value: i128,
+    pub ikind: &'static str,
+}
+
+/// Value of `exp` in `model`, or None if it is not an integer constant
+/// expression or its evaluation is undefined.
+pub fn evaluate(exp: &Bound<CExp>, model: &CDataModel) -> PyResult<Option<IntValue>> {
+    ConstEvaluator { model }.exp(exp.as_any())
+}
+
+/// Properties of an integer kind: canonical name, conversion rank and
+/// signedness.
+#[derive(Clone, Copy)]
+struct IKind {
+    name: &'static str,
+    rank: u8,
+    signed: bool,
+}
+
+const IKINDS: [IKind; 12] = [
+    IKind {
+        name: "ibool",
+        rank: 0,
+        signed: false,
+    },
+    IKind {
+        name: "ichar",
+        rank: 1,
+        signed: true,
+    },
+    IKind {
+        name: "ischar",
+        rank: 1,
+        signed: true,
+    },
+    IKind {
+        name: "iuchar",
+        rank: 1,
+        signed: false,
+    },
+    IKind {
+        name: "ishort",
+        rank: 2,
+        signed: true,
+    },
+    IKind {
+        name: "iushort",
+        rank: 2,
+        signed: false,
+    },
+    IKind {
+        name: "iint",
+        rank: 3,
+        signed: true,
+    },
+    IKind {
+        name: "iuint",
+        rank: 3,
+        signed: false,
+    },
+    IKind {
+        name: "ilong",
+        rank: 4,
+        signed: true,
+    },
+    IKind {
+        name: "iulong",
+        rank: 4,
+        signed: false,
+    },
+    IKind {
+        name: "ilonglong",
+        rank: 5,
+        signed: true,
+    },
+    IKind {
+        name: "iulonglong",
+        rank: 5,
+        signed: false,
+    },
+];
+
+/// Kind of `int`, the kind of promoted small integers, character and
+/// enumeration constants, and comparisons.
+const INT: IKind = IKINDS[6];
+
+fn ikind(name: &str) -> Option<IKind> {
+    IKINDS.iter().find(|ikind| ikind.name == name).copied()
+}
+
+/// Unsigned kind with the same rank as `ikind`.
+fn unsigned(ikind: IKind) -> IKind {
+    IKINDS
+        .iter()
+        .find(|other| other.rank == ikind.rank && !other.signed)
+        .copied()
+        .unwrap_or(ikind)
+}
+
+fn flag(item: &Bound<PyAny>, name: &Bound<PyString>) -> PyResult<bool> {
+    item.getattr(name)?.extract()
+}
+
+struct ConstEvaluator<'m> {
+    model: &'m CDataModel,
+}
+
+impl ConstEvaluator<'_> {
+    /// Number of value bits of `ikind` (128-bit kinds are not supported).
+    fn width(&self, ikind: IKind) -> Option<u32> {
+        if ikind.name == "ibool" {
+            return Some(1);
+        }
+        let size = self.model.ikind_size(ikind.name)?;
+        u32::try_from(8 * size).ok().filter(|width| *width < 128)
+    }
+
+    fn range(&self, ikind: IKind) -> Option<(i128, i128)> {
+        let width = self.width(ikind)?;
+        if ikind.signed {
+            Some((-(1 << (width - 1)), (1 << (width - 1)) - 1))
+        } else {
+            Some((0, (1 << width) - 1))
+        }
+    }
+
+    fn fits(&self, value: i128, ikind: IKind) -> bool {
+        self.range(ikind)
+            .is_some_and(|(min, max)| min <= value && value <= max)
+    }
+
+    /// `value` converted to `ikind`, wrapping around if it does not fit.
+    fn convert(&self, value: i128, ikind: IKind) -> Option<IntValue> {
+        if ikind.name == "ibool" {
+            return Some(IntValue {
+                value: i128::from(value != 0),
+                ikind: ikind.name,
+            });
+        }
+        let width = self.width(ikind)?;
+        let mut value = value.rem_euclid(1 << width);
+        if ikind.signed && value >= 1 << (width - 1) {
+            value -= 1 << width;
+        }
+        Some(IntValue {
+            value,
+            ikind: ikind.name,
+        })
+    }
+
+    /// Result of an arithmetic operation in `ikind`: unsigned results wrap
+    /// around, signed overflow has no value.
+    fn arithmetic(&self, value: i128, ikind: IKind) -> Option<IntValue> {
+        if ikind.signed {
+            self.fits(value, ikind).then_some(IntValue {
+                value,
+                ikind: ikind.name,
+            })
+        } else {
+            self.convert(value, ikind)
+        }
+    }
+
+    /// Integer promotion: kinds of lower rank than int become int if int
+    /// can represent all their values, and unsigned int otherwise.
+    fn promote(&self, value: IntValue) -> Option<(i128, IKind)> {
+        let kind = ikind(value.ikind)?;
+        let int = INT;
+        if kind.rank >= int.rank {
+            return Some((value.value, kind));
+        }
+        let (min, max) = self.range(kind)?;
+        if self.fits(min, int) && self.fits(max, int) {
+            Some((value.value, int))
+        } else {
+            Some((value.value, unsigned(int)))
+        }
+    }
+
+    /// Common kind of two promoted operands (usual arithmetic conversions).
+    fn common(&self, kind1: IKind, kind2: IKind) -> Option<IKind> {
+        if kind1.name == kind2.name {
+            return Some(kind1);
+        }
+        if kind1.signed == kind2.signed {
+            return Some(if kind1.rank >= kind2.rank {
+                kind1
+            } else {
+                kind2
+            });
+        }
+        let (signed, unsigned_kind) = if kind1.signed {
+            (kind1, kind2)
+        } else {
+            (kind2, kind1)
+        };
+        if unsigned_kind.rank >= signed.rank {
+            return Some(unsigned_kind);
+        }
+        let (min, max) = self.range(unsigned_kind)?;
+        if self.fits(min, signed) && self.fits(max, signed) {
+            Some(signed)
+        } else {
+            Some(unsigned(signed))
+        }
+    }
+
+    /// Operands converted to their common kind.
+    fn operands(&self, value1: IntValue, value2: IntValue) -> Option<(i128, i128, IKind)> {
+        let (value1, kind1) = self.promote(value1)?;
+        let (value2, kind2) = self.promote(value2)?;
+        let kind = self.common(kind1, kind2)?;
+        Some((
+            self.convert(value1, kind)?.value,
+            self.convert(value2, kind)?.value,
+            kind,
+        ))
+    }
+
+    fn int(&self, value: bool) -> IntValue {
+        IntValue {
+            value: i128::from(value),
+            ikind: INT.name,
+        }
+    }
+
+    /// Integer kind of `typ`, or None if it is not an integer type.
+    fn typ_ikind(&self, typ: &Bound<PyAny>) -> PyResult<Option<IKind>> {
+        let typ = CTyp::unroll(typ.downcast::<CTyp>()?)?;
+        if let Ok(int) = typ.downcast::<CTypInt>() {
+            Ok(ikind(int.get().ikind()))
+        } else if let Ok(typ) = typ.downcast::<CTypEnum>() {
+            Ok(CTypEnum::ikind(typ)?.and_then(|kind| ikind(&kind)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Kind of `size_t`: the unsigned kind with the size of a pointer.
+    fn size_t(&self) -> &'static str {
+        let pointer_size = Some(self.model.pointer_layout().size);
+        if pointer_size == self.model.ikind_size("iuint") {
+            "iuint"
+        } else if pointer_size == self.model.ikind_size("iulong") {
+            "iulong"
+        } else {
+            "iulonglong"
+        }
+    }
+
+    fn size_value(&self, size: isize) -> IntValue {
+        IntValue {
+            value: size as i128,
+            ikind: self.size_t(),
+        }
+    }
+
+    fn exp(&self, exp: &Bound<PyAny>) -> PyResult<Option<IntValue>> {
+        let py = exp.py();
+        let get = |name: &Bound<PyString>| exp.getattr(name);
+        if flag(exp, intern!(py, "is_constant"))? {
+            self.constant(&get(intern!(py, "constant"))?)
+        } else if flag(exp, intern!(py, "is_sizeof"))? {
+            let typ = get(intern!(py, "typ"))?;
+            let layout = CTyp::layout(typ.downcast::<CTyp>()?, self.model)?;
+            Ok(layout.map(|layout| self.size_value(layout.size)))
+        } else if flag(exp, intern!(py, "is_alignof"))? {
+            let typ = get(intern!(py, "typ"))?;
+            let layout = CTyp::layout(typ.downcast::<CTyp>()?, self.model)?;
+            Ok(layout.map(|layout| self.size_value(layout.align)))
+        } else if flag(exp, intern!(py, "is_sizeofstr"))? {
+            let strg: String = get(intern!(py, "stringvalue"))?.extract()?;
+            Ok(Some(self.size_value(strg.len() as isize + 1)))
+        } else if flag(exp, intern!(py, "is_caste"))? {
+            let Some(kind) = self.typ_ikind(&get(intern!(py, "typ"))?)? else {
+                return Ok(None);
+            };
+            let Some(value) = self.exp(&get(intern!(py, "exp"))?)? else {
+                return Ok(None);
+            };
+            Ok(self.convert(value.value, kind))
+        } else if flag(exp, intern!(py, "is_unop"))? {
+            let op: String = get(intern!(py, "op"))?.extract()?;
+            let Some(value) = self.exp(&get(intern!(py, "exp"))?)? else {
+                return Ok(None);
+            };
+            Ok(self.unop(&op, value))
+        } else if flag(exp, intern!(py, "is_binop"))? {
+            let op: String = get(intern!(py, "op"))?.extract()?;
+            let Some(value1) = self.exp(&get(intern!(py, "exp1"))?)? else {
+                return Ok(None);
+            };
+            // The second operand of && and || is not evaluated if the first
+            // determines the result
+            match (op.as_str(), value1.value != 0) {
+                ("land", false) => return Ok(Some(self.int(false))),
+                ("lor", true) => return Ok(Some(self.int(true))),
+                _ => {}
+            }
+            let Some(value2) = self.exp(&get(intern!(py, "exp2"))?)? else {
+                return Ok(None);
+            };
+            Ok(self.binop(&op, value1, value2))
+        } else if flag(exp, intern!(py, "is_question"))? {
+            let Some(condition) = self.exp(&get(intern!(py, "condition"))?)? else {
+                return Ok(None);
+            };
+            let (chosen, other) = if condition.value != 0 {
+                (intern!(py, "true_exp"), intern!(py, "false_exp"))
+            } else {
+                (intern!(py, "false_exp"), intern!(py, "true_exp"))
+            };
+            let Some(value) = self.exp(&get(chosen)?)? else {
+                return Ok(None);
+            };
+            // The result has the common kind of both branches
+            match self.exp(&get(other)?)? {
+                Some(other) => Ok(self.promote(other).zip(self.promote(value)).and_then(
+                    |((_, kind1), (value, kind2))| self.convert(value, self.common(kind1, kind2)?),
+                )),
+                None => Ok(Some(value)),
+            }
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn constant(&self, constant: &Bound<PyAny>) -> PyResult<Option<IntValue>> {
+        let py = constant.py();
+        if flag(constant, intern!(py, "is_int"))? {
+            let value: i128 = constant.getattr(intern!(py, "intvalue"))?.extract()?;
+            let ikind_name: String = constant.getattr(intern!(py, "ikind"))?.extract()?;
+            Ok(ikind(&ikind_name).and_then(|kind| self.convert(value, kind)))
+        } else if flag(constant, intern!(py, "is_chr"))? {
+            // Character constants have type int
+            let value: i128 = constant.getattr(intern!(py, "chrvalue"))?.extract()?;
+            Ok(Some(IntValue {
+                value,
+                ikind: INT.name,
+            }))
+        } else if flag(constant, intern!(py, "is_enum"))? {
+            // Enumeration constants have type int
+            let Some(value) = self.exp(&constant.getattr(intern!(py, "exp"))?)? else {
+                return Ok(None);
+            };
+            Ok(self.convert(value.value, INT))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn unop(&self, op: &str, value: IntValue) -> Option<IntValue> {
+        if op == "lnot" {
+            return Some(self.int(value.value == 0));
+        }
+        let (value, kind) = self.promote(value)?;
+        match op {
+            "neg" => self.arithmetic(-value, kind),
+            "bnot" => self.convert(!value, kind),
+            _ => None,
+        }
+    }
+
+    fn binop(&self, op: &str, value1: IntValue, value2: IntValue) -> Option<IntValue> {
+        match op {
+            "land" | "lor" => return Some(self.int(value2.value != 0)),
+            "shiftlt" | "shiftrt" => return self.shift(op, value1, value2),
+            _ => {}
+        }
+        let (left, right, kind) = self.operands(value1, value2)?;
+        match op {
+            "plusa" => self.arithmetic(left + right, kind),
+            "minusa" => self.arithmetic(left - right, kind),
+            "mult" if kind.signed => self.arithmetic(left.checked_mul(right)?, kind),
+            // Wrapping modulo 2^128 keeps the value modulo 2^width
+            "mult" => self.convert(left.wrapping_mul(right), kind),
+            "div" if right != 0 => self.arithmetic(left / right, kind),
+            "mod" if right != 0 => {
+                // The quotient must be representable as well
+                self.arithmetic(left / right, kind)?;
+                self.arithmetic(left % right, kind)
+            }
+            "band" => self.convert(left & right, kind),
+            "bor" => self.convert(left | right, kind),
+            "bxor" => self.convert(left ^ right, kind),
+            "lt" => Some(self.int(left < right)),
+            "gt" => Some(self.int(left > right)),
+            "le" => Some(self.int(left <= right)),
+            "ge" => Some(self.int(left >= right)),
+            "eq" => Some(self.int(left == right)),
+            "ne" => Some(self.int(left != right)),
+            // Division by zero and pointer arithmetic
+            _ => None,
+        }
+    }
+
+    /// Shifts; the kind of the result is that of the promoted left operand.
+    fn shift(&self, op: &str, value1: IntValue, value2: IntValue) -> Option<IntValue> {
+        let (value, kind) = self.promote(value1)?;
+        let (count, _) = self.promote(value2)?;
+        let width = self.width(kind)?;
+        let count = u32::try_from(count).ok().filter(|count| *count < width)?;
+        if op == "shiftrt" {
+            return self.convert(value >> count, kind);
+        }
+        if kind.signed && value < 0 {
+            return None;
+        }
+        self.arithmetic(value << count, kind)
+    }
+}
@@ -0,0 +1,396 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Rewriting of expressions and lvalues.
+//!
+//! A rewrite rebuilds an expression bottom-up. Nodes whose subterms changed
+//! are interned in the cdictionary of the expression with `mk_exp_index`,
+//! `mk_lval_index`, `mk_lhost_index` and `mk_offset_index`; unchanged
+//! subterms keep their records. Types are not rewritten.
+
+use std::collections::HashMap;
+
+use pyo3::{intern, prelude::*, types::PyString};
+
+use crate::{
+    app::{c_exp::CExp, c_lval::CLval, c_offset::COffset, c_typ::CTyp, c_typ_compat},
+    util::indexed_table::IndexedTableValue,
+};
+
+pyo3::import_exception!(chc.util.fileutil, CHCError);
+
+/// Hooks of a rewrite. The `pre_` hooks are called before the subterms of
+/// a node are rewritten; a replacement returned by them is used as is. The
+/// `post_` hooks are called with the node rebuilt from the rewritten
+/// subterms.
+pub trait Rewrite<'py> {
+    fn pre_exp(&mut self, _exp: &Bound<'py, CExp>) -> PyResult<Option<Bound<'py, CExp>>> {
+        Ok(None)
+    }
+
+    fn post_exp(&mut self, exp: Bound<'py, CExp>) -> PyResult<Bound<'py, CExp>> {
+        Ok(exp)
+    }
+
+    fn pre_lval(&mut self, _lval: &Bound<'py, CLval>) -> PyResult<Option<Bound<'py, CLval>>> {
+        Ok(None)
+    }
+
+    fn post_lval(&mut self, lval: Bound<'py, CLval>) -> PyResult<Bound<'py, CLval>> {
+        Ok(lval)
+    }
+}
+
+/// `exp` rewritten with `rewrite`.
+pub fn rewrite_exp<'py, R: Rewrite<'py>>(
+    rewrite: &mut R,
+    exp: &Bound<'py, CExp>,
+) -> PyResult<Bound<'py, CExp>> {
+    if let Some(replacement) = rewrite.pre_exp(exp)? {
+        return Ok(replacement);
+    }
+    let py = exp.py();
+    let item = exp.as_any();
+    let mut args = record(item)?.args().to_vec();
+    if flag(item, intern!(py, "is_lval"))?
+        || flag(item, intern!(py, "is_addrof"))?
+        || flag(item, intern!(py, "is_startof"))?
+    {
+        let lval = item.getattr(intern!(py, "lval"))?.downcast_into()?;
+        let lval = rewrite_lval(rewrite, &lval)?;
+        set_arg(item, &mut args, 0, index(lval.as_any())?)?;
+    } else if flag(item, intern!(py, "is_sizeofe"))?
+        || flag(item, intern!(py, "is_alignofe"))?
+        || flag(item, intern!(py, "is_unop"))?
+    {
+        let arg = rewrite_subexp(rewrite, item, intern!(py, "exp"))?;
+        set_arg(item, &mut args, 0, arg)?;
+    } else if flag(item, intern!(py, "is_binop"))? {
+        let arg = rewrite_subexp(rewrite, item, intern!(py, "exp1"))?;
+        set_arg(item, &mut args, 0, arg)?;
+        let arg = rewrite_subexp(rewrite, item, intern!(py, "exp2"))?;
+        set_arg(item, &mut args, 1, arg)?;
+    } else if flag(item, intern!(py, "is_question"))? {
+        let arg = rewrite_subexp(rewrite, item, intern!(py, "condition"))?;
+        set_arg(item, &mut args, 0, arg)?;
+        let arg = rewrite_subexp(rewrite, item, intern!(py, "true_exp"))?;
+        set_arg(item, &mut args, 1, arg)?;
+        let arg = rewrite_subexp(rewrite, item, intern!(py, "false_exp"))?;
+        set_arg(item, &mut args, 2, arg)?;
+    } else if flag(item, intern!(py, "is_caste"))? {
+        let arg = rewrite_subexp(rewrite, item, intern!(py, "exp"))?;
+        set_arg(item, &mut args, 1, arg)?;
+    } else if flag(item, intern!(py, "is_fn_app"))? {
+        // args[0] and args[1] are the location of the call
+        let arg = rewrite_subexp(rewrite, item, intern!(py, "exp"))?;
+        set_arg(item, &mut args, 2, arg)?;
+        args.truncate(3);
+        args.extend(rewrite_arguments(rewrite, item)?);
+    } else if flag(item, intern!(py, "is_cn_app"))? {
+        args.truncate(1);
+        args.extend(rewrite_arguments(rewrite, item)?);
+    }
+    let exp = rebuild(item, intern!(py, "exp"), args)?.downcast_into()?;
+    rewrite.post_exp(exp)
+}
+
+/// `lval` rewritten with `rewrite`.
+pub fn rewrite_lval<'py, R: Rewrite<'py>>(
+    rewrite: &mut R,
+    lval: &Bound<'py, CLval>,
+) -> PyResult<Bound<'py, CLval>> {
+    if let Some(replacement) = rewrite.pre_lval(lval)? {
+        return Ok(replacement);
+    }
+    let py = lval.py();
+    let item = lval.as_any();
+    let lhost = item.getattr(intern!(py, "lhost"))?;
+    let mut lhost_args = record(&lhost)?.args().to_vec();
+    if flag(&lhost, intern!(py, "is_mem"))? {
+        let exp = rewrite_subexp(rewrite, &lhost, intern!(py, "exp"))?;
+        set_arg(&lhost, &mut lhost_args, 0, exp)?;
+    }
+    let lhost = rebuild(&lhost, intern!(py, "lhost"), lhost_args)?;
+    let offset = rewrite_offset(rewrite, &item.getattr(intern!(py, "offset"))?)?;
+    let args = vec![index(&lhost)?, index(&offset)?];
+    let lval = rebuild(item, intern!(py, "lval"), args)?.downcast_into()?;
+    rewrite.post_lval(lval)
+}
+
+fn rewrite_offset<'py, R: Rewrite<'py>>(
+    rewrite: &mut R,
+    offset: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py = offset.py();
+    if flag(offset, intern!(py, "is_no_offset"))? {
+        return Ok(offset.clone());
+    }
+    let mut args = record(offset)?.args().to_vec();
+    if flag(offset, intern!(py, "is_index"))? {
+        let index_exp = rewrite_subexp(rewrite, offset, intern!(py, "index_exp"))?;
+        set_arg(offset, &mut args, 0, index_exp)?;
+    }
+    let suboffset = rewrite_offset(rewrite, &offset.getattr(intern!(py, "offset"))?)?;
+    set_arg(offset, &mut args, 1, index(&suboffset)?)?;
+    rebuild(offset, intern!(py, "offset"), args)
+}
+
+/// Index of the rewritten subexpression `name` of `item`.
+fn rewrite_subexp<'py, R: Rewrite<'py>>(
+    rewrite: &mut R,
+    item: &Bound<'py, PyAny>,
+    name: &Bound<PyString>,
+) -> PyResult<isize> {
+    let exp = item.getattr(name)?.downcast_into()?;
+    index(rewrite_exp(rewrite, &exp)?.as_any())
+}
+
+/// Indices of the rewritten arguments of an application (-1 if unknown).
+fn rewrite_arguments<'py, R: Rewrite<'py>>(
+    rewrite: &mut R,
+    exp: &Bound<'py, PyAny>,
+) -> PyResult<Vec<isize>> {
+    let mut result = Vec::new();
+    for argument in exp.getattr(intern!(exp.py(), "arguments"))?.iter()? {
+        let argument = argument?;
+        if argument.is_none() {
+            result.push(-1);
+        } else {
+            let argument = argument.downcast_into()?;
+            result.push(index(rewrite_exp(rewrite, &argument)?.as_any())?);
+        }
+    }
+    Ok(result)
+}
+
+/// `offset1` followed by `offset2`.
+pub fn append_offset<'py>(
+    offset1: &Bound<'py, COffset>,
+    offset2: &Bound<'py, COffset>,
+) -> PyResult<Bound<'py, COffset>> {
+    let py = offset1.py();
+    let item = offset1.as_any();
+    if flag(item, intern!(py, "is_no_offset"))? {
+        return Ok(offset2.clone());
+    }
+    let suboffset = item.getattr(intern!(py, "offset"))?.downcast_into()?;
+    let mut args = record(item)?.args().to_vec();
+    let suboffset = append_offset(&suboffset, offset2)?;
+    set_arg(item, &mut args, 1, index(suboffset.as_any())?)?;
+    Ok(rebuild(item, intern!(py, "offset"), args)?.downcast_into()?)
+}
+
+/// `lval` with `offset` appended to its offset.
+fn extend_lval<'py>(
+    lval: &Bound<'py, CLval>,
+    offset: &Bound<'py, COffset>,
+) -> PyResult<Bound<'py, CLval>> {
+    let py = lval.py();
+    let item = lval.as_any();
+    let own_offset = item.getattr(intern!(py, "offset"))?.downcast_into()?;
+    let mut args = record(item)?.args().to_vec();
+    let offset = append_offset(&own_offset, offset)?;
+    set_arg(item, &mut args, 1, index(offset.as_any())?)?;
+    Ok(rebuild(item, intern!(py, "lval"), args)?.downcast_into()?)
+}
+
+/// Record of `item` with `args` in the table `table` of its cdictionary;
+/// `item` itself if its arguments are unchanged.
+fn rebuild<'py>(
+    item: &Bound<'py, PyAny>,
+    table: &Bound<PyString>,
+    args: Vec<isize>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let py = item.py();
+    let ixval = record(item)?;
+    if ixval.args() == args.as_slice() {
+        return Ok(item.clone());
+    }
+    let cd = item.getattr(intern!(py, "cd"))?;
+    let index = cd.call_method1(
+        format!("mk_{table}_index").as_str(),
+        (ixval.tags().to_vec(), args),
+    )?;
+    cd.call_method1(format!("get_{table}").as_str(), (index,))
+}
+
+/// Sets argument `n` of `args`, the arguments of the record `item` that is
+/// rebuilt.
+fn set_arg(item: &Bound<PyAny>, args: &mut [isize], n: usize, value: isize) -> PyResult<()> {
+    match args.get_mut(n) {
+        Some(arg) => {
+            *arg = value;
+            Ok(())
+        }
+        None => Err(record(item)?.too_short("argument", n)),
+    }
+}
+
+fn record<'a, 'py>(item: &'a Bound<'py, PyAny>) -> PyResult<&'a IndexedTableValue> {
+    Ok(item.downcast::<IndexedTableValue>()?.get())
+}
+
+fn index(item: &Bound<PyAny>) -> PyResult<isize> {
+    Ok(record(item)?.index())
+}
+
+fn flag(item: &Bound<PyAny>, name: &Bound<PyString>) -> PyResult<bool> {
+    item.getattr(name)?.extract()
+}
+
+/// Variable id of `lval` if it is a variable without offset.
+fn variable(lval: &Bound<PyAny>) -> PyResult<Option<isize>> {
+    let py = lval.py();
+    let lhost = lval.getattr(intern!(py, "lhost"))?;
+    if !flag(&lhost, intern!(py, "is_var"))? {
+        return Ok(None);
+    }
+    Ok(Some(lhost.getattr(intern!(py, "vid"))?.extract()?))
+}
+
+/// Simultaneous substitution of expressions for variables.
+///
+/// A variable that is used as an lvalue with an offset, or whose address is
+/// taken, can only be replaced by an lvalue expression.
+pub struct Substitution<'py> {
+    subst: HashMap<isize, Bound<'py, CExp>>,
+}
+
+impl<'py> Substitution<'py> {
+    /// Substitution of `subst`; the expressions must belong to the
+    /// cdictionary `cd`.
+    pub fn new(cd: &Bound<'py, PyAny>, subst: HashMap<isize, Bound<'py, CExp>>) -> PyResult<Self> {
+        for exp in subst.values() {
+            if !exp.as_any().getattr(intern!(cd.py(), "cd"))?.is(cd) {
+                return Err(CHCError::new_err(format!(
+                    "Expression {} belongs to a different dictionary",
+                    exp.str()?
+                )));
+            }
+        }
+        Ok(Substitution { subst })
+    }
+
+    fn replacement(&self, lval: &Bound<'py, PyAny>) -> PyResult<Option<&Bound<'py, CExp>>> {
+        Ok(variable(lval)?.and_then(|vid| self.subst.get(&vid)))
+    }
+}
+
+impl<'py> Rewrite<'py> for Substitution<'py> {
+    fn pre_exp(&mut self, exp: &Bound<'py, CExp>) -> PyResult<Option<Bound<'py, CExp>>> {
+        let py = exp.py();
+        if !flag(exp.as_any(), intern!(py, "is_lval"))? {
+            return Ok(None);
+        }
+        let lval = exp.as_any().getattr(intern!(py, "lval"))?;
+        let offset = lval.getattr(intern!(py, "offset"))?;
+        if !flag(&offset, intern!(py, "is_no_offset"))? {
+            return Ok(None);
+        }
+        Ok(self.replacement(&lval)?.cloned())
+    }
+
+    fn pre_lval(&mut self, lval: &Bound<'py, CLval>) -> PyResult<Option<Bound<'py, CLval>>> {
+        let py = lval.py();
+        let Some(replacement) = self.replacement(lval.as_any())? else {
+            return Ok(None);
+        };
+        if !flag(replacement.as_any(), intern!(py, "is_lval"))? {
+            return Err(CHCError::new_err(format!(
+                "Cannot substitute {} in lvalue {}",
+                replacement.str()?,
+                lval.str()?
+            )));
+        }
+        let target = replacement.as_any().getattr(intern!(py, "lval"))?;
+        let offset = lval.as_any().getattr(intern!(py, "offset"))?;
+        Ok(Some(extend_lval(target.downcast()?, offset.downcast()?)?))
+    }
+}
+
+/// Simplification of `*(&x)` to `x`, `&(*p)` to `p`, and removal of casts
+/// to the type that the operand already has.
+pub struct Normalization;
+
+impl<'py> Rewrite<'py> for Normalization {
+    fn post_exp(&mut self, exp: Bound<'py, CExp>) -> PyResult<Bound<'py, CExp>> {
+        let py = exp.py();
+        let item = exp.as_any();
+        if flag(item, intern!(py, "is_addrof"))? {
+            let lval = item.getattr(intern!(py, "lval"))?;
+            let lhost = lval.getattr(intern!(py, "lhost"))?;
+            let offset = lval.getattr(intern!(py, "offset"))?;
+            if flag(&lhost, intern!(py, "is_mem"))? && flag(&offset, intern!(py, "is_no_offset"))? {
+                return Ok(lhost.getattr(intern!(py, "exp"))?.downcast_into()?);
+            }
+        } else if flag(item, intern!(py, "is_caste"))? {
+            let operand = item.getattr(intern!(py, "exp"))?;
+            if let Some(typ) = exp_type(&operand)? {
+                let target = item.getattr(intern!(py, "typ"))?;
+                if c_typ_compat::identical(target.downcast()?, &typ)? {
+                    return Ok(operand.downcast_into()?);
+                }
+            }
+        }
+        Ok(exp)
+    }
+
+    fn post_lval(&mut self, lval: Bound<'py, CLval>) -> PyResult<Bound<'py, CLval>> {
+        let py = lval.py();
+        let lhost = lval.as_any().getattr(intern!(py, "lhost"))?;
+        if !flag(&lhost, intern!(py, "is_mem"))? {
+            return Ok(lval);
+        }
+        let address = lhost.getattr(intern!(py, "exp"))?;
+        if !flag(&address, intern!(py, "is_addrof"))? {
+            return Ok(lval);
+        }
+        let target = address.getattr(intern!(py, "lval"))?;
+        let offset = lval.as_any().getattr(intern!(py, "offset"))?;
+        extend_lval(target.downcast()?, offset.downcast()?)
+    }
+}
+
+/// Type of `exp` if it is recorded in the expression.
+fn exp_type<'py>(exp: &Bound<'py, PyAny>) -> PyResult<Option<Bound<'py, CTyp>>> {
+    let py = exp.py();
+    for name in [
+        intern!(py, "is_unop"),
+        intern!(py, "is_binop"),
+        intern!(py, "is_question"),
+        intern!(py, "is_caste"),
+        intern!(py, "is_cn_app"),
+    ] {
+        if flag(exp, name)? {
+            return Ok(Some(exp.getattr(intern!(py, "typ"))?.downcast_into()?));
+        }
+    }
+    Ok(None)
+}
@@ -34,9 +34,13 @@ use pyo3::{intern, prelude::*};
 
 use crate::{
     app::{
-        c_attributes::CAttributes, c_declarations::CDeclarations,
-        c_dictionary_record::CDeclarationsRecord, c_file_declarations::CFileDeclarations,
-        c_location::CLocation, c_typ::CTyp,
+        c_attributes::CAttributes,
+        c_data_model::{data_model, CDataModel, FieldShape, LayoutAttributes},
+        c_declarations::CDeclarations,
+        c_dictionary_record::CDeclarationsRecord,
+        c_file_declarations::CFileDeclarations,
+        c_location::CLocation,
+        c_typ::CTyp,
     },
     util::indexed_table::IndexedTableValue,
 };
@@ -72,7 +76,7 @@ impl CFieldInfo {
     }
 
     #[getter]
-    fn ftype<'a, 'b>(slf: &'a Bound<'b, Self>) -> PyResult<Bound<'b, CTyp>> {
+    pub fn ftype<'a, 'b>(slf: &'a Bound<'b, Self>) -> PyResult<Bound<'b, CTyp>> {
         let c_decl_record = slf.borrow().into_super();
         let dictionary = c_decl_record.dictionary(slf.py())?;
         let args_1 = c_decl_record.into_super().args()[1];
@@ -82,16 +86,15 @@ impl CFieldInfo {
     }
 
     #[getter]
-    fn bitfield(slf: PyRef<Self>) -> isize {
+    pub fn bitfield(slf: PyRef<Self>) -> isize {
         slf.into_super().into_super().args()[2]
     }
 
-    // Unvalidated
+    /// Size in bytes of the field type in the current data model, or None
+    /// if the type has no size.
     #[getter]
-    pub fn size(slf: &Bound<Self>) -> PyResult<isize> {
-        Ok(CFieldInfo::ftype(slf)?
-            .call_method0(intern!(slf.py(), "size"))?
-            .extract()?)
+    pub fn size(slf: &Bound<Self>) -> PyResult<Option<isize>> {
+        Ok(CTyp::layout(&Self::ftype(slf)?, &data_model())?.map(|layout| layout.size))
     }
 
     // Unvalidated
@@ -140,3 +143,22 @@ impl CFieldInfo {
         ))
     }
 }
+
+impl CFieldInfo {
+    /// What the layout of the struct needs to know about this field; `packed`
+    /// is true if the struct is packed.
+    pub fn shape(slf: &Bound<Self>, model: &CDataModel, packed: bool) -> PyResult<FieldShape> {
+        let ftype = Self::ftype(slf)?;
+        let mut attributes = match Self::attributes(slf)? {
+            Some(attributes) => attributes.get().layout_attributes(slf.py(), model)?,
+            None => LayoutAttributes::default(),
+        };
+        attributes.packed |= packed;
+        Ok(FieldShape {
+            layout: CTyp::layout(&ftype, model)?,
+            bitfield: Self::bitfield(slf.borrow()),
+            flexible_align: CTyp::flexible_array_align(&ftype, model)?,
+            attributes,
+        })
+    }
+}
@@ -28,7 +28,28 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 ------------------------------------------------------------------------------
 */
-use pyo3::prelude::*;
+//! C-file main access point.
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use once_cell::sync::OnceCell;
+use pyo3::{
+    exceptions::PyException,
+    intern,
+    prelude::*,
+    types::{PyDict, PyString},
+};
+
+use crate::{
+    app::c_function::{cached, clear, fileutil, python_class},
+    util::{dictionary_cache::read_xml_tables_cached, dictionary_xml::XmlTables},
+};
+
+pyo3::import_exception!(chc.util.fileutil, CHCError);
 
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "c_file")?;
@@ -36,14 +57,549 @@ pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     Ok(module)
 }
 
+pub(crate) fn chklogger(py: Python, level: &str, text: String) -> PyResult<()> {
+    let chc = PyModule::import_bound(py, intern!(py, "chc"))?;
+    let util = chc.getattr(intern!(py, "util"))?;
+    let loggingutil = util.getattr(intern!(py, "loggingutil"))?;
+    let chklogger = loggingutil.getattr(intern!(py, "chklogger"))?;
+    let logger = chklogger.getattr(intern!(py, "logger"))?;
+    logger.call_method1(level, (text,))?;
+    Ok(())
+}
+
+/// Target path, project name, file path and file name, the arguments by
+/// which fileutil locates the results for a file.
+type FileArgs<'a> = (Bound<'a, PyAny>, Bound<'a, PyAny>, Option<String>, String);
+
+/// Functions of a file in order of their global declaration.
+struct Functions {
+    functions: Vec<(isize, String, Py<PyAny>)>,
+    by_vid: HashMap<isize, usize>,
+    by_name: HashMap<String, usize>,
+}
+
+/// Global variables of a file by vid and by name.
+struct GlobalVarinfos {
+    by_vid: HashMap<isize, Py<PyAny>>,
+    by_name: HashMap<String, Py<PyAny>>,
+}
+
 /// C File main access point.
-#[pyclass(subclass)]
-pub struct CFile {}
+///
+/// The file-level dictionaries are loaded on first use, each independently
+/// of the others, and can be reset separately to be reloaded after the
+/// analyzer updated them.
+#[pyclass(frozen, subclass)]
+pub struct CFile {
+    #[pyo3(get)]
+    capp: Py<PyAny>,
+    #[pyo3(get)]
+    index: isize,
+    /// Base filename (without extension).
+    #[pyo3(get)]
+    cfilename: String,
+    /// Path relative to project directory or None if at toplevel.
+    #[pyo3(get)]
+    cfilepath: Option<String>,
+    sourcefile: OnceCell<Py<PyAny>>,
+    contracts: OnceCell<Py<PyAny>>,
+    // Contents of the analysis results, dropped by reload
+    cfileglobals: RwLock<Option<Py<PyAny>>>,
+    functions: RwLock<Option<Arc<Functions>>>,
+    global_varinfos: RwLock<Option<Arc<GlobalVarinfos>>>,
+    dictionary: RwLock<Option<Py<PyAny>>>,
+    contextdictionary: RwLock<Option<Py<PyAny>>>,
+    declarations: RwLock<Option<Py<PyAny>>>,
+    interfacedictionary: RwLock<Option<Py<PyAny>>>,
+    assigndictionary: RwLock<Option<Py<PyAny>>>,
+    predicatedictionary: RwLock<Option<Py<PyAny>>>,
+}
 
 #[pymethods]
 impl CFile {
     #[new]
-    fn new() -> CFile {
-        CFile {}
+    #[pyo3(signature = (capp, index, cfilename, cfilepath))]
+    fn new(capp: Py<PyAny>, index: isize, cfilename: String, cfilepath: Option<String>) -> CFile {
+        CFile {
+            capp,
+            index,
+            cfilename,
+            cfilepath,
+            sourcefile: OnceCell::new(),
+            contracts: OnceCell::new(),
+            cfileglobals: RwLock::new(None),
+            functions: RwLock::new(None),
+            global_varinfos: RwLock::new(None),
+            dictionary: RwLock::new(None),
+            contextdictionary: RwLock::new(None),
+            declarations: RwLock::new(None),
+            interfacedictionary: RwLock::new(None),
+            assigndictionary: RwLock::new(None),
+            predicatedictionary: RwLock::new(None),
+        }
+    }
+
+    /// Full name relative to the project directory.
+    ///
+    /// Note: the filename is without extension
+    #[getter]
+    fn name(&self) -> String {
+        match &self.cfilepath {
+            None => self.cfilename.clone(),
+            Some(cfilepath) => Path::new(cfilepath)
+                .join(&self.cfilename)
+                .to_string_lossy()
+                .into_owned(),
+        }
+    }
+
+    #[getter]
+    fn targetpath<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.capp.bind(py).getattr(intern!(py, "targetpath"))
+    }
+
+    #[getter]
+    fn projectname<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.capp.bind(py).getattr(intern!(py, "projectname"))
+    }
+
+    #[getter]
+    fn contractpath<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.capp.bind(py).getattr(intern!(py, "contractpath"))
+    }
+
+    #[getter]
+    fn cfileglobals<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        let sg = slf.get();
+        cached(py, &sg.cfileglobals, || {
+            chklogger(py, "info", format!("Load _cfile for {}", sg.name()))?;
+            let xcfile = sg.file_xnode(py, "get_cfile_xnode")?;
+            if xcfile.is_none() {
+                return Err(CHCError::new_err(format!(
+                    "_cfile.xml not found for {}",
+                    sg.name()
+                )));
+            }
+            python_class(py, "chc.app.CFileGlobals", "CFileGlobals")?.call1((slf, xcfile))
+        })
+    }
+
+    #[getter]
+    fn sourcefile<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        let sg = slf.get();
+        let sourcefile = sg.sourcefile.get_or_try_init(|| -> PyResult<_> {
+            let srcpath: String = fileutil(py)?
+                .call_method1(
+                    intern!(py, "get_savedsource_path"),
+                    (sg.targetpath(py)?, sg.projectname(py)?),
+                )?
+                .extract()?;
+            let srcfile = Path::new(&srcpath)
+                .join(sg.name() + ".c")
+                .to_string_lossy()
+                .into_owned();
+            chklogger(py, "info", format!("Source file: {srcfile}"))?;
+            Ok(python_class(py, "chc.source.CSrcFile", "CSrcFile")?
+                .call1((sg.capp.bind(py), srcfile))?
+                .unbind())
+        })?;
+        Ok(sourcefile.bind(py).clone())
+    }
+
+    #[getter]
+    fn contracts<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        let sg = slf.get();
+        let contracts = sg.contracts.get_or_try_init(|| -> PyResult<_> {
+            Ok(
+                python_class(py, "chc.api.CFileContracts", "CFileContracts")?
+                    .call1((slf, sg.contractpath(py)?))?
+                    .unbind(),
+            )
+        })?;
+        Ok(contracts.bind(py).clone())
+    }
+
+    #[getter]
+    fn dictionary<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        cached(slf.py(), &slf.get().dictionary, || {
+            Ok(Self::read_dictionary_file(slf)?.0)
+        })
+    }
+
+    fn reset_dictionary(&self) {
+        clear(&self.dictionary)
+    }
+
+    #[getter]
+    fn contextdictionary<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        let sg = slf.get();
+        cached(py, &sg.contextdictionary, || {
+            let xnode = sg.file_xnode(py, "get_cfile_contexttable_xnode")?;
+            if xnode.is_none() {
+                return Err(CHCError::new_err("Context table file not found"));
+            }
+            python_class(py, "chc.app.CContextDictionary", "CContextDictionary")?
+                .call1((slf, xnode))
+        })
+    }
+
+    fn reset_contextdictionary(&self) {
+        clear(&self.contextdictionary)
+    }
+
+    #[getter]
+    fn declarations<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        cached(slf.py(), &slf.get().declarations, || {
+            Ok(Self::read_dictionary_file(slf)?.1)
+        })
+    }
+
+    fn reset_declarations(&self) {
+        clear(&self.declarations)
+    }
+
+    #[getter]
+    fn interfacedictionary<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        let sg = slf.get();
+        cached(py, &sg.interfacedictionary, || {
+            let xnode = sg.file_xnode(py, "get_cfile_interface_dictionary_xnode")?;
+            python_class(py, "chc.api.InterfaceDictionary", "InterfaceDictionary")?
+                .call1((slf, xnode))
+        })
+    }
+
+    fn reset_interfacedictionary(&self) {
+        clear(&self.interfacedictionary)
+    }
+
+    #[getter]
+    fn assigndictionary<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        let sg = slf.get();
+        cached(py, &sg.assigndictionary, || {
+            let xnode = sg.file_xnode(py, "get_cfile_assignment_dictionary_xnode")?;
+            python_class(
+                py,
+                "chc.app.CFileAssignmentDictionary",
+                "CFileAssignmentDictionary",
+            )?
+            .call1((slf, xnode))
+        })
+    }
+
+    fn reset_assigndictionary(&self) {
+        clear(&self.assigndictionary)
+    }
+
+    #[getter]
+    fn predicatedictionary<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        let sg = slf.get();
+        cached(py, &sg.predicatedictionary, || {
+            let xnode = sg.file_xnode(py, "get_cfile_predicate_dictionary_xnode")?;
+            python_class(
+                py,
+                "chc.proof.CFilePredicateDictionary",
+                "CFilePredicateDictionary",
+            )?
+            .call1((slf, xnode))
+        })
+    }
+
+    fn reset_predicatedictionary(&self) {
+        clear(&self.predicatedictionary)
+    }
+
+    /// Drops everything read from the analysis results of this file, so that
+    /// the dictionaries, declarations, globals and functions (with their own
+    /// tables) are read again on next use.
+    fn reload(&self) {
+        clear(&self.dictionary);
+        clear(&self.contextdictionary);
+        clear(&self.declarations);
+        clear(&self.interfacedictionary);
+        clear(&self.assigndictionary);
+        clear(&self.predicatedictionary);
+        clear(&self.cfileglobals);
+        *self
+            .functions
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = None;
+        *self
+            .global_varinfos
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = None;
+    }
+
+    /// Functions by vid of their global declaration.
+    #[getter]
+    fn functions<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyDict>> {
+        let py = slf.py();
+        let result = PyDict::new_bound(py);
+        for (vid, _, cfun) in &Self::function_table(slf)?.functions {
+            result.set_item(vid, cfun.bind(py))?;
+        }
+        Ok(result)
+    }
+
+    /// Map from function names to vid's.
+    #[getter]
+    fn functionxref(slf: &Bound<Self>) -> PyResult<HashMap<String, isize>> {
+        Ok(Self::function_table(slf)?
+            .functions
+            .iter()
+            .map(|(vid, name, _)| (name.clone(), *vid))
+            .collect())
+    }
+
+    #[getter]
+    fn functionnames(slf: &Bound<Self>) -> PyResult<Vec<String>> {
+        Ok(Self::function_table(slf)?
+            .functions
+            .iter()
+            .map(|(_, name, _)| name.clone())
+            .collect())
+    }
+
+    fn has_function_by_name(slf: &Bound<Self>, fnname: &str) -> PyResult<bool> {
+        Ok(Self::function_table(slf)?.by_name.contains_key(fnname))
+    }
+
+    fn get_function_by_name<'a>(slf: &Bound<'a, Self>, fnname: &str) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        let table = Self::function_table(slf)?;
+        match table.by_name.get(fnname) {
+            Some(&pos) => Ok(table.functions[pos].2.bind(py).clone()),
+            None => Err(PyErr::from_value_bound(
+                python_class(py, "chc.app.CFile", "CFunctionNotFoundException")?
+                    .call1((slf, fnname))?,
+            )),
+        }
+    }
+
+    fn has_function_by_index(slf: &Bound<Self>, index: isize) -> PyResult<bool> {
+        Ok(Self::function_table(slf)?.by_vid.contains_key(&index))
+    }
+
+    fn get_function_by_index<'a>(
+        slf: &Bound<'a, Self>,
+        index: isize,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let table = Self::function_table(slf)?;
+        match table.by_vid.get(&index) {
+            Some(&pos) => Ok(table.functions[pos].2.bind(slf.py()).clone()),
+            None => Err(PyException::new_err(format!(
+                "Unable to find function with global vid {index}"
+            ))),
+        }
+    }
+
+    fn has_global_varinfo(slf: &Bound<Self>, vid: isize) -> PyResult<bool> {
+        Ok(Self::global_varinfo_table(slf)?.by_vid.contains_key(&vid))
+    }
+
+    fn get_global_varinfo<'a>(slf: &Bound<'a, Self>, vid: isize) -> PyResult<Bound<'a, PyAny>> {
+        match Self::global_varinfo_table(slf)?.by_vid.get(&vid) {
+            Some(vinfo) => Ok(vinfo.bind(slf.py()).clone()),
+            None => Err(CHCError::new_err(format!(
+                "Global variable with vid: {vid} not found"
+            ))),
+        }
+    }
+
+    fn has_global_varinfo_by_name(slf: &Bound<Self>, name: &str) -> PyResult<bool> {
+        Ok(Self::global_varinfo_table(slf)?.by_name.contains_key(name))
+    }
+
+    fn get_global_varinfo_by_name<'a>(
+        slf: &Bound<'a, Self>,
+        name: &str,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        match Self::global_varinfo_table(slf)?.by_name.get(name) {
+            Some(vinfo) => Ok(vinfo.bind(slf.py()).clone()),
+            None => Err(CHCError::new_err(format!(
+                "Varinfo with name {name} not found in {}",
+                slf.get().name()
+            ))),
+        }
+    }
+}
+
+impl CFile {
+    /// Sets the file dictionary and declarations from tables that were
+    /// already read from the dictionary file.
+    pub(crate) fn set_dictionary_tables(slf: &Bound<Self>, tables: XmlTables) -> PyResult<()> {
+        let sg = slf.get();
+        let tables = Py::new(slf.py(), tables)?;
+        set(
+            &sg.dictionary,
+            Self::from_tables(slf, "CFileDictionary", &tables)?,
+        );
+        set(
+            &sg.declarations,
+            Self::from_tables(slf, "CFileDeclarations", &tables)?,
+        );
+        Ok(())
+    }
+
+    /// Reads the tables of the dictionary file, which holds both the file
+    /// dictionary and the declarations, through the dictionary cache; either
+    /// of the two that is not set yet is built from them.
+    fn read_dictionary_file<'a>(
+        slf: &Bound<'a, Self>,
+    ) -> PyResult<(Bound<'a, PyAny>, Bound<'a, PyAny>)> {
+        let py = slf.py();
+        let sg = slf.get();
+        let fileutil = fileutil(py)?;
+        let filename: PathBuf = fileutil
+            .call_method1(intern!(py, "get_cfile_dictionaryname"), sg.file_args(py)?)?
+            .extract()?;
+        if !filename.is_file() {
+            return Err(CHCError::new_err("File dictionary file not found"));
+        }
+        let cachepath: PathBuf = fileutil
+            .call_method1(
+                intern!(py, "get_dictionary_cache_path"),
+                (sg.targetpath(py)?, sg.projectname(py)?),
+            )?
+            .extract()?;
+        let tables = Py::new(py, read_xml_tables_cached(filename, cachepath)?)?;
+        Ok((
+            cached(py, &sg.dictionary, || {
+                Self::from_tables(slf, "CFileDictionary", &tables)
+            })?,
+            cached(py, &sg.declarations, || {
+                Self::from_tables(slf, "CFileDeclarations", &tables)
+            })?,
+        ))
+    }
+
+    /// Instance of `chc.app.<class>` for this file, filled from `tables`.
+    fn from_tables<'a>(
+        slf: &Bound<'a, Self>,
+        class: &str,
+        tables: &Py<XmlTables>,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        let module = format!("chc.app.{class}");
+        let value = python_class(py, &module, class)?.call1((slf, py.None()))?;
+        value.call_method1(intern!(py, "initialize_from_tables"), (tables,))?;
+        Ok(value)
+    }
+
+    /// Sets the file globals from the c-file element `xcfile` of the
+    /// _cfile.xml file.
+    pub(crate) fn set_cfileglobals(slf: &Bound<Self>, xcfile: Bound<PyAny>) -> PyResult<()> {
+        let py = slf.py();
+        let cfileglobals =
+            python_class(py, "chc.app.CFileGlobals", "CFileGlobals")?.call1((slf, xcfile))?;
+        set(&slf.get().cfileglobals, cfileglobals);
+        Ok(())
+    }
+
+    /// Sets the functions of the file, with the function elements of the
+    /// function files that were already read in `xnodes` by function name.
+    pub(crate) fn set_functions(
+        slf: &Bound<Self>,
+        xnodes: HashMap<String, Bound<PyAny>>,
+    ) -> PyResult<()> {
+        let functions = Arc::new(Self::read_functions(slf, xnodes)?);
+        *slf.get()
+            .functions
+            .write()
+            .unwrap_or_else(|err| err.into_inner()) = Some(functions);
+        Ok(())
+    }
+
+    /// Arguments that locate a file of the analysis results of this file.
+    fn file_args<'a>(&self, py: Python<'a>) -> PyResult<FileArgs<'a>> {
+        Ok((
+            self.targetpath(py)?,
+            self.projectname(py)?,
+            self.cfilepath.clone(),
+            self.cfilename.clone(),
+        ))
+    }
+
+    /// Root element of a file read with the fileutil function `getter`, or
+    /// None if the file does not exist.
+    fn file_xnode<'a>(&self, py: Python<'a>, getter: &str) -> PyResult<Bound<'a, PyAny>> {
+        fileutil(py)?.call_method1(PyString::new_bound(py, getter), self.file_args(py)?)
+    }
+
+    fn function_table(slf: &Bound<Self>) -> PyResult<Arc<Functions>> {
+        cached_table(&slf.get().functions, || {
+            Self::read_functions(slf, HashMap::new())
+        })
+    }
+
+    /// Functions of the file, built from the function elements in `xnodes`
+    /// by function name, and from their function files for the others.
+    fn read_functions(
+        slf: &Bound<Self>,
+        mut xnodes: HashMap<String, Bound<PyAny>>,
+    ) -> PyResult<Functions> {
+        let py = slf.py();
+        let cfunction = python_class(py, "chc.app.CFunction", "CFunction")?;
+        let gfunctions = Self::cfileglobals(slf)?.getattr(intern!(py, "gfunctions"))?;
+        let mut table = Functions {
+            functions: Vec::new(),
+            by_vid: HashMap::new(),
+            by_name: HashMap::new(),
+        };
+        for item in gfunctions.call_method0(intern!(py, "items"))?.iter()? {
+            let (vid, gf): (isize, Bound<PyAny>) = item?.extract()?;
+            let fnname: String = gf.getattr(intern!(py, "vname"))?.extract()?;
+            let cfun = match xnodes.remove(&fnname) {
+                Some(xnode) => cfunction.call1((slf, xnode, &fnname))?,
+                None => cfunction.call_method1(intern!(py, "load"), (slf, &fnname))?,
+            };
+            if cfun.is_none() {
+                chklogger(py, "warning", format!("Function {fnname} not found"))?;
+                continue;
+            }
+            table.by_vid.insert(vid, table.functions.len());
+            table.by_name.insert(fnname.clone(), table.functions.len());
+            table.functions.push((vid, fnname, cfun.unbind()));
+        }
+        Ok(table)
+    }
+
+    fn global_varinfo_table(slf: &Bound<Self>) -> PyResult<Arc<GlobalVarinfos>> {
+        let py = slf.py();
+        cached_table(&slf.get().global_varinfos, || {
+            let cfileglobals = Self::cfileglobals(slf)?;
+            Ok(GlobalVarinfos {
+                by_vid: cfileglobals
+                    .getattr(intern!(py, "global_varinfo_vids"))?
+                    .extract()?,
+                by_name: cfileglobals
+                    .getattr(intern!(py, "global_varinfo_names"))?
+                    .extract()?,
+            })
+        })
+    }
+}
+
+/// Fills `cell` with `value`, replacing what was cached before.
+fn set(cell: &RwLock<Option<Py<PyAny>>>, value: Bound<PyAny>) {
+    *cell.write().unwrap_or_else(|err| err.into_inner()) = Some(value.unbind());
+}
+
+/// Like `cached`, for tables that are kept on the Rust side.
+fn cached_table<T>(
+    cell: &RwLock<Option<Arc<T>>>,
+    init: impl FnOnce() -> PyResult<T>,
+) -> PyResult<Arc<T>> {
+    if let Some(value) = cell.read().unwrap_or_else(|err| err.into_inner()).as_ref() {
+        return Ok(value.clone());
     }
+    let value = Arc::new(init()?);
+    let mut guard = cell.write().unwrap_or_else(|err| err.into_inner());
+    Ok(guard.get_or_insert(value).clone())
 }
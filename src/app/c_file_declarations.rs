@@ -28,13 +28,17 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 ------------------------------------------------------------------------------
 */
-use pyo3::{intern, prelude::*};
+use std::{collections::HashMap, sync::RwLock};
+
+use pyo3::{intern, prelude::*, types::PyString};
 
 use crate::{
-    app::{c_declarations::CDeclarations, c_type_info::CTypeInfo},
-    util::indexed_table::IndexedTable,
+    app::{c_declarations::CDeclarations, c_typ::CTyp, c_type_info::CTypeInfo},
+    util::indexed_table::{IndexedTableValue, PyIndexedTable},
 };
 
+pyo3::import_exception!(chc.util.fileutil, CHCError);
+
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "c_file_declarations")?;
     module.add_class::<CFileDeclarations>()?;
@@ -48,20 +52,57 @@ pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
 ///
 /// Declarations are dependent on CFileDictionary
 #[pyclass(extends = CDeclarations, frozen, subclass)]
-pub struct CFileDeclarations {}
+pub struct CFileDeclarations {
+    /// Index in the file dictionary of the type of each typedef name.
+    typedefs: Versioned<HashMap<String, isize>>,
+    /// Integer kind of each enum name.
+    enums: Versioned<HashMap<String, String>>,
+    /// Expansion of each named type in the file dictionary, with the version
+    /// of the typeinfo table it was computed from; entries of the typ table
+    /// do not change once added.
+    expansions: Versioned<HashMap<isize, Expansion>>,
+}
+
+/// Value computed from a declarations table, with the version of the table
+/// it was computed from.
+type Versioned<T> = RwLock<(u64, T)>;
+
+/// Index in the file dictionary of the type a typedef name stands for,
+/// together with the indices of the attributes of the names in the chain and
+/// of that type.
+pub type Expansion = (isize, Vec<isize>);
 
 #[pymethods]
 impl CFileDeclarations {
     #[new]
     fn new() -> PyClassInitializer<Self> {
-        PyClassInitializer::from(CDeclarations::new()).add_subclass(CFileDeclarations {})
+        PyClassInitializer::from(CDeclarations::new()).add_subclass(CFileDeclarations {
+            typedefs: RwLock::new((0, HashMap::new())),
+            enums: RwLock::new((0, HashMap::new())),
+            expansions: RwLock::new((0, HashMap::new())),
+        })
+    }
+
+    /// Type defined by typedef `name`, with typedefs expanded.
+    fn expand<'a>(slf: &Bound<'a, Self>, name: &str) -> PyResult<Bound<'a, CTyp>> {
+        let py = slf.py();
+        let Some(definition) = Self::typedef(slf, name)? else {
+            return Err(CHCError::new_err(format!(
+                "No type definition found for {name}"
+            )));
+        };
+        let typ = slf
+            .getattr(intern!(py, "dictionary"))?
+            .call_method1(intern!(py, "get_typ"), (definition,))?
+            .downcast_into::<CTyp>()?;
+        CTyp::unroll(&typ)
     }
 
     fn get_typeinfo<'a>(slf: &Bound<'a, Self>, ix: isize) -> PyResult<Bound<'a, CTypeInfo>> {
         let py = slf.py();
         let itv = slf
             .getattr(intern!(py, "typeinfo_table"))?
-            .downcast::<IndexedTable>()?
+            .downcast::<PyIndexedTable>()?
             .clone()
             .borrow()
             .retrieve(ix)?
@@ -73,3 +114,88 @@ impl CFileDeclarations {
         )
     }
 }
+
+impl CFileDeclarations {
+    /// Index in the file dictionary of the type defined by typedef `name`.
+    ///
+    /// The typedefs are read from the typeinfo table again after it changed.
+    pub fn typedef(slf: &Bound<Self>, name: &str) -> PyResult<Option<isize>> {
+        let table = intern!(slf.py(), "typeinfo_table");
+        Self::lookup(slf, table, &slf.get().typedefs, name, |typeinfo| {
+            Ok((typeinfo.tag(0)?.to_string(), typeinfo.arg(0)?))
+        })
+    }
+
+    /// Integer kind of enum `name`, or None if the file has no definition for
+    /// it.
+    ///
+    /// The enums are read from the enuminfo table again after it changed.
+    pub fn enum_ikind(slf: &Bound<Self>, name: &str) -> PyResult<Option<String>> {
+        let table = intern!(slf.py(), "enuminfo_table");
+        Self::lookup(slf, table, &slf.get().enums, name, |enuminfo| {
+            Ok((enuminfo.tag(0)?.to_string(), enuminfo.tag(1)?.to_string()))
+        })
+    }
+
+    /// Value for `name` in the map of `memo`, which is built from the entries
+    /// of `table` with `entry`, and built again after the table changed.
+    fn lookup<V: Clone>(
+        slf: &Bound<Self>,
+        table: &Bound<PyString>,
+        memo: &Versioned<HashMap<String, V>>,
+        name: &str,
+        entry: impl Fn(&IndexedTableValue) -> PyResult<(String, V)>,
+    ) -> PyResult<Option<V>> {
+        let table = slf.getattr(table)?;
+        let table = table.downcast::<PyIndexedTable>()?.borrow();
+        let version = table.table().version();
+        {
+            let map = memo.read().unwrap_or_else(|err| err.into_inner());
+            if map.0 == version {
+                return Ok(map.1.get(name).cloned());
+            }
+        }
+        let map = table
+            .table()
+            .values()
+            .map(|value| entry(value.get()))
+            .collect::<PyResult<HashMap<_, _>>>()?;
+        let value = map.get(name).cloned();
+        *memo.write().unwrap_or_else(|err| err.into_inner()) = (version, map);
+        Ok(value)
+    }
+
+    /// Version of the typeinfo table, which expansions are computed from.
+    fn typeinfo_version(slf: &Bound<Self>) -> PyResult<u64> {
+        let table = slf.getattr(intern!(slf.py(), "typeinfo_table"))?;
+        let version = table
+            .downcast::<PyIndexedTable>()?
+            .borrow()
+            .table()
+            .version();
+        Ok(version)
+    }
+
+    /// Memoized expansion of the named type with index `index` in the file
+    /// dictionary.
+    pub fn expansion(slf: &Bound<Self>, index: isize) -> PyResult<Option<Expansion>> {
+        let version = Self::typeinfo_version(slf)?;
+        let expansions = slf.get().expansions.read();
+        let expansions = expansions.unwrap_or_else(|err| err.into_inner());
+        if expansions.0 != version {
+            return Ok(None);
+        }
+        Ok(expansions.1.get(&index).cloned())
+    }
+
+    pub fn set_expansion(slf: &Bound<Self>, index: isize, expansion: Expansion) -> PyResult<()> {
+        let version = Self::typeinfo_version(slf)?;
+        let expansions = slf.get().expansions.write();
+        let mut expansions = expansions.unwrap_or_else(|err| err.into_inner());
+        if expansions.0 != version {
+            *expansions = (version, HashMap::new());
+        }
+        expansions.1.insert(index, expansion);
+        Ok(())
+    }
+}
@@ -28,7 +28,31 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 ------------------------------------------------------------------------------
 */
-use pyo3::prelude::*;
+//! Main access point for a c function.
+
+use std::sync::RwLock;
+
+use once_cell::sync::OnceCell;
+use pyo3::{
+    intern,
+    prelude::*,
+    types::{PyDict, PyType},
+};
+
+use crate::{
+    app::{
+        c_cfg::Cfg,
+        c_fun_declarations::CFunDeclarations,
+        c_instr::{CCallInstr, CInstr},
+        c_stmt::{xml_attr, xml_find, CBlock, CFunctionBody, CInstrsStmt, CStmt},
+    },
+    invariants::{
+        c_fun_inv_dictionary::CFunInvDictionary, c_fun_var_dictionary::CFunVarDictionary,
+    },
+    proof::{c_fun_po_dictionary::CFunPODictionary, c_function_proofs::CFunctionProofs},
+};
+
+pyo3::import_exception!(chc.util.fileutil, CHCError);
 
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "c_function")?;
@@ -36,14 +60,551 @@ pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     Ok(module)
 }
 
-/// C File main access point.
-#[pyclass(subclass)]
-pub struct CFunction {}
+/// Python class `name` from module `module`.
+pub(crate) fn python_class<'a>(
+    py: Python<'a>,
+    module: &str,
+    name: &str,
+) -> PyResult<Bound<'a, PyAny>> {
+    PyModule::import_bound(py, module)?.getattr(name)
+}
+
+/// Value of `cell`, initialized with `init` if it is empty.
+pub(crate) fn cached<'a, T>(
+    py: Python<'a>,
+    cell: &RwLock<Option<Py<T>>>,
+    init: impl FnOnce() -> PyResult<Bound<'a, T>>,
+) -> PyResult<Bound<'a, T>> {
+    if let Some(value) = cell.read().unwrap_or_else(|err| err.into_inner()).as_ref() {
+        return Ok(value.bind(py).clone());
+    }
+    let value = init()?;
+    let mut guard = cell.write().unwrap_or_else(|err| err.into_inner());
+    Ok(guard.get_or_insert_with(|| value.unbind()).bind(py).clone())
+}
+
+pub(crate) fn clear<T>(cell: &RwLock<Option<Py<T>>>) {
+    *cell.write().unwrap_or_else(|err| err.into_inner()) = None;
+}
+
+/// Formal parameters and local variables, as (vid, varinfo) in order of
+/// declaration.
+struct Variables {
+    formals: Vec<(isize, Py<PyAny>)>,
+    locals: Vec<(isize, Py<PyAny>)>,
+}
+
+/// Function implementation.
+///
+/// The function file (`*_cfun.xml`) is parsed once; the declarations, body,
+/// control flow graph and the per-function dictionaries are created on first
+/// use and kept with the function. The dictionaries are instances of the
+/// python subclasses of the rust dictionary classes; the api and the
+/// invariant table are python objects.
+#[pyclass(frozen, subclass)]
+pub struct CFunction {
+    #[pyo3(get)]
+    cfile: Py<PyAny>,
+    #[pyo3(get)]
+    xnode: Py<PyAny>, // ET.Element
+    #[pyo3(get)]
+    name: String,
+    cfundecls: OnceCell<Py<CFunDeclarations>>,
+    svar: OnceCell<Py<PyAny>>,
+    sbody: OnceCell<Py<CFunctionBody>>,
+    cfg: OnceCell<Py<Cfg>>,
+    variables: OnceCell<Variables>,
+    invdictionary: OnceCell<Py<CFunInvDictionary>>,
+    invarianttable: OnceCell<Py<PyAny>>,
+    // Tables that are reloaded after the analysis updated them
+    api: RwLock<Option<Py<PyAny>>>,
+    vardictionary: RwLock<Option<Py<CFunVarDictionary>>>,
+    podictionary: RwLock<Option<Py<CFunPODictionary>>>,
+    proofs: RwLock<Option<Py<CFunctionProofs>>>,
+}
 
 #[pymethods]
 impl CFunction {
     #[new]
-    fn new() -> CFunction {
-        CFunction {}
+    fn new(cfile: Py<PyAny>, xnode: Py<PyAny>, fname: String) -> CFunction {
+        CFunction {
+            cfile,
+            xnode,
+            name: fname,
+            cfundecls: OnceCell::new(),
+            svar: OnceCell::new(),
+            sbody: OnceCell::new(),
+            cfg: OnceCell::new(),
+            variables: OnceCell::new(),
+            invdictionary: OnceCell::new(),
+            invarianttable: OnceCell::new(),
+            api: RwLock::new(None),
+            vardictionary: RwLock::new(None),
+            podictionary: RwLock::new(None),
+            proofs: RwLock::new(None),
+        }
+    }
+
+    /// Function `fname` of `cfile` read from its function file, or None if
+    /// the file has no function element.
+    #[classmethod]
+    fn load<'a>(
+        cls: &Bound<'a, PyType>,
+        cfile: &Bound<'a, PyAny>,
+        fname: &str,
+    ) -> PyResult<Option<Bound<'a, PyAny>>> {
+        let py = cls.py();
+        let xnode = fileutil(py)?.call_method1(
+            intern!(py, "get_cfun_xnode"),
+            (
+                cfile.getattr(intern!(py, "targetpath"))?,
+                cfile.getattr(intern!(py, "projectname"))?,
+                cfile.getattr(intern!(py, "cfilepath"))?,
+                cfile.getattr(intern!(py, "cfilename"))?,
+                fname,
+            ),
+        )?;
+        if xnode.is_none() {
+            return Ok(None);
+        }
+        Ok(Some(cls.call1((cfile, xnode, fname))?))
+    }
+
+    fn xmsg(&self, txt: &str) -> String {
+        format!("Function {}: {txt}", self.name)
+    }
+
+    #[getter]
+    fn capp<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.cfile.bind(py).getattr(intern!(py, "capp"))
+    }
+
+    #[getter]
+    fn targetpath<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.cfile.bind(py).getattr(intern!(py, "targetpath"))
+    }
+
+    #[getter]
+    fn projectname<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.cfile.bind(py).getattr(intern!(py, "projectname"))
+    }
+
+    #[getter]
+    fn cfilepath<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.cfile.bind(py).getattr(intern!(py, "cfilepath"))
+    }
+
+    #[getter]
+    fn cfilename<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.cfile.bind(py).getattr(intern!(py, "cfilename"))
+    }
+
+    #[getter]
+    fn cfiledecls<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.cfile.bind(py).getattr(intern!(py, "declarations"))
+    }
+
+    #[getter]
+    fn interfacedictionary<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.cfile
+            .bind(py)
+            .getattr(intern!(py, "interfacedictionary"))
+    }
+
+    #[getter]
+    fn cfundecls<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CFunDeclarations>> {
+        let py = slf.py();
+        let sg = slf.get();
+        let cfundecls = sg.cfundecls.get_or_try_init(|| -> PyResult<_> {
+            let Some(dxnode) = xml_find(sg.xnode.bind(py), "declarations")? else {
+                return Err(CHCError::new_err(
+                    sg.xmsg("declarations are missing from cfun file"),
+                ));
+            };
+            let class = python_class(py, "chc.app.CFunDeclarations", "CFunDeclarations")?;
+            Ok(class.call1((slf, dxnode))?.downcast_into()?.unbind())
+        })?;
+        Ok(cfundecls.bind(py).clone())
+    }
+
+    #[getter]
+    fn svar<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        let sg = slf.get();
+        let svar = sg.svar.get_or_try_init(|| -> PyResult<_> {
+            let Some(xsvar) = xml_find(sg.xnode.bind(py), "svar")? else {
+                return Err(CHCError::new_err(
+                    sg.xmsg("svar element is missing from cfun file"),
+                ));
+            };
+            let Some(xivinfo) = xml_attr(&xsvar, "ivinfo")? else {
+                return Err(CHCError::new_err(sg.xmsg(
+                    "ivinfo attribute is missing from svar element in cfun file",
+                )));
+            };
+            let index: isize = xivinfo
+                .parse()
+                .map_err(|_| CHCError::new_err(sg.xmsg(&format!("invalid ivinfo {xivinfo}"))))?;
+            Ok(sg
+                .cfiledecls(py)?
+                .call_method1(intern!(py, "get_varinfo"), (index,))?
+                .unbind())
+        })?;
+        Ok(svar.bind(py).clone())
+    }
+
+    #[getter]
+    fn ftype<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        Self::svar(slf)?.getattr(intern!(slf.py(), "vtype"))
+    }
+
+    fn get_vid(slf: &Bound<Self>) -> PyResult<isize> {
+        Self::svar(slf)?
+            .getattr(intern!(slf.py(), "vid"))?
+            .extract()
+    }
+
+    #[getter]
+    fn sbody<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CFunctionBody>> {
+        let py = slf.py();
+        let sg = slf.get();
+        let sbody = sg.sbody.get_or_try_init(|| -> PyResult<_> {
+            let Some(xsbody) = xml_find(sg.xnode.bind(py), "sbody")? else {
+                return Err(CHCError::new_err(
+                    sg.xmsg("sbody element is missing from cfun file"),
+                ));
+            };
+            Py::new(
+                py,
+                CFunctionBody::new(slf.clone().into_any().unbind(), xsbody.unbind()),
+            )
+        })?;
+        Ok(sbody.bind(py).clone())
+    }
+
+    /// Control flow graph of the statements in the function body.
+    #[getter]
+    fn cfg<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, Cfg>> {
+        let py = slf.py();
+        let cfg = slf
+            .get()
+            .cfg
+            .get_or_try_init(|| Py::new(py, Cfg::new(&Self::body(slf)?)?))?;
+        Ok(cfg.bind(py).clone())
+    }
+
+    /// Formal parameters by vid.
+    #[getter]
+    fn formals<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyDict>> {
+        let variables = Self::variables(slf)?;
+        to_dict(slf.py(), &variables.formals)
+    }
+
+    /// Local variables by vid.
+    #[getter]
+    fn locals<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyDict>> {
+        let variables = Self::variables(slf)?;
+        to_dict(slf.py(), &variables.locals)
+    }
+
+    fn get_formal_vid(slf: &Bound<Self>, name: &str) -> PyResult<isize> {
+        let variables = Self::variables(slf)?;
+        if let Some(vid) = find_variable(slf.py(), &variables.formals, name)? {
+            return Ok(vid);
+        }
+        let formals = variables
+            .formals
+            .iter()
+            .map(|(_, v)| Ok(v.bind(slf.py()).str()?.to_string()))
+            .collect::<PyResult<Vec<_>>>()?;
+        Err(CHCError::new_err(format!("Formals: {}", formals.join(","))))
+    }
+
+    fn get_variable_vid(slf: &Bound<Self>, vname: &str) -> PyResult<isize> {
+        let variables = Self::variables(slf)?;
+        for vars in [&variables.formals, &variables.locals] {
+            if let Some(vid) = find_variable(slf.py(), vars, vname)? {
+                return Ok(vid);
+            }
+        }
+        Err(CHCError::new_err(format!(
+            "Could not find vid for variable \"{vname}\""
+        )))
+    }
+
+    fn has_variable_vid(slf: &Bound<Self>, vname: &str) -> PyResult<bool> {
+        let variables = Self::variables(slf)?;
+        for vars in [&variables.formals, &variables.locals] {
+            if find_variable(slf.py(), vars, vname)?.is_some() {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    #[getter]
+    fn vardictionary<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CFunVarDictionary>> {
+        let py = slf.py();
+        let sg = slf.get();
+        cached(py, &sg.vardictionary, || {
+            let Some(vxnode) = Self::function_xnode(slf, "get_vars_xnode")? else {
+                return Err(CHCError::new_err(sg.xmsg(&format!(
+                    "var-dictionary file not found for function {} in file {}",
+                    sg.name,
+                    sg.cfile.bind(py).getattr(intern!(py, "name"))?
+                ))));
+            };
+            let Some(xvard) = xml_find(&vxnode, "var-dictionary")? else {
+                return Err(CHCError::new_err(
+                    sg.xmsg("var-dictionary missing from cfun-vars file"),
+                ));
+            };
+            Ok(
+                python_class(py, "chc.invariants.CFunVarDictionary", "CFunVarDictionary")?
+                    .call1((slf, xvard))?
+                    .downcast_into()?,
+            )
+        })
+    }
+
+    #[getter]
+    fn invdictionary<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CFunInvDictionary>> {
+        let py = slf.py();
+        let sg = slf.get();
+        let invd = sg.invdictionary.get_or_try_init(|| -> PyResult<_> {
+            let xinvs = Self::invs_element(slf, "inv-dictionary", "inv-dictionary")?;
+            Ok(
+                python_class(py, "chc.invariants.CFunInvDictionary", "CFunInvDictionary")?
+                    .call1((slf, xinvs))?
+                    .downcast_into()?
+                    .unbind(),
+            )
+        })?;
+        Ok(invd.bind(py).clone())
+    }
+
+    #[getter]
+    fn invarianttable<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        let sg = slf.get();
+        let table = sg.invarianttable.get_or_try_init(|| -> PyResult<_> {
+            let xinvs = Self::invs_element(slf, "location-invariants", "inv-table")?;
+            Ok(python_class(
+                py,
+                "chc.invariants.CFunInvariantTable",
+                "CFunInvariantTable",
+            )?
+            .call1((slf, xinvs))?
+            .unbind())
+        })?;
+        Ok(table.bind(py).clone())
+    }
+
+    #[getter]
+    fn podictionary<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CFunPODictionary>> {
+        let py = slf.py();
+        let sg = slf.get();
+        cached(py, &sg.podictionary, || {
+            let Some(pxnode) = Self::function_xnode(slf, "get_pod_xnode")? else {
+                return Err(CHCError::new_err(sg.xmsg("pod file not found")));
+            };
+            Ok(
+                python_class(py, "chc.proof.CFunPODictionary", "CFunPODictionary")?
+                    .call1((slf, pxnode))?
+                    .downcast_into()?,
+            )
+        })
+    }
+
+    #[getter]
+    fn proofs<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CFunctionProofs>> {
+        let py = slf.py();
+        let sg = slf.get();
+        cached(py, &sg.proofs, || {
+            let Some(xpponode) = Self::function_xnode(slf, "get_ppo_xnode")? else {
+                return Err(CHCError::new_err(sg.xmsg("ppo file is missing")));
+            };
+            let Some(xxpponode) = xml_find(&xpponode, "ppos")? else {
+                return Err(CHCError::new_err(sg.xmsg("_ppo file has no ppos element")));
+            };
+            let Some(xsponode) = Self::function_xnode(slf, "get_spo_xnode")? else {
+                return Err(CHCError::new_err(sg.xmsg("spo file is missing")));
+            };
+            let Some(xxsponode) = xml_find(&xsponode, "spos")? else {
+                return Err(CHCError::new_err(sg.xmsg("_spo file has no spos element")));
+            };
+            Ok(
+                python_class(py, "chc.proof.CFunctionProofs", "CFunctionProofs")?
+                    .call1((slf, xxpponode, xxsponode))?
+                    .downcast_into()?,
+            )
+        })
+    }
+
+    #[getter]
+    fn api<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        let sg = slf.get();
+        cached(py, &sg.api, || {
+            let Some(axnode) = Self::function_xnode(slf, "get_api_xnode")? else {
+                return Err(CHCError::new_err(sg.xmsg("api file not found")));
+            };
+            let Some(apinode) = xml_find(&axnode, "api")? else {
+                return Err(CHCError::new_err(sg.xmsg("api file has no api node")));
+            };
+            python_class(py, "chc.api.CFunctionApi", "CFunctionApi")?.call1((slf, apinode))
+        })
+    }
+
+    /// Drops the tables that the analysis updates, so that they are reloaded
+    /// on next use.
+    fn reinitialize_tables(&self) {
+        clear(&self.api);
+        clear(&self.podictionary);
+        clear(&self.vardictionary);
+        clear(&self.proofs);
+    }
+
+    #[getter]
+    fn strings(slf: &Bound<Self>) -> PyResult<Vec<String>> {
+        Self::body(slf)?
+            .getattr(intern!(slf.py(), "strings"))?
+            .extract()
+    }
+
+    /// Number of occurrences of `vid` in expressions and lhs.
+    fn get_variable_uses(slf: &Bound<Self>, vid: isize) -> PyResult<isize> {
+        Self::body(slf)?
+            .call_method1(intern!(slf.py(), "get_variable_uses"), (vid,))?
+            .extract()
+    }
+
+    /// Number of blocks, including the function body.
+    #[getter]
+    fn block_count(slf: &Bound<Self>) -> PyResult<usize> {
+        let stmts = CStmt::all_nested(&Self::body(slf)?)?;
+        Ok(stmts
+            .iter()
+            .filter(|stmt| stmt.is_instance_of::<CBlock>())
+            .count())
+    }
+
+    /// Number of statements, including the function body.
+    #[getter]
+    fn stmt_count(slf: &Bound<Self>) -> PyResult<usize> {
+        Ok(CStmt::all_nested(&Self::body(slf)?)?.len())
+    }
+
+    #[getter]
+    fn instr_count(slf: &Bound<Self>) -> PyResult<usize> {
+        Ok(Self::instrs(slf)?.len())
+    }
+
+    #[getter]
+    fn call_instrs<'a>(slf: &Bound<'a, Self>) -> PyResult<Vec<Bound<'a, CInstr>>> {
+        Ok(Self::instrs(slf)?
+            .into_iter()
+            .filter(|instr| instr.is_instance_of::<CCallInstr>())
+            .collect())
+    }
+}
+
+impl CFunction {
+    fn body<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CStmt>> {
+        Ok(Self::sbody(slf)?.into_any().downcast_into()?)
+    }
+
+    /// Instructions of all statements in the body, in program order.
+    fn instrs<'a>(slf: &Bound<'a, Self>) -> PyResult<Vec<Bound<'a, CInstr>>> {
+        let mut result = Vec::new();
+        for stmt in CStmt::all_nested(&Self::body(slf)?)? {
+            if let Ok(stmt) = stmt.downcast::<CInstrsStmt>() {
+                result.extend(CInstrsStmt::instrs(stmt)?);
+            }
+        }
+        Ok(result)
+    }
+
+    fn variables<'a>(slf: &'a Bound<Self>) -> PyResult<&'a Variables> {
+        let py = slf.py();
+        slf.get().variables.get_or_try_init(|| {
+            let varinfos = Self::cfundecls(slf)?.getattr(intern!(py, "varinfos"))?;
+            let mut variables = Variables {
+                formals: Vec::new(),
+                locals: Vec::new(),
+            };
+            for item in varinfos.call_method0(intern!(py, "items"))?.iter()? {
+                let (vid, vinfo): (isize, Bound<PyAny>) = item?.extract()?;
+                if vinfo.getattr(intern!(py, "is_param"))?.extract()? {
+                    variables.formals.push((vid, vinfo.unbind()));
+                } else {
+                    variables.locals.push((vid, vinfo.unbind()));
+                }
+            }
+            Ok(variables)
+        })
+    }
+
+    /// Root element of a per-function results file, read with the
+    /// fileutil function `getter`.
+    fn function_xnode<'a>(
+        slf: &Bound<'a, Self>,
+        getter: &str,
+    ) -> PyResult<Option<Bound<'a, PyAny>>> {
+        let py = slf.py();
+        let sg = slf.get();
+        let xnode = fileutil(py)?.call_method1(
+            getter,
+            (
+                sg.targetpath(py)?,
+                sg.projectname(py)?,
+                sg.cfilepath(py)?,
+                sg.cfilename(py)?,
+                sg.name.as_str(),
+            ),
+        )?;
+        Ok((!xnode.is_none()).then_some(xnode))
+    }
+
+    /// Element `tag` of the invariants file.
+    fn invs_element<'a>(
+        slf: &Bound<'a, Self>,
+        tag: &str,
+        desc: &str,
+    ) -> PyResult<Bound<'a, PyAny>> {
+        let sg = slf.get();
+        let Some(ixnode) = Self::function_xnode(slf, "get_invs_xnode")? else {
+            return Err(CHCError::new_err(sg.xmsg("inv-dictionary file not found")));
+        };
+        xml_find(&ixnode, tag)?.ok_or_else(|| {
+            CHCError::new_err(sg.xmsg(&format!("{desc} missing from cfun-invs file")))
+        })
+    }
+}
+
+pub(crate) fn fileutil(py: Python) -> PyResult<Bound<PyModule>> {
+    PyModule::import_bound(py, intern!(py, "chc.util.fileutil"))
+}
+
+fn to_dict<'a>(py: Python<'a>, variables: &[(isize, Py<PyAny>)]) -> PyResult<Bound<'a, PyDict>> {
+    let result = PyDict::new_bound(py);
+    for (vid, vinfo) in variables {
+        result.set_item(vid, vinfo.bind(py))?;
+    }
+    Ok(result)
+}
+
+/// Vid of the variable named `name` in `variables`.
+fn find_variable(
+    py: Python,
+    variables: &[(isize, Py<PyAny>)],
+    name: &str,
+) -> PyResult<Option<isize>> {
+    for (vid, vinfo) in variables {
+        let vname: String = vinfo.bind(py).getattr(intern!(py, "vname"))?.extract()?;
+        if vname == name {
+            return Ok(Some(*vid));
+        }
     }
+    Ok(None)
 }
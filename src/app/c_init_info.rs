@@ -34,8 +34,13 @@ use pyo3::{intern, prelude::*};
 
 use crate::{
     app::{
-        c_declarations::CDeclarations, c_dictionary_record::CDeclarationsRecord, c_exp::CExp,
-        c_offset::COffset, c_typ::CTyp,
+        c_data_model::{data_model, CDataModel},
+        c_declarations::CDeclarations,
+        c_dictionary_record::CDeclarationsRecord,
+        c_exp::CExp,
+        c_exp_eval,
+        c_offset::COffset,
+        c_typ::CTyp,
     },
     util::indexed_table::IndexedTableValue,
 };
@@ -95,6 +100,15 @@ impl CSingleInitInfo {
             .clone())
     }
 
+    /// Value of the initializer in `model` (default: the current data model)
+    /// if it is an integer constant expression.
+    #[pyo3(signature = (model=None))]
+    fn value(slf: &Bound<Self>, model: Option<CDataModel>) -> PyResult<Option<i128>> {
+        let model = model.unwrap_or_else(data_model);
+        let exp = Self::exp(slf)?;
+        Ok(c_exp_eval::evaluate(&exp, &model)?.map(|value| value.value))
+    }
+
     #[getter]
     fn is_single(&self) -> bool {
         true
@@ -28,21 +28,403 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 ------------------------------------------------------------------------------
 */
-use pyo3::prelude::*;
+//! C instruction (assignment, call, or inserted assembly code).
+
+use pyo3::{intern, prelude::*};
+
+use crate::app::c_stmt::{xml_attr, xml_find, xml_findall, xml_indices, CStmt};
+
+pyo3::import_exception!(chc.util.fileutil, CHCError);
 
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "c_instr")?;
     module.add_class::<CInstr>()?;
+    module.add_class::<CCallInstr>()?;
+    module.add_class::<CAssignInstr>()?;
+    module.add_class::<CAsmInstr>()?;
+    module.add_class::<CAsmInput>()?;
+    module.add_class::<CAsmOutput>()?;
     Ok(module)
 }
 
-#[pyclass(subclass)]
-pub struct CInstr {}
+/// Dictionary index in attribute `name` of `xnode`, or `message` as error.
+fn xml_index(xnode: &Bound<PyAny>, name: &str, message: &str) -> PyResult<isize> {
+    let Some(value) = xml_attr(xnode, name)? else {
+        return Err(CHCError::new_err(message.to_string()));
+    };
+    value
+        .parse()
+        .map_err(|_| CHCError::new_err(format!("invalid {name} {value}")))
+}
+
+/// Base class for instructions.
+#[pyclass(frozen, subclass)]
+pub struct CInstr {
+    #[pyo3(get)]
+    parent: Py<CStmt>,
+    #[pyo3(get)]
+    xnode: Py<PyAny>, // ET.Element
+}
 
 #[pymethods]
 impl CInstr {
     #[new]
-    fn new() -> CInstr {
-        CInstr {}
+    fn new(parent: Py<CStmt>, xnode: Py<PyAny>) -> CInstr {
+        CInstr { parent, xnode }
+    }
+
+    #[getter]
+    fn cfun<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.parent.bind(py).getattr(intern!(py, "cfun"))
+    }
+
+    #[getter]
+    fn cdictionary<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        CStmt::cdictionary(self.parent.bind(py))
+    }
+
+    #[getter]
+    fn is_assign(&self) -> bool {
+        false
+    }
+
+    #[getter]
+    fn is_call(&self) -> bool {
+        false
+    }
+
+    #[getter]
+    fn is_asm(&self) -> bool {
+        false
+    }
+
+    #[getter]
+    fn strings(&self) -> Vec<String> {
+        vec![]
+    }
+
+    fn get_variable_uses(&self, _vid: isize) -> isize {
+        0
+    }
+}
+
+impl CInstr {
+    fn get<'a>(&self, py: Python<'a>, method: &str, index: isize) -> PyResult<Bound<'a, PyAny>> {
+        self.cdictionary(py)?.call_method1(method, (index,))
+    }
+}
+
+/// Call instruction.
+#[pyclass(extends = CInstr, frozen, subclass)]
+pub struct CCallInstr {}
+
+#[pymethods]
+impl CCallInstr {
+    #[new]
+    pub fn new(parent: Py<CStmt>, xnode: Py<PyAny>) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CInstr::new(parent, xnode)).add_subclass(CCallInstr {})
+    }
+
+    #[getter]
+    fn is_call(&self) -> bool {
+        true
+    }
+
+    #[getter]
+    fn lhs<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let instr = slf.as_super();
+        let index = xml_index(
+            instr.xnode.bind(py),
+            "ilval",
+            "call instruction does not have an lval",
+        )?;
+        instr.get(py, "get_lval", index)
+    }
+
+    #[getter]
+    fn callee<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let instr = slf.as_super();
+        let index = xml_index(
+            instr.xnode.bind(py),
+            "iexp",
+            "call instruction does not hava a callee",
+        )?;
+        instr.get(py, "get_exp", index)
+    }
+
+    #[getter]
+    fn callargs<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Vec<Bound<'a, PyAny>>> {
+        let instr = slf.as_super();
+        let Some(xargs) = xml_find(instr.xnode.bind(py), "args")? else {
+            return Err(CHCError::new_err(
+                "Argument element missing from call instruction",
+            ));
+        };
+        xml_findall(&xargs, "exp")?
+            .iter()
+            .map(|xarg| {
+                let index = xml_index(xarg, "iexp", "iexp attribute not found in call argument")?;
+                instr.get(py, "get_exp", index)
+            })
+            .collect()
+    }
+
+    #[getter]
+    fn strings(slf: PyRef<Self>, py: Python) -> PyResult<Vec<String>> {
+        let mut result = Vec::new();
+        for arg in Self::callargs(slf, py)? {
+            result.extend(
+                arg.call_method0(intern!(py, "get_strings"))?
+                    .extract::<Vec<String>>()?,
+            );
+        }
+        Ok(result)
+    }
+
+    fn has_lhs(slf: PyRef<Self>, py: Python) -> PyResult<bool> {
+        Ok(xml_attr(slf.as_super().xnode.bind(py), "ilval")?.is_some())
+    }
+
+    fn get_variable_uses(slf: &Bound<Self>, vid: isize) -> PyResult<isize> {
+        let py = slf.py();
+        let uses = |item: Bound<PyAny>| -> PyResult<isize> {
+            item.call_method1(intern!(py, "get_variable_uses"), (vid,))?
+                .extract()
+        };
+        let mut result = 0;
+        if Self::has_lhs(slf.borrow(), py)? {
+            result += uses(Self::lhs(slf.borrow(), py)?)?;
+        }
+        for arg in Self::callargs(slf.borrow(), py)? {
+            result += uses(arg)?;
+        }
+        Ok(result + uses(Self::callee(slf.borrow(), py)?)?)
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: PyRef<Self>, py: Python) -> PyResult<String> {
+        Ok(format!("      call {}", Self::callee(slf, py)?.str()?))
+    }
+}
+
+/// Assignment instruction.
+#[pyclass(extends = CInstr, frozen, subclass)]
+pub struct CAssignInstr {}
+
+#[pymethods]
+impl CAssignInstr {
+    #[new]
+    pub fn new(parent: Py<CStmt>, xnode: Py<PyAny>) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CInstr::new(parent, xnode)).add_subclass(CAssignInstr {})
+    }
+
+    #[getter]
+    fn is_assign(&self) -> bool {
+        true
+    }
+
+    #[getter]
+    fn lhs<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let instr = slf.as_super();
+        let index = xml_index(
+            instr.xnode.bind(py),
+            "ilval",
+            "Lhs attribute missing from assign instruction",
+        )?;
+        instr.get(py, "get_lval", index)
+    }
+
+    #[getter]
+    fn rhs<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let instr = slf.as_super();
+        let index = xml_index(
+            instr.xnode.bind(py),
+            "iexp",
+            "Rhs attribute missing from assign instruction",
+        )?;
+        instr.get(py, "get_exp", index)
+    }
+
+    #[getter]
+    fn strings(slf: PyRef<Self>, py: Python) -> PyResult<Vec<String>> {
+        Self::rhs(slf, py)?
+            .call_method0(intern!(py, "get_strings"))?
+            .extract()
+    }
+
+    fn get_variable_uses(slf: &Bound<Self>, vid: isize) -> PyResult<isize> {
+        let py = slf.py();
+        let lhs_uses: isize = Self::lhs(slf.borrow(), py)?
+            .call_method1(intern!(py, "get_variable_uses"), (vid,))?
+            .extract()?;
+        let rhs_uses: isize = Self::rhs(slf.borrow(), py)?
+            .call_method1(intern!(py, "get_variable_uses"), (vid,))?
+            .extract()?;
+        Ok(lhs_uses + rhs_uses)
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: &Bound<Self>) -> PyResult<String> {
+        let py = slf.py();
+        Ok(format!(
+            "     assign: {} := {}",
+            Self::lhs(slf.borrow(), py)?.str()?,
+            Self::rhs(slf.borrow(), py)?.str()?
+        ))
+    }
+}
+
+/// Instruction representing inserted assembly code.
+#[pyclass(extends = CInstr, frozen, subclass)]
+pub struct CAsmInstr {}
+
+#[pymethods]
+impl CAsmInstr {
+    #[new]
+    pub fn new(parent: Py<CStmt>, xnode: Py<PyAny>) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CInstr::new(parent, xnode)).add_subclass(CAsmInstr {})
+    }
+
+    #[getter]
+    fn is_asm(&self) -> bool {
+        true
+    }
+
+    #[getter]
+    fn asminputs(slf: &Bound<Self>) -> PyResult<Vec<CAsmInput>> {
+        let py = slf.py();
+        let xnode = slf.borrow().as_super().xnode.bind(py).clone();
+        let Some(xinputs) = xml_find(&xnode, "asminputs")? else {
+            return Ok(Vec::new());
+        };
+        Ok(xml_findall(&xinputs, "asminput")?
+            .into_iter()
+            .map(|xinput| CAsmInput {
+                parent: slf.clone().unbind(),
+                xnode: xinput.unbind(),
+            })
+            .collect())
+    }
+
+    #[getter]
+    fn asmoutputs(slf: &Bound<Self>) -> PyResult<Vec<CAsmOutput>> {
+        let py = slf.py();
+        let xnode = slf.borrow().as_super().xnode.bind(py).clone();
+        let Some(xoutputs) = xml_find(&xnode, "asmoutputs")? else {
+            return Ok(Vec::new());
+        };
+        Ok(xml_findall(&xoutputs, "asmoutput")?
+            .into_iter()
+            .map(|xoutput| CAsmOutput {
+                parent: slf.clone().unbind(),
+                xnode: xoutput.unbind(),
+            })
+            .collect())
+    }
+
+    #[getter]
+    fn templates(slf: PyRef<Self>, py: Python) -> PyResult<Vec<String>> {
+        let instr = slf.as_super();
+        let Some(xtemplate) = xml_find(instr.xnode.bind(py), "templates")? else {
+            return Ok(Vec::new());
+        };
+        xml_indices(&xtemplate, "str-indices")?
+            .into_iter()
+            .map(|index| instr.get(py, "get_string", index)?.extract())
+            .collect()
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: &Bound<Self>) -> PyResult<String> {
+        let mut lines = Self::templates(slf.borrow(), slf.py())?;
+        for input in Self::asminputs(slf)? {
+            lines.push(format!("  {}", input.str(slf.py())?));
+        }
+        for output in Self::asmoutputs(slf)? {
+            lines.push(format!("  {}", output.str(slf.py())?));
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+#[pyclass(frozen)]
+pub struct CAsmOutput {
+    #[pyo3(get)]
+    parent: Py<CAsmInstr>,
+    #[pyo3(get)]
+    xnode: Py<PyAny>, // ET.Element
+}
+
+#[pymethods]
+impl CAsmOutput {
+    #[new]
+    fn new(parent: Py<CAsmInstr>, xnode: Py<PyAny>) -> CAsmOutput {
+        CAsmOutput { parent, xnode }
+    }
+
+    #[getter]
+    fn lhs<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let index = xml_index(
+            self.xnode.bind(py),
+            "ilval",
+            "ilval attribute missing from asm output",
+        )?;
+        let parent = self.parent.bind(py).borrow();
+        parent.as_super().get(py, "get_lval", index)
+    }
+
+    #[getter]
+    fn constraint(&self, py: Python) -> PyResult<String> {
+        Ok(xml_attr(self.xnode.bind(py), "constraint")?.unwrap_or_else(|| "none".to_string()))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "{}; lval: {}",
+            self.constraint(py)?,
+            self.lhs(py)?.str()?
+        ))
+    }
+}
+
+#[pyclass(frozen)]
+pub struct CAsmInput {
+    #[pyo3(get)]
+    parent: Py<CAsmInstr>,
+    #[pyo3(get)]
+    xnode: Py<PyAny>, // ET.Element
+}
+
+#[pymethods]
+impl CAsmInput {
+    #[new]
+    fn new(parent: Py<CAsmInstr>, xnode: Py<PyAny>) -> CAsmInput {
+        CAsmInput { parent, xnode }
+    }
+
+    #[getter]
+    fn exp<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let index = xml_index(
+            self.xnode.bind(py),
+            "iexp",
+            "iexp attribute missing from asm input",
+        )?;
+        let parent = self.parent.bind(py).borrow();
+        parent.as_super().get(py, "get_exp", index)
+    }
+
+    #[getter]
+    fn constraint(&self, py: Python) -> PyResult<String> {
+        Ok(xml_attr(self.xnode.bind(py), "constraint")?.unwrap_or_else(|| "none".to_string()))
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self, py: Python) -> PyResult<String> {
+        Ok(format!(
+            "{}; exp: {}",
+            self.constraint(py)?,
+            self.exp(py)?.str()?
+        ))
     }
 }
@@ -30,14 +30,20 @@ SOFTWARE.
 */
 //! Left-hand side value.
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use pyo3::{intern, prelude::*};
 
 use crate::{
     app::{
-        c_dictionary::CDictionary, c_dictionary_record::CDictionaryRecord, c_lhost::CLHost,
+        c_dictionary::CDictionary,
+        c_dictionary_record::CDictionaryRecord,
+        c_exp::CExp,
+        c_exp_rewrite::{self, Normalization, Substitution},
+        c_lhost::CLHost,
         c_offset::COffset,
+        c_pretty,
+        c_visitor::{Dereferences, Node, PyVisitor, VariableIds},
     },
     util::indexed_table::IndexedTableValue,
 };
@@ -90,47 +96,47 @@ impl CLval {
             .clone())
     }
 
-    // Unvalidated
     fn has_variable(&self, py: Python, vid: isize) -> PyResult<bool> {
         // Method is overridden
-        Ok(self
+        let in_lhost: bool = self
             .lhost(py)?
             .call_method1(intern!(py, "has_variable"), (vid,))?
-            .extract()?)
+            .extract()?;
+        Ok(in_lhost
+            || self
+                .offset(py)?
+                .call_method1(intern!(py, "has_variable"), (vid,))?
+                .extract()?)
     }
 
-    // Unvalidated
-    fn get_strings(&self, py: Python, vid: isize) -> PyResult<Vec<String>> {
+    fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
         // Method is overridden
         let mut result: Vec<String> = self
             .lhost(py)?
-            .call_method1(intern!(py, "get_strings"), (vid,))?
+            .call_method0(intern!(py, "get_strings"))?
             .extract()?;
         // Method is overridden
         result.append(
             &mut self
                 .offset(py)?
-                .call_method1(intern!(py, "get_strings"), (vid,))?
+                .call_method0(intern!(py, "get_strings"))?
                 .extract()?,
         );
-        return Ok(result);
+        Ok(result)
     }
 
-    // Unvalidated
-    fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<Vec<String>> {
+    fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<isize> {
         // Method is overridden
-        let mut result: Vec<String> = self
+        let in_lhost: isize = self
             .lhost(py)?
             .call_method1(intern!(py, "get_variable_uses"), (vid,))?
             .extract()?;
         // Method is overridden
-        result.append(
-            &mut self
-                .offset(py)?
-                .call_method1(intern!(py, "get_variable_uses"), (vid,))?
-                .extract()?,
-        );
-        return Ok(result);
+        let in_offset: isize = self
+            .offset(py)?
+            .call_method1(intern!(py, "get_variable_uses"), (vid,))?
+            .extract()?;
+        Ok(in_lhost + in_offset)
     }
 
     // Unvalidated
@@ -171,6 +177,53 @@ impl CLval {
         ]))
     }
 
+    /// C syntax of this lvalue.
+    fn to_c(slf: &Bound<Self>) -> PyResult<String> {
+        c_pretty::to_c(slf.as_any())
+    }
+
+    /// Address expressions of the memory references in this lvalue,
+    /// outermost first.
+    fn get_dereferenced_exps<'a>(slf: &Bound<'a, Self>) -> PyResult<Vec<Bound<'a, CExp>>> {
+        Dereferences::of(&Node::Lval(slf.clone()))
+    }
+
+    /// Ids of the variables that occur in this lvalue.
+    fn get_variable_ids(slf: &Bound<Self>) -> PyResult<BTreeSet<isize>> {
+        Node::Lval(slf.clone()).fold(&mut VariableIds)
+    }
+
+    /// Calls `enter` and `leave` with this lvalue and with each of its
+    /// subterms, depth-first, before and after the children of the subterm.
+    ///
+    /// A hook that returns a value other than None stops the traversal, and
+    /// walk returns that value; walk returns None if all nodes are visited.
+    #[pyo3(signature = (enter=None, leave=None))]
+    fn walk<'a>(
+        slf: &Bound<'a, Self>,
+        enter: Option<Bound<'a, PyAny>>,
+        leave: Option<Bound<'a, PyAny>>,
+    ) -> PyResult<Option<Bound<'a, PyAny>>> {
+        PyVisitor::walk(&Node::Lval(slf.clone()), enter, leave)
+    }
+
+    /// This lvalue with the variables with ids in `subst` replaced by the
+    /// corresponding expressions, interned in the same cdictionary.
+    fn substitute<'a>(
+        slf: &Bound<'a, Self>,
+        subst: HashMap<isize, Bound<'a, CExp>>,
+    ) -> PyResult<Bound<'a, Self>> {
+        let cd = slf.as_any().getattr(intern!(slf.py(), "cd"))?;
+        let mut substitution = Substitution::new(&cd, subst)?;
+        c_exp_rewrite::rewrite_lval(&mut substitution, slf)
+    }
+
+    /// This lvalue with `*(&x)` simplified to `x`, `&(*p)` to `p`, and casts
+    /// to the type of the operand removed.
+    fn normalize<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, Self>> {
+        c_exp_rewrite::rewrite_lval(&mut Normalization, slf)
+    }
+
     #[pyo3(name = "__str__")]
     fn str(&self, py: Python) -> PyResult<String> {
         Ok(self.lhost(py)?.str()?.extract::<String>()?
@@ -36,13 +36,21 @@ use pyo3::{exceptions::PyException, intern, prelude::*};
 
 use crate::{
     app::{
+        c_comp_info::CCompInfo,
+        c_data_model::{data_model, CDataModel},
         c_dictionary::CDictionary,
         c_dictionary_record::{CDictionaryRecord, CDictionaryRegistryEntry},
         c_exp::CExp,
+        c_exp_eval,
+        c_field_info::CFieldInfo,
+        c_pretty,
+        c_typ::{CTyp, CTypArray, CTypComp},
     },
     util::indexed_table::IndexedTableValue,
 };
 
+pyo3::import_exception!(chc.util.fileutil, CHCError);
+
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "c_offset")?;
     module.add_class::<COffset>()?;
@@ -114,26 +122,50 @@ impl COffset {
         matches!(self.typ, COffsetType::CIndexOffset { .. })
     }
 
+    fn has_variable(&self, py: Python, vid: isize) -> PyResult<bool> {
+        match self.typ {
+            COffsetType::CNoOffset => Ok(false),
+            COffsetType::CFieldOffset { .. } => self.offset(py)?.get().has_variable(py, vid),
+            COffsetType::CIndexOffset { .. } => {
+                // Resolve with python interpreter in case this method is overridden
+                let in_index: bool = self
+                    .index_exp(py)?
+                    .call_method1(intern!(py, "has_variable"), (vid,))?
+                    .extract()?;
+                Ok(in_index || self.offset(py)?.get().has_variable(py, vid)?)
+            }
+        }
+    }
+
     fn get_strings(&self, py: Python) -> PyResult<Vec<String>> {
-        if !matches!(self.typ, COffsetType::CIndexOffset { .. }) {
-            return Ok(vec![]);
+        match self.typ {
+            COffsetType::CNoOffset => Ok(vec![]),
+            COffsetType::CFieldOffset { .. } => self.offset(py)?.get().get_strings(py),
+            COffsetType::CIndexOffset { .. } => {
+                // Resolve with python interpreter in case this method is overridden
+                let mut result: Vec<String> = self
+                    .index_exp(py)?
+                    .call_method0(intern!(py, "get_strings"))?
+                    .extract()?;
+                result.extend(self.offset(py)?.get().get_strings(py)?);
+                Ok(result)
+            }
         }
-        // Resolve with python interpreter in case this method is overridden
-        Ok(self
-            .index_exp(py)?
-            .call_method0(intern!(py, "get_strings"))?
-            .extract()?)
     }
 
     fn get_variable_uses(&self, py: Python, vid: isize) -> PyResult<isize> {
-        if !matches!(self.typ, COffsetType::CIndexOffset { .. }) {
-            return Ok(0);
+        match self.typ {
+            COffsetType::CNoOffset => Ok(0),
+            COffsetType::CFieldOffset { .. } => self.offset(py)?.get().get_variable_uses(py, vid),
+            COffsetType::CIndexOffset { .. } => {
+                // Resolve with python interpreter in case this method is overridden
+                let in_index: isize = self
+                    .index_exp(py)?
+                    .call_method1(intern!(py, "get_variable_uses"), (vid,))?
+                    .extract()?;
+                Ok(in_index + self.offset(py)?.get().get_variable_uses(py, vid)?)
+            }
         }
-        // Resolve with python interpreter in case this method is overridden
-        Ok(self
-            .index_exp(py)?
-            .call_method1(intern!(py, "get_variable_uses"), (vid,))?
-            .extract()?)
     }
 
     // Unvalidated
@@ -213,6 +245,20 @@ impl COffset {
         Ok(*ckey)
     }
 
+    /// Byte offset from the start of an object of type `base` in `model`
+    /// (default: the current data model), or None if an index is not an
+    /// integer constant expression, a type has no size, or the offset
+    /// overflows.
+    #[pyo3(signature = (base, model=None))]
+    fn resolve(&self, base: &Bound<CTyp>, model: Option<CDataModel>) -> PyResult<Option<isize>> {
+        self.byte_offset(base, &model.unwrap_or_else(data_model))
+    }
+
+    /// C syntax of this offset applied to an empty base, e.g. `.f[3]`.
+    fn to_c(slf: &Bound<Self>) -> PyResult<String> {
+        c_pretty::to_c(slf.as_any())
+    }
+
     #[pyo3(name = "__str__")]
     fn str(&self, py: Python) -> PyResult<String> {
         match self.typ {
@@ -233,6 +279,68 @@ impl COffset {
     }
 }
 
+impl COffset {
+    /// Byte offset from the start of an object of type `base`, or None if it
+    /// cannot be computed or overflows.
+    pub fn byte_offset(&self, base: &Bound<CTyp>, model: &CDataModel) -> PyResult<Option<isize>> {
+        let py = base.py();
+        let base = CTyp::unroll(base)?;
+        match &self.typ {
+            COffsetType::CNoOffset => Ok(Some(0)),
+            COffsetType::CFieldOffset { fieldname, .. } => {
+                let comp = base.downcast::<CTypComp>().map_err(|_| {
+                    CHCError::new_err(format!(
+                        "Field offset .{fieldname} applied to non-struct type {}",
+                        base.str()
+                            .map_or_else(|_| "?".to_string(), |s| s.to_string())
+                    ))
+                })?;
+                let compinfo = CTypComp::compinfo(comp)?;
+                let Some(layout) = CCompInfo::comp_layout(&compinfo, model)? else {
+                    return Ok(None);
+                };
+                let fields = CCompInfo::fields(&compinfo)?;
+                let Some((field, placement)) = fields
+                    .iter()
+                    .zip(&layout.fields)
+                    .find(|(field, _)| CFieldInfo::fname(field.borrow()) == *fieldname)
+                else {
+                    return Err(CHCError::new_err(format!(
+                        "Field {fieldname} not found in struct {}",
+                        CCompInfo::name(&compinfo)?
+                    )));
+                };
+                let suboffset = self
+                    .offset(py)?
+                    .get()
+                    .byte_offset(&CFieldInfo::ftype(field)?, model)?;
+                Ok(suboffset.and_then(|suboffset| placement.offset().checked_add(suboffset)))
+            }
+            COffsetType::CIndexOffset { .. } => {
+                let array = base.downcast::<CTypArray>().map_err(|_| {
+                    CHCError::new_err(format!(
+                        "Index offset applied to non-array type {}",
+                        base.str()
+                            .map_or_else(|_| "?".to_string(), |s| s.to_string())
+                    ))
+                })?;
+                let Some(index) = c_exp_eval::evaluate(&self.index_exp(py)?, model)?
+                    .and_then(|index| isize::try_from(index.value).ok())
+                else {
+                    return Ok(None);
+                };
+                let Some(element) = CTypArray::element_layout(array, model)? else {
+                    return Ok(None);
+                };
+                let element_type = CTypArray::array_basetype(array.borrow(), py)?;
+                let suboffset = self.offset(py)?.get().byte_offset(&element_type, model)?;
+                Ok(suboffset
+                    .and_then(|suboffset| index.checked_mul(element.size)?.checked_add(suboffset)))
+            }
+        }
+    }
+}
+
 fn create_no_offset<'a, 'b>(
     cd: &'a Bound<'b, CDictionary>,
     ixval: IndexedTableValue,
@@ -0,0 +1,545 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! C syntax for types, expressions, lvalues and offsets.
+//!
+//! Unlike the `__str__` methods, which show the structure of the cdictionary
+//! records, the printer renders items the way they would be written in a C
+//! source file: types as declarations, expressions with the minimal number of
+//! parentheses, and field accesses through pointers as `p->f`.
+//!
+//! Items are inspected through their python attributes, so variants that are
+//! still implemented in python (function types, lhosts) are printed as well.
+
+use pyo3::{exceptions::PyTypeError, intern, prelude::*, types::PyString};
+
+use crate::app::{
+    c_exp::CExp,
+    c_lval::CLval,
+    c_offset::COffset,
+    c_typ::{CTyp, FLOAT_NAMES, INTEGER_NAMES},
+};
+
+pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
+    let module = PyModule::new_bound(py, "c_pretty")?;
+    module.add_function(wrap_pyfunction!(to_c, &module)?)?;
+    module.add_function(wrap_pyfunction!(declaration, &module)?)?;
+    Ok(module)
+}
+
+/// C syntax of a type, expression, lvalue or offset.
+///
+/// An offset is printed relative to an empty base, e.g. `.f[3]`.
+#[pyfunction]
+pub fn to_c(item: &Bound<PyAny>) -> PyResult<String> {
+    if item.is_instance_of::<CTyp>() {
+        typ_to_c(item, "")
+    } else if item.is_instance_of::<CExp>() {
+        Ok(exp_to_c(item)?.text)
+    } else if item.is_instance_of::<CLval>() {
+        Ok(lval_to_c(item)?.text)
+    } else if item.is_instance_of::<COffset>() {
+        Ok(offset_to_c(Printed::new(String::new(), POSTFIX), item)?.text)
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "cannot print {} as C",
+            item.get_type().name()?
+        )))
+    }
+}
+
+/// Declaration of `name` with type `typ`, e.g. `int (*name)[4]`.
+#[pyfunction]
+pub fn declaration(typ: &Bound<CTyp>, name: &str) -> PyResult<String> {
+    typ_to_c(typ.as_any(), name)
+}
+
+// Precedence levels of C operators, from loosest to tightest binding.
+const CONDITIONAL: u8 = 3;
+const LOGICAL_OR: u8 = 4;
+const LOGICAL_AND: u8 = 5;
+const BITWISE_OR: u8 = 6;
+const BITWISE_XOR: u8 = 7;
+const BITWISE_AND: u8 = 8;
+const EQUALITY: u8 = 9;
+const RELATIONAL: u8 = 10;
+const SHIFT: u8 = 11;
+const ADDITIVE: u8 = 12;
+const MULTIPLICATIVE: u8 = 13;
+const CAST: u8 = 14;
+const UNARY: u8 = 15;
+const POSTFIX: u8 = 16;
+
+/// Printed item together with the precedence of its outermost operator.
+struct Printed {
+    text: String,
+    precedence: u8,
+}
+
+impl Printed {
+    fn new(text: String, precedence: u8) -> Self {
+        Printed { text, precedence }
+    }
+
+    /// Text, parenthesized if it binds less tightly than `precedence`.
+    fn at(&self, precedence: u8) -> String {
+        if self.precedence < precedence {
+            format!("({})", self.text)
+        } else {
+            self.text.clone()
+        }
+    }
+}
+
+fn flag(item: &Bound<PyAny>, name: &Bound<PyString>) -> PyResult<bool> {
+    item.getattr(name)?.extract()
+}
+
+fn binop(op: &str) -> PyResult<(&'static str, u8)> {
+    Ok(match op {
+        "band" => ("&", BITWISE_AND),
+        "bor" => ("|", BITWISE_OR),
+        "bxor" => ("^", BITWISE_XOR),
+        "div" => ("/", MULTIPLICATIVE),
+        "eq" => ("==", EQUALITY),
+        "ge" => (">=", RELATIONAL),
+        "gt" => (">", RELATIONAL),
+        "land" => ("&&", LOGICAL_AND),
+        "le" => ("<=", RELATIONAL),
+        "lor" => ("||", LOGICAL_OR),
+        "lt" => ("<", RELATIONAL),
+        "mod" => ("%", MULTIPLICATIVE),
+        "mult" => ("*", MULTIPLICATIVE),
+        "ne" => ("!=", EQUALITY),
+        "indexpi" | "plusa" | "pluspi" => ("+", ADDITIVE),
+        "minusa" | "minuspi" | "minuspp" => ("-", ADDITIVE),
+        "shiftlt" => ("<<", SHIFT),
+        "shiftrt" => (">>", SHIFT),
+        _ => {
+            return Err(PyTypeError::new_err(format!(
+                "unknown binary operator '{op}'"
+            )))
+        }
+    })
+}
+
+fn unop(op: &str) -> PyResult<&'static str> {
+    Ok(match op {
+        "neg" => "-",
+        "bnot" => "~",
+        "lnot" => "!",
+        _ => {
+            return Err(PyTypeError::new_err(format!(
+                "unknown unary operator '{op}'"
+            )))
+        }
+    })
+}
+
+/// Qualifiers and attributes of a type, as written before a base type or
+/// after a `*`.
+fn qualifiers(typ: &Bound<PyAny>) -> PyResult<Vec<String>> {
+    let py = typ.py();
+    let attributes = typ
+        .getattr(intern!(py, "attributes"))?
+        .getattr(intern!(py, "attributes"))?;
+    let mut result = Vec::new();
+    for attribute in attributes.iter()? {
+        let attribute = attribute?;
+        let name: String = attribute.getattr(intern!(py, "name"))?.extract()?;
+        match name.as_str() {
+            "const" | "volatile" | "restrict" => result.push(name),
+            _ => {
+                let params = attribute
+                    .getattr(intern!(py, "params"))?
+                    .iter()?
+                    .map(|param| Ok(param?.str()?.to_string()))
+                    .collect::<PyResult<Vec<String>>>()?;
+                if params.is_empty() {
+                    result.push(format!("__attribute__(({name}))"));
+                } else {
+                    result.push(format!("__attribute__(({name}({})))", params.join(", ")));
+                }
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Declarator `decl` wrapped so that a suffix (`[]` or `()`) applies to it
+/// rather than to the type it points to.
+fn suffixable(decl: &str) -> String {
+    if decl.starts_with('*') {
+        format!("({decl})")
+    } else {
+        decl.to_string()
+    }
+}
+
+/// Type `typ` applied to declarator `decl`; an empty declarator gives the
+/// abstract type name.
+fn typ_to_c(typ: &Bound<PyAny>, decl: &str) -> PyResult<String> {
+    let py = typ.py();
+    if flag(typ, intern!(py, "is_pointer"))? {
+        let target = typ.getattr(intern!(py, "pointedto_type"))?;
+        let mut inner = String::from("*");
+        for qualifier in qualifiers(typ)? {
+            inner.push_str(&qualifier);
+            inner.push(' ');
+        }
+        inner.push_str(decl);
+        return typ_to_c(&target, inner.trim_end());
+    }
+    if flag(typ, intern!(py, "is_array"))? {
+        let base = typ.getattr(intern!(py, "array_basetype"))?;
+        let has_size: bool = typ
+            .call_method0(intern!(py, "has_array_size_expr"))?
+            .extract()?;
+        let size = if has_size {
+            exp_to_c(&typ.getattr(intern!(py, "array_size_expr"))?)?.text
+        } else {
+            String::new()
+        };
+        return typ_to_c(&base, &format!("{}[{size}]", suffixable(decl)));
+    }
+    if flag(typ, intern!(py, "is_function"))? {
+        let return_type = typ.getattr(intern!(py, "return_type"))?;
+        let params = parameters(typ)?;
+        return typ_to_c(&return_type, &format!("{}({params})", suffixable(decl)));
+    }
+    let base = if flag(typ, intern!(py, "is_void"))? {
+        "void".to_string()
+    } else if flag(typ, intern!(py, "is_int"))? {
+        let ikind: String = typ.getattr(intern!(py, "ikind"))?.extract()?;
+        let names = INTEGER_NAMES;
+        names
+            .get(ikind.as_str())
+            .ok_or_else(|| PyTypeError::new_err(format!("unknown type '{ikind}'")))?
+            .to_string()
+    } else if flag(typ, intern!(py, "is_float"))? {
+        let fkind: String = typ.getattr(intern!(py, "fkind"))?.extract()?;
+        let names = FLOAT_NAMES;
+        names
+            .get(fkind.as_str())
+            .ok_or_else(|| PyTypeError::new_err(format!("unknown type '{fkind}'")))?
+            .to_string()
+    } else if flag(typ, intern!(py, "is_named_type"))? {
+        typ.getattr(intern!(py, "name"))?.extract()?
+    } else if flag(typ, intern!(py, "is_comp"))? {
+        let keyword = if flag(typ, intern!(py, "is_struct"))? {
+            "struct"
+        } else {
+            "union"
+        };
+        let name: String = typ.getattr(intern!(py, "name"))?.extract()?;
+        format!("{keyword} {name}")
+    } else if flag(typ, intern!(py, "is_enum"))? {
+        let name: String = typ.getattr(intern!(py, "name"))?.extract()?;
+        format!("enum {name}")
+    } else if flag(typ, intern!(py, "is_builtin_vaargs"))? {
+        "__builtin_va_list".to_string()
+    } else {
+        return Err(PyTypeError::new_err(format!(
+            "cannot print type {} as C",
+            typ.str()?
+        )));
+    };
+    let mut result = qualifiers(typ)?;
+    result.push(base);
+    if !decl.is_empty() {
+        result.push(decl.to_string());
+    }
+    Ok(result.join(" "))
+}
+
+/// Parameter list of a function type, without the parentheses.
+fn parameters(typ: &Bound<PyAny>) -> PyResult<String> {
+    let py = typ.py();
+    let funargs = typ.getattr(intern!(py, "funargs"))?;
+    if funargs.is_none() {
+        return Ok(String::new());
+    }
+    let mut params = Vec::new();
+    for funarg in funargs.getattr(intern!(py, "arguments"))?.iter()? {
+        let funarg = funarg?;
+        let name: String = funarg.getattr(intern!(py, "name"))?.extract()?;
+        // Names of parameters of prototypes without names are made up by CIL
+        let name = if name.starts_with("$par$") || name == "__" {
+            ""
+        } else {
+            name.as_str()
+        };
+        params.push(typ_to_c(&funarg.getattr(intern!(py, "typ"))?, name)?);
+    }
+    if flag(typ, intern!(py, "is_vararg"))? {
+        params.push("...".to_string());
+    }
+    if params.is_empty() {
+        Ok("void".to_string())
+    } else {
+        Ok(params.join(", "))
+    }
+}
+
+/// Argument list of an application; arguments that are not known are
+/// printed as comments to keep the argument positions.
+fn arguments(exp: &Bound<PyAny>) -> PyResult<String> {
+    let mut result = Vec::new();
+    for argument in exp.getattr(intern!(exp.py(), "arguments"))?.iter()? {
+        let argument = argument?;
+        if argument.is_none() {
+            result.push("/* unknown */".to_string());
+        } else {
+            result.push(exp_to_c(&argument)?.text);
+        }
+    }
+    Ok(result.join(", "))
+}
+
+fn exp_to_c(exp: &Bound<PyAny>) -> PyResult<Printed> {
+    let py = exp.py();
+    let get = |name: &Bound<PyString>| exp.getattr(name);
+    if flag(exp, intern!(py, "is_constant"))? {
+        const_to_c(&get(intern!(py, "constant"))?)
+    } else if flag(exp, intern!(py, "is_lval"))? {
+        lval_to_c(&get(intern!(py, "lval"))?)
+    } else if flag(exp, intern!(py, "is_sizeof"))? {
+        let typ = typ_to_c(&get(intern!(py, "typ"))?, "")?;
+        Ok(Printed::new(format!("sizeof({typ})"), UNARY))
+    } else if flag(exp, intern!(py, "is_sizeofe"))? {
+        let operand = exp_to_c(&get(intern!(py, "exp"))?)?;
+        Ok(Printed::new(format!("sizeof({})", operand.text), UNARY))
+    } else if flag(exp, intern!(py, "is_sizeofstr"))? {
+        let strg: String = get(intern!(py, "stringvalue"))?.extract()?;
+        Ok(Printed::new(
+            format!("sizeof({})", string_literal(&strg)),
+            UNARY,
+        ))
+    } else if flag(exp, intern!(py, "is_alignof"))? {
+        let typ = typ_to_c(&get(intern!(py, "typ"))?, "")?;
+        Ok(Printed::new(format!("_Alignof({typ})"), UNARY))
+    } else if flag(exp, intern!(py, "is_alignofe"))? {
+        let operand = exp_to_c(&get(intern!(py, "exp"))?)?;
+        Ok(Printed::new(
+            format!("__alignof__({})", operand.text),
+            UNARY,
+        ))
+    } else if flag(exp, intern!(py, "is_unop"))? {
+        let op: String = get(intern!(py, "op"))?.extract()?;
+        let operand = exp_to_c(&get(intern!(py, "exp"))?)?.at(CAST);
+        let op = unop(&op)?;
+        // Keep "- -x" from turning into a decrement
+        let sep = if operand.starts_with(op) { " " } else { "" };
+        Ok(Printed::new(format!("{op}{sep}{operand}"), UNARY))
+    } else if flag(exp, intern!(py, "is_binop"))? {
+        let op: String = get(intern!(py, "op"))?.extract()?;
+        let (op, precedence) = binop(&op)?;
+        let exp1 = exp_to_c(&get(intern!(py, "exp1"))?)?.at(precedence);
+        let exp2 = exp_to_c(&get(intern!(py, "exp2"))?)?.at(precedence + 1);
+        Ok(Printed::new(format!("{exp1} {op} {exp2}"), precedence))
+    } else if flag(exp, intern!(py, "is_question"))? {
+        let condition = exp_to_c(&get(intern!(py, "condition"))?)?.at(LOGICAL_OR);
+        let true_exp = exp_to_c(&get(intern!(py, "true_exp"))?)?.text;
+        let false_exp = exp_to_c(&get(intern!(py, "false_exp"))?)?.at(CONDITIONAL);
+        Ok(Printed::new(
+            format!("{condition} ? {true_exp} : {false_exp}"),
+            CONDITIONAL,
+        ))
+    } else if flag(exp, intern!(py, "is_caste"))? {
+        let typ = typ_to_c(&get(intern!(py, "typ"))?, "")?;
+        let operand = exp_to_c(&get(intern!(py, "exp"))?)?.at(CAST);
+        Ok(Printed::new(format!("({typ}){operand}"), CAST))
+    } else if flag(exp, intern!(py, "is_addrof"))? {
+        let lval = lval_to_c(&get(intern!(py, "lval"))?)?.at(CAST);
+        Ok(Printed::new(format!("&{lval}"), UNARY))
+    } else if flag(exp, intern!(py, "is_startof"))? {
+        // The array decays to a pointer to its first element
+        lval_to_c(&get(intern!(py, "lval"))?)
+    } else if flag(exp, intern!(py, "is_fn_app"))? {
+        let function = exp_to_c(&get(intern!(py, "exp"))?)?.at(POSTFIX);
+        Ok(Printed::new(
+            format!("{function}({})", arguments(exp)?),
+            POSTFIX,
+        ))
+    } else if flag(exp, intern!(py, "is_cn_app"))? {
+        let name: String = get(intern!(py, "name"))?.extract()?;
+        Ok(Printed::new(
+            format!("{name}({})", arguments(exp)?),
+            POSTFIX,
+        ))
+    } else if exp.hasattr(intern!(py, "label_sid"))? {
+        // Labels are not named in the cdictionary; use the statement id
+        let sid: isize = get(intern!(py, "label_sid"))?.extract()?;
+        Ok(Printed::new(format!("&&label_{sid}"), UNARY))
+    } else {
+        Err(PyTypeError::new_err(format!(
+            "cannot print expression {} as C",
+            exp.str()?
+        )))
+    }
+}
+
+fn const_to_c(constant: &Bound<PyAny>) -> PyResult<Printed> {
+    let py = constant.py();
+    let text = if flag(constant, intern!(py, "is_int"))? {
+        let value: i128 = constant.getattr(intern!(py, "intvalue"))?.extract()?;
+        let ikind: String = constant.getattr(intern!(py, "ikind"))?.extract()?;
+        let suffix = match ikind.as_str() {
+            "iuint" => "U",
+            "ilong" => "L",
+            "iulong" => "UL",
+            "ilonglong" => "LL",
+            "iulonglong" => "ULL",
+            _ => "",
+        };
+        let text = format!("{value}{suffix}");
+        if value < 0 {
+            return Ok(Printed::new(text, UNARY));
+        }
+        text
+    } else if flag(constant, intern!(py, "is_str"))? {
+        string_literal(
+            &constant
+                .getattr(intern!(py, "stringvalue"))?
+                .extract::<String>()?,
+        )
+    } else if flag(constant, intern!(py, "is_wstr"))? {
+        let strg: String = constant.getattr(intern!(py, "stringvalue"))?.extract()?;
+        format!("L{}", string_literal(&strg))
+    } else if flag(constant, intern!(py, "is_chr"))? {
+        let value: i64 = constant.getattr(intern!(py, "chrvalue"))?.extract()?;
+        char_literal(value)
+    } else if flag(constant, intern!(py, "is_real"))? {
+        let value: f64 = constant.getattr(intern!(py, "realvalue"))?.extract()?;
+        let fkind: String = constant.getattr(intern!(py, "fkind"))?.extract()?;
+        let suffix = match fkind.as_str() {
+            "float" => "f",
+            "flongdouble" => "L",
+            _ => "",
+        };
+        let text = format!("{value:?}{suffix}");
+        if value.is_sign_negative() {
+            return Ok(Printed::new(text, UNARY));
+        }
+        text
+    } else if flag(constant, intern!(py, "is_enum"))? {
+        constant.getattr(intern!(py, "item_name"))?.extract()?
+    } else {
+        return Err(PyTypeError::new_err(format!(
+            "cannot print constant {} as C",
+            constant.str()?
+        )));
+    };
+    Ok(Printed::new(text, POSTFIX))
+}
+
+/// `c` as it appears in a literal quoted with `quote`. Other characters than
+/// printable ascii are written as three-digit octal escapes, which cannot run
+/// into a digit that follows: of the byte for latin-1 chars, which is how the
+/// string tables hold the bytes of a literal, and of the utf-8 bytes for
+/// others.
+fn escape(c: char, quote: char) -> String {
+    match c {
+        '\n' => "\\n".to_string(),
+        '\t' => "\\t".to_string(),
+        '\r' => "\\r".to_string(),
+        '\\' => "\\\\".to_string(),
+        c if c == quote => format!("\\{c}"),
+        c if c.is_ascii_graphic() || c == ' ' => c.to_string(),
+        c if (c as u32) <= 0xff => octal_escape(c as u8),
+        c => c
+            .encode_utf8(&mut [0; 4])
+            .bytes()
+            .map(octal_escape)
+            .collect(),
+    }
+}
+
+fn octal_escape(byte: u8) -> String {
+    format!("\\{byte:03o}")
+}
+
+fn string_literal(strg: &str) -> String {
+    format!(
+        "\"{}\"",
+        strg.chars().map(|c| escape(c, '"')).collect::<String>()
+    )
+}
+
+fn char_literal(value: i64) -> String {
+    match u8::try_from(value) {
+        Ok(byte) if byte.is_ascii() => format!("'{}'", escape(byte as char, '\'')),
+        Ok(byte) => format!("'{}'", octal_escape(byte)),
+        Err(_) => value.to_string(),
+    }
+}
+
+fn lval_to_c(lval: &Bound<PyAny>) -> PyResult<Printed> {
+    let py = lval.py();
+    let lhost = lval.getattr(intern!(py, "lhost"))?;
+    let offset = lval.getattr(intern!(py, "offset"))?;
+    if flag(&lhost, intern!(py, "is_var"))? {
+        let name: String = lhost.getattr(intern!(py, "name"))?.extract()?;
+        return offset_to_c(Printed::new(name, POSTFIX), &offset);
+    }
+    let address = exp_to_c(&lhost.getattr(intern!(py, "exp"))?)?;
+    if flag(&offset, intern!(py, "is_field"))? {
+        // (*p).f is written as p->f
+        let fieldname: String = offset.getattr(intern!(py, "fieldname"))?.extract()?;
+        let base = format!("{}->{fieldname}", address.at(POSTFIX));
+        return offset_to_c(
+            Printed::new(base, POSTFIX),
+            &offset.getattr(intern!(py, "offset"))?,
+        );
+    }
+    offset_to_c(
+        Printed::new(format!("*{}", address.at(CAST)), UNARY),
+        &offset,
+    )
+}
+
+/// Access of `offset` in `base`.
+fn offset_to_c(base: Printed, offset: &Bound<PyAny>) -> PyResult<Printed> {
+    let py = offset.py();
+    if flag(offset, intern!(py, "is_no_offset"))? {
+        return Ok(base);
+    }
+    let accessed = if flag(offset, intern!(py, "is_field"))? {
+        let fieldname: String = offset.getattr(intern!(py, "fieldname"))?.extract()?;
+        format!("{}.{fieldname}", base.at(POSTFIX))
+    } else {
+        let index = exp_to_c(&offset.getattr(intern!(py, "index_exp"))?)?;
+        format!("{}[{}]", base.at(POSTFIX), index.text)
+    };
+    offset_to_c(
+        Printed::new(accessed, POSTFIX),
+        &offset.getattr(intern!(py, "offset"))?,
+    )
+}
@@ -30,14 +30,108 @@ SOFTWARE.
 */
 //! Control flow element in a function.
 
-use pyo3::prelude::*;
+use once_cell::sync::OnceCell;
+use pyo3::{
+    intern,
+    prelude::*,
+    types::{PyDict, PyString},
+};
+
+use crate::app::c_instr::{CAsmInstr, CAssignInstr, CCallInstr, CInstr};
+
+pyo3::import_exception!(chc.util.fileutil, CHCError);
 
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "c_stmt")?;
     module.add_class::<CStmt>()?;
+    module.add_class::<CBlock>()?;
+    module.add_class::<CFunctionBody>()?;
+    module.add_class::<CIfStmt>()?;
+    module.add_class::<CLoopStmt>()?;
+    module.add_class::<CSwitchStmt>()?;
+    module.add_class::<CBreakStmt>()?;
+    module.add_class::<CContinueStmt>()?;
+    module.add_class::<CGotoStmt>()?;
+    module.add_class::<CReturnStmt>()?;
+    module.add_class::<CInstrsStmt>()?;
+    module.add_function(wrap_pyfunction!(get_statement, &module)?)?;
     Ok(module)
 }
 
+/// Value of attribute `name` of xml element `xnode`, if present.
+pub(crate) fn xml_attr(xnode: &Bound<PyAny>, name: &str) -> PyResult<Option<String>> {
+    xnode
+        .call_method1(intern!(xnode.py(), "get"), (name,))?
+        .extract()
+}
+
+/// First child element of `xnode` with tag `tag`, if present.
+pub(crate) fn xml_find<'py>(
+    xnode: &Bound<'py, PyAny>,
+    tag: &str,
+) -> PyResult<Option<Bound<'py, PyAny>>> {
+    let child = xnode.call_method1(intern!(xnode.py(), "find"), (tag,))?;
+    Ok((!child.is_none()).then_some(child))
+}
+
+/// Child elements of `xnode` with tag `tag`.
+pub(crate) fn xml_findall<'py>(
+    xnode: &Bound<'py, PyAny>,
+    tag: &str,
+) -> PyResult<Vec<Bound<'py, PyAny>>> {
+    xnode
+        .call_method1(intern!(xnode.py(), "findall"), (tag,))?
+        .extract()
+}
+
+/// Comma-separated list of integers in attribute `name` of `xnode`.
+pub(crate) fn xml_indices(xnode: &Bound<PyAny>, name: &str) -> PyResult<Vec<isize>> {
+    let Some(value) = xml_attr(xnode, name)? else {
+        return Ok(Vec::new());
+    };
+    value
+        .split(',')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse::<isize>()
+                .map_err(|_| CHCError::new_err(format!("Invalid index {s} in {name}")))
+        })
+        .collect()
+}
+
+/// Return the appropriate kind of CStmt dependent on the stmt kind.
+#[pyfunction]
+pub fn get_statement<'py>(
+    parent: &Bound<'py, CStmt>,
+    xnode: &Bound<'py, PyAny>,
+) -> PyResult<Bound<'py, CStmt>> {
+    let py = parent.py();
+    let Some(knode) = xml_find(xnode, "skind")? else {
+        return Err(CHCError::new_err("missing element `skind`"));
+    };
+    let Some(tag) = xml_attr(&knode, "stag")? else {
+        return Err(CHCError::new_err("missing attribute `stag`"));
+    };
+    let parent = parent.clone().unbind();
+    let xnode = xnode.clone().unbind();
+    let stmt = match tag.as_str() {
+        "instr" => Bound::new(py, CInstrsStmt::new(parent, xnode))?.into_any(),
+        "if" => Bound::new(py, CIfStmt::new(parent, xnode))?.into_any(),
+        "loop" => Bound::new(py, CLoopStmt::new(parent, xnode))?.into_any(),
+        "break" => Bound::new(py, CBreakStmt::new(parent, xnode))?.into_any(),
+        "return" => Bound::new(py, CReturnStmt::new(parent, xnode))?.into_any(),
+        "goto" => Bound::new(py, CGotoStmt::new(parent, xnode))?.into_any(),
+        "switch" => Bound::new(py, CSwitchStmt::new(parent, xnode))?.into_any(),
+        "continue" => Bound::new(py, CContinueStmt::new(parent, xnode))?.into_any(),
+        _ => {
+            return Err(CHCError::new_err(format!(
+                "Unknown statement tag found: {tag}"
+            )))
+        }
+    };
+    Ok(stmt.downcast_into()?)
+}
+
 /// Superclass of all control flow components in a function.
 #[pyclass(frozen, subclass)]
 pub struct CStmt {
@@ -45,6 +139,8 @@ pub struct CStmt {
     parent: Option<Py<CStmt>>,
     #[pyo3(get)]
     xnode: Py<PyAny>, // ET.Element
+    /// Statements nested in this statement, created on first use.
+    stmts: OnceCell<Vec<Py<CStmt>>>,
 }
 
 #[pymethods]
@@ -52,6 +148,523 @@ impl CStmt {
     #[new]
     #[pyo3(signature = (parent, xnode))]
     fn new(parent: Option<Py<CStmt>>, xnode: Py<PyAny>) -> CStmt {
-        CStmt { parent, xnode }
+        CStmt {
+            parent,
+            xnode,
+            stmts: OnceCell::new(),
+        }
+    }
+
+    #[getter]
+    fn cfun<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        match &self.parent {
+            // Method is overridden
+            Some(parent) => parent.bind(py).getattr(intern!(py, "cfun")),
+            None => Err(CHCError::new_err("Unable to chain back to function body")),
+        }
+    }
+
+    #[getter]
+    fn cfile<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        slf.getattr(intern!(py, "cfun"))?
+            .getattr(intern!(py, "cfile"))
+    }
+
+    #[getter]
+    pub fn cdictionary<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        Self::cfile(slf)?.getattr(intern!(py, "dictionary"))
+    }
+
+    #[getter]
+    pub fn sid(&self, py: Python) -> PyResult<isize> {
+        match xml_attr(self.xnode.bind(py), "sid")? {
+            Some(sid) => sid
+                .parse()
+                .map_err(|_| CHCError::new_err(format!("invalid sid {sid}"))),
+            None => Err(CHCError::new_err("sid missing from stmt")),
+        }
+    }
+
+    #[getter]
+    fn xskind<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        xml_find(self.xnode.bind(py), "skind")?
+            .ok_or_else(|| CHCError::new_err("skind missing from stmt"))
+    }
+
+    #[getter]
+    fn kind(&self, py: Python) -> PyResult<String> {
+        xml_attr(&self.xskind(py)?, "stag")?
+            .ok_or_else(|| CHCError::new_err("stag missing from stmt"))
+    }
+
+    #[getter]
+    pub fn preds(&self, py: Python) -> PyResult<Vec<isize>> {
+        match xml_find(self.xnode.bind(py), "preds")? {
+            Some(xpreds) => xml_indices(&xpreds, "r"),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    #[getter]
+    pub fn succs(&self, py: Python) -> PyResult<Vec<isize>> {
+        match xml_find(self.xnode.bind(py), "succs")? {
+            Some(xsuccs) => xml_indices(&xsuccs, "r"),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// Statements nested in this statement, keyed by sid.
+    #[getter]
+    fn stmts<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyDict>> {
+        let py = slf.py();
+        let result = PyDict::new_bound(py);
+        for stmt in Self::nested(slf)? {
+            result.set_item(stmt.get().sid(py)?, stmt)?;
+        }
+        Ok(result)
+    }
+
+    fn iter_stmts(slf: &Bound<Self>, f: &Bound<PyAny>) -> PyResult<()> {
+        for stmt in Self::nested(slf)? {
+            f.call1((stmt,))?;
+        }
+        Ok(())
+    }
+
+    #[getter]
+    fn is_instrs_stmt(&self) -> bool {
+        false
+    }
+
+    #[getter]
+    fn is_if_stmt(&self) -> bool {
+        false
+    }
+
+    #[getter]
+    fn is_block_stmt(&self) -> bool {
+        false
+    }
+
+    #[getter]
+    fn is_function_body(&self) -> bool {
+        false
+    }
+
+    #[getter]
+    fn block_count(slf: &Bound<Self>) -> PyResult<isize> {
+        sum_nested(slf, intern!(slf.py(), "block_count"))
+    }
+
+    #[getter]
+    fn stmt_count(slf: &Bound<Self>) -> PyResult<isize> {
+        Ok(sum_nested(slf, intern!(slf.py(), "stmt_count"))? + 1)
+    }
+
+    #[getter]
+    fn instr_count(slf: &Bound<Self>) -> PyResult<isize> {
+        sum_nested(slf, intern!(slf.py(), "instr_count"))
+    }
+
+    #[getter]
+    fn call_instrs<'a>(slf: &Bound<'a, Self>) -> PyResult<Vec<Bound<'a, PyAny>>> {
+        concat_nested(slf, intern!(slf.py(), "call_instrs"))
+    }
+
+    #[getter]
+    fn strings(slf: &Bound<Self>) -> PyResult<Vec<String>> {
+        let mut result = Vec::new();
+        for stmt in Self::nested(slf)? {
+            // Method is overridden
+            result.extend(
+                stmt.getattr(intern!(slf.py(), "strings"))?
+                    .extract::<Vec<String>>()?,
+            );
+        }
+        Ok(result)
+    }
+
+    fn get_variable_uses(slf: &Bound<Self>, vid: isize) -> PyResult<isize> {
+        let mut result = 0;
+        for stmt in Self::nested(slf)? {
+            // Method is overridden
+            result += stmt
+                .call_method1(intern!(slf.py(), "get_variable_uses"), (vid,))?
+                .extract::<isize>()?;
+        }
+        Ok(result)
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: &Bound<Self>) -> PyResult<String> {
+        let py = slf.py();
+        let sg = slf.get();
+        let join = |ids: Vec<isize>| {
+            ids.iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        let mut lines = vec![format!(
+            "{:>4}: [{}] {} [{}]",
+            sg.sid(py)?,
+            join(sg.preds(py)?),
+            sg.kind(py)?,
+            join(sg.succs(py)?)
+        )];
+        for stmt in Self::nested(slf)? {
+            lines.push(format!("  {}", stmt.str()?));
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+impl CStmt {
+    /// Statements nested in this statement, in program order.
+    pub fn nested<'a>(slf: &Bound<'a, Self>) -> PyResult<Vec<Bound<'a, CStmt>>> {
+        let py = slf.py();
+        let stmts = slf.get().stmts.get_or_try_init(|| -> PyResult<_> {
+            let xnode = slf.get().xnode.bind(py);
+            let xskind = || slf.get().xskind(py);
+            let blocks = if slf.is_instance_of::<CBlock>() {
+                let Some(bstmts) = xml_find(xnode, "bstmts")? else {
+                    return Err(CHCError::new_err(
+                        "stmts element is missing from block element",
+                    ));
+                };
+                return xml_findall(&bstmts, "stmt")?
+                    .iter()
+                    .map(|xstmt| Ok(get_statement(slf, xstmt)?.unbind()))
+                    .collect();
+            } else if slf.is_instance_of::<CIfStmt>() {
+                let xskind = xskind()?;
+                let mut blocks = Vec::new();
+                blocks.extend(xml_find(&xskind, "thenblock")?);
+                blocks.extend(xml_find(&xskind, "elseblock")?);
+                blocks
+            } else if slf.is_instance_of::<CLoopStmt>() || slf.is_instance_of::<CSwitchStmt>() {
+                match xml_find(&xskind()?, "block")? {
+                    Some(xblock) => vec![xblock],
+                    None => {
+                        return Err(CHCError::new_err(format!(
+                            "{} stmt without nested block",
+                            slf.get().kind(py)?
+                        )))
+                    }
+                }
+            } else {
+                Vec::new()
+            };
+            let mut stmts = Vec::new();
+            for xblock in blocks {
+                let block =
+                    Bound::new(py, CBlock::new(Some(slf.clone().unbind()), xblock.unbind()))?;
+                let block = block.into_any().downcast_into::<CStmt>()?;
+                stmts.extend(Self::nested(&block)?.into_iter().map(Bound::unbind));
+            }
+            Ok(stmts)
+        })?;
+        Ok(stmts.iter().map(|stmt| stmt.bind(py).clone()).collect())
+    }
+
+    /// This statement and all statements nested in it, in program order.
+    pub fn all_nested<'a>(slf: &Bound<'a, Self>) -> PyResult<Vec<Bound<'a, CStmt>>> {
+        let mut result = vec![slf.clone()];
+        for stmt in Self::nested(slf)? {
+            result.extend(Self::all_nested(&stmt)?);
+        }
+        Ok(result)
+    }
+}
+
+fn sum_nested(slf: &Bound<CStmt>, name: &Bound<PyString>) -> PyResult<isize> {
+    let mut result = 0;
+    for stmt in CStmt::nested(slf)? {
+        // Method is overridden
+        result += stmt.getattr(name)?.extract::<isize>()?;
+    }
+    Ok(result)
+}
+
+fn concat_nested<'a>(
+    slf: &Bound<'a, CStmt>,
+    name: &Bound<PyString>,
+) -> PyResult<Vec<Bound<'a, PyAny>>> {
+    let mut result = Vec::new();
+    for stmt in CStmt::nested(slf)? {
+        // Method is overridden
+        result.extend(stmt.getattr(name)?.extract::<Vec<Bound<PyAny>>>()?);
+    }
+    Ok(result)
+}
+
+#[pyclass(extends = CStmt, frozen, subclass)]
+pub struct CBlock {}
+
+#[pymethods]
+impl CBlock {
+    #[new]
+    #[pyo3(signature = (parent, xnode))]
+    fn new(parent: Option<Py<CStmt>>, xnode: Py<PyAny>) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CStmt::new(parent, xnode)).add_subclass(CBlock {})
+    }
+
+    #[getter]
+    fn is_block_stmt(&self) -> bool {
+        true
+    }
+
+    #[getter]
+    fn block_count(slf: &Bound<Self>) -> PyResult<isize> {
+        Ok(sum_nested(slf.downcast()?, intern!(slf.py(), "block_count"))? + 1)
+    }
+}
+
+#[pyclass(extends = CBlock, frozen, subclass)]
+pub struct CFunctionBody {
+    cfun: Py<PyAny>,
+}
+
+#[pymethods]
+impl CFunctionBody {
+    #[new]
+    pub(crate) fn new(cfun: Py<PyAny>, xnode: Py<PyAny>) -> PyClassInitializer<Self> {
+        CBlock::new(None, xnode).add_subclass(CFunctionBody { cfun })
+    }
+
+    #[getter]
+    fn cfun<'a>(&self, py: Python<'a>) -> Bound<'a, PyAny> {
+        self.cfun.bind(py).clone()
+    }
+
+    #[getter]
+    fn is_function_body(&self) -> bool {
+        true
+    }
+}
+
+#[pyclass(extends = CStmt, frozen, subclass)]
+pub struct CIfStmt {}
+
+#[pymethods]
+impl CIfStmt {
+    #[new]
+    fn new(parent: Py<CStmt>, xnode: Py<PyAny>) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CStmt::new(Some(parent), xnode)).add_subclass(CIfStmt {})
+    }
+
+    #[getter]
+    fn condition<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, PyAny>> {
+        let py = slf.py();
+        let stmt = slf.downcast::<CStmt>()?;
+        let Some(xiexp) = xml_attr(&stmt.get().xskind(py)?, "iexp")? else {
+            return Err(CHCError::new_err("iexp attribute is missing from if stmt"));
+        };
+        let index: isize = xiexp
+            .parse()
+            .map_err(|_| CHCError::new_err(format!("invalid iexp {xiexp}")))?;
+        CStmt::cdictionary(stmt)?.call_method1(intern!(py, "get_exp"), (index,))
+    }
+
+    #[getter]
+    fn strings(slf: &Bound<Self>) -> PyResult<Vec<String>> {
+        let mut result = CStmt::strings(slf.downcast()?)?;
+        result.extend(
+            Self::condition(slf)?
+                .call_method0(intern!(slf.py(), "get_strings"))?
+                .extract::<Vec<String>>()?,
+        );
+        Ok(result)
+    }
+
+    #[getter]
+    fn is_if_stmt(&self) -> bool {
+        true
+    }
+
+    fn get_variable_uses(slf: &Bound<Self>, vid: isize) -> PyResult<isize> {
+        let in_stmts = CStmt::get_variable_uses(slf.downcast()?, vid)?;
+        let in_condition: isize = Self::condition(slf)?
+            .call_method1(intern!(slf.py(), "get_variable_uses"), (vid,))?
+            .extract()?;
+        Ok(in_stmts + in_condition)
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: &Bound<Self>) -> PyResult<String> {
+        Ok(format!(
+            "{}: {}",
+            CStmt::str(slf.downcast()?)?,
+            Self::condition(slf)?.str()?
+        ))
+    }
+}
+
+#[pyclass(extends = CStmt, frozen, subclass)]
+pub struct CLoopStmt {}
+
+#[pymethods]
+impl CLoopStmt {
+    #[new]
+    fn new(parent: Py<CStmt>, xnode: Py<PyAny>) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CStmt::new(Some(parent), xnode)).add_subclass(CLoopStmt {})
+    }
+}
+
+#[pyclass(extends = CStmt, frozen, subclass)]
+pub struct CSwitchStmt {}
+
+#[pymethods]
+impl CSwitchStmt {
+    #[new]
+    fn new(parent: Py<CStmt>, xnode: Py<PyAny>) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CStmt::new(Some(parent), xnode)).add_subclass(CSwitchStmt {})
+    }
+}
+
+#[pyclass(extends = CStmt, frozen, subclass)]
+pub struct CBreakStmt {}
+
+#[pymethods]
+impl CBreakStmt {
+    #[new]
+    fn new(parent: Py<CStmt>, xnode: Py<PyAny>) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CStmt::new(Some(parent), xnode)).add_subclass(CBreakStmt {})
+    }
+}
+
+#[pyclass(extends = CStmt, frozen, subclass)]
+pub struct CContinueStmt {}
+
+#[pymethods]
+impl CContinueStmt {
+    #[new]
+    fn new(parent: Py<CStmt>, xnode: Py<PyAny>) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CStmt::new(Some(parent), xnode)).add_subclass(CContinueStmt {})
+    }
+}
+
+#[pyclass(extends = CStmt, frozen, subclass)]
+pub struct CGotoStmt {}
+
+#[pymethods]
+impl CGotoStmt {
+    #[new]
+    fn new(parent: Py<CStmt>, xnode: Py<PyAny>) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CStmt::new(Some(parent), xnode)).add_subclass(CGotoStmt {})
+    }
+}
+
+/// Function return.
+#[pyclass(extends = CStmt, frozen, subclass)]
+pub struct CReturnStmt {}
+
+#[pymethods]
+impl CReturnStmt {
+    #[new]
+    fn new(parent: Py<CStmt>, xnode: Py<PyAny>) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CStmt::new(Some(parent), xnode)).add_subclass(CReturnStmt {})
+    }
+}
+
+/// Sequence of instructions without control flow.
+#[pyclass(extends = CStmt, frozen, subclass)]
+pub struct CInstrsStmt {
+    instrs: OnceCell<Vec<Py<CInstr>>>,
+}
+
+#[pymethods]
+impl CInstrsStmt {
+    #[new]
+    fn new(parent: Py<CStmt>, xnode: Py<PyAny>) -> PyClassInitializer<Self> {
+        PyClassInitializer::from(CStmt::new(Some(parent), xnode)).add_subclass(CInstrsStmt {
+            instrs: OnceCell::new(),
+        })
+    }
+
+    #[getter]
+    fn is_instrs_stmt(&self) -> bool {
+        true
+    }
+
+    #[getter]
+    pub fn instrs<'a>(slf: &Bound<'a, Self>) -> PyResult<Vec<Bound<'a, CInstr>>> {
+        let py = slf.py();
+        let instrs = slf.get().instrs.get_or_try_init(|| -> PyResult<_> {
+            let stmt = slf.downcast::<CStmt>()?;
+            let Some(xinstrs) = xml_find(&stmt.get().xskind(py)?, "instrs")? else {
+                return Err(CHCError::new_err("Instr stmt is missing instrs element"));
+            };
+            let mut instrs = Vec::new();
+            for xinode in xml_findall(&xinstrs, "instr")? {
+                let Some(xitag) = xml_attr(&xinode, "itag")? else {
+                    return Err(CHCError::new_err("Instr stmt is missing itag attribute"));
+                };
+                let parent = stmt.clone().unbind();
+                let xinode = xinode.unbind();
+                let instr = match xitag.as_str() {
+                    "call" => Bound::new(py, CCallInstr::new(parent, xinode))?.into_any(),
+                    "set" => Bound::new(py, CAssignInstr::new(parent, xinode))?.into_any(),
+                    "asm" => Bound::new(py, CAsmInstr::new(parent, xinode))?.into_any(),
+                    _ => {
+                        return Err(CHCError::new_err(format!(
+                            "unknown instruction tag: {xitag}"
+                        )))
+                    }
+                };
+                instrs.push(instr.downcast_into::<CInstr>()?.unbind());
+            }
+            Ok(instrs)
+        })?;
+        Ok(instrs.iter().map(|instr| instr.bind(py).clone()).collect())
+    }
+
+    #[getter]
+    fn instr_count(slf: &Bound<Self>) -> PyResult<isize> {
+        Ok(Self::instrs(slf)?.len() as isize)
+    }
+
+    #[getter]
+    fn call_instrs<'a>(slf: &Bound<'a, Self>) -> PyResult<Vec<Bound<'a, CInstr>>> {
+        Ok(Self::instrs(slf)?
+            .into_iter()
+            .filter(|instr| instr.is_instance_of::<CCallInstr>())
+            .collect())
+    }
+
+    #[getter]
+    fn strings(slf: &Bound<Self>) -> PyResult<Vec<String>> {
+        let mut result = Vec::new();
+        for instr in Self::instrs(slf)? {
+            // Method is overridden
+            result.extend(
+                instr
+                    .getattr(intern!(slf.py(), "strings"))?
+                    .extract::<Vec<String>>()?,
+            );
+        }
+        Ok(result)
+    }
+
+    fn get_variable_uses(slf: &Bound<Self>, vid: isize) -> PyResult<isize> {
+        let mut result = 0;
+        for instr in Self::instrs(slf)? {
+            // Method is overridden
+            result += instr
+                .call_method1(intern!(slf.py(), "get_variable_uses"), (vid,))?
+                .extract::<isize>()?;
+        }
+        Ok(result)
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: &Bound<Self>) -> PyResult<String> {
+        let mut lines = vec![CStmt::str(slf.downcast()?)?];
+        for (n, instr) in Self::instrs(slf)?.iter().enumerate() {
+            lines.push(format!("  {n:>4}: {}", instr.str()?));
+        }
+        Ok(lines.join("\n"))
     }
 }
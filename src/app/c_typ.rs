@@ -36,17 +36,22 @@ use pyo3::{exceptions::PyException, intern, prelude::*};
 
 use crate::{
     app::{
-        c_attributes::CAttributes,
+        c_attributes::{CAttribute, CAttributes},
         c_comp_info::CCompInfo,
-        c_const::CConstInt,
+        c_data_model::{data_model, CDataModel, LayoutAttributes, TypeLayout},
         c_dictionary::CDictionary,
-        c_dictionary_record::{CDictionaryRecord, CDictionaryRegistryEntry},
-        c_exp::{CExp, CExpConst},
+        c_dictionary_record::{
+            CDictionaryRecord, CDictionaryRecordTrait, CDictionaryRegistryEntry,
+        },
+        c_exp::CExp,
+        c_exp_eval,
+        c_file_declarations::{CFileDeclarations, Expansion},
+        c_pretty, c_typ_compat,
     },
     util::indexed_table::IndexedTableValue,
 };
 
-pyo3::import_exception!(chcc.util.fileutil, CHCError);
+pyo3::import_exception!(chc.util.fileutil, CHCError);
 
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "c_typ")?;
@@ -56,6 +61,7 @@ pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     module.add_class::<CTypComp>()?;
     module.add_class::<CTypEnum>()?;
     module.add_class::<CTypFloat>()?;
+    module.add_class::<CTypFun>()?;
     module.add_class::<CTypInt>()?;
     module.add_class::<CTypNamed>()?;
     module.add_class::<CTypPtr>()?;
@@ -92,11 +98,12 @@ const ATTRIBUTE_INDEX: Lazy<BTreeMap<&'static str, usize>> = Lazy::new(|| {
         ("tnamed", 0),
         ("tcomp", 1),
         ("tenum", 0),
-        ("tbuiltin-va)-list", 0),
+        ("tbuiltinvaargs", 0),
+        ("tbuiltin-va-list", 0),
     ])
 });
 
-const FLOAT_NAMES: Lazy<BTreeMap<&'static str, &'static str>> = Lazy::new(|| {
+pub(crate) const FLOAT_NAMES: Lazy<BTreeMap<&'static str, &'static str>> = Lazy::new(|| {
     BTreeMap::from([
         ("float", "float"),
         ("fdouble", "double"),
@@ -104,7 +111,7 @@ const FLOAT_NAMES: Lazy<BTreeMap<&'static str, &'static str>> = Lazy::new(|| {
     ])
 });
 
-const INTEGER_NAMES: Lazy<BTreeMap<&'static str, &'static str>> = Lazy::new(|| {
+pub(crate) const INTEGER_NAMES: Lazy<BTreeMap<&'static str, &'static str>> = Lazy::new(|| {
     BTreeMap::from([
         ("ichar", "char"),
         ("ischar", "signed char"),
@@ -209,7 +216,7 @@ impl CTyp {
     }
 
     #[getter]
-    fn attributes<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CAttributes>> {
+    pub fn attributes<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CAttributes>> {
         let aindex = *ATTRIBUTE_INDEX
             .get(self.tags[0].as_str())
             .ok_or_else(|| PyException::new_err(format!("no such aindex: {}", self.tags[0])))?;
@@ -246,9 +253,46 @@ impl CTyp {
         Ok(slf_index == other_index)
     }
 
+    /// True if this type and `other` are compatible in the sense of C11,
+    /// optionally ignoring qualifiers and other attributes. The types may be
+    /// from different file dictionaries.
+    #[pyo3(signature = (other, ignore_attributes = false))]
+    fn is_compatible(
+        slf: &Bound<Self>,
+        other: &Bound<Self>,
+        ignore_attributes: bool,
+    ) -> PyResult<bool> {
+        c_typ_compat::compatible(slf, other, ignore_attributes)
+    }
+
+    /// Size in bytes in the current data model, or None if the type has no
+    /// size.
     #[getter]
-    fn size(&self) -> isize {
-        -1000
+    fn size(slf: &Bound<Self>) -> PyResult<Option<isize>> {
+        Self::sizeof(slf, None)
+    }
+
+    /// Alignment in bytes in the current data model, or None if the type
+    /// has no size.
+    #[getter]
+    fn alignment(slf: &Bound<Self>) -> PyResult<Option<isize>> {
+        Self::alignof(slf, None)
+    }
+
+    /// Size in bytes in `model` (default: the current data model), or None
+    /// for void, functions, arrays without length and incomplete types.
+    #[pyo3(signature = (model=None))]
+    fn sizeof(slf: &Bound<Self>, model: Option<CDataModel>) -> PyResult<Option<isize>> {
+        let model = model.unwrap_or_else(data_model);
+        Ok(Self::layout(slf, &model)?.map(|layout| layout.size))
+    }
+
+    /// Alignment in bytes in `model` (default: the current data model), or
+    /// None if the type has no size.
+    #[pyo3(signature = (model=None))]
+    fn alignof(slf: &Bound<Self>, model: Option<CDataModel>) -> PyResult<Option<isize>> {
+        let model = model.unwrap_or_else(data_model);
+        Ok(Self::layout(slf, &model)?.map(|layout| layout.align))
     }
 
     #[getter]
@@ -340,6 +384,11 @@ impl CTyp {
         BTreeMap::from([("base", "type")])
     }
 
+    /// C type name, e.g. `int (*)[4]`.
+    fn to_c(slf: &Bound<Self>) -> PyResult<String> {
+        c_pretty::to_c(slf.as_any())
+    }
+
     // Unvalidated
     fn to_idict(&self, py: Python) -> BTreeMap<&'static str, Py<PyAny>> {
         BTreeMap::from([
@@ -349,6 +398,109 @@ impl CTyp {
     }
 }
 
+impl CTyp {
+    /// Size and alignment in `model`, or None for void, functions, arrays
+    /// without length and incomplete types.
+    pub fn layout(slf: &Bound<Self>, model: &CDataModel) -> PyResult<Option<TypeLayout>> {
+        let Some(layout) = Self::unattributed_layout(slf, model)? else {
+            return Ok(None);
+        };
+        Ok(Some(
+            Self::layout_attributes(slf, model)?.type_layout(layout),
+        ))
+    }
+
+    /// The `packed` and `aligned` attributes of this type.
+    pub fn layout_attributes(slf: &Bound<Self>, model: &CDataModel) -> PyResult<LayoutAttributes> {
+        let py = slf.py();
+        let sg = slf.get();
+        let index = ATTRIBUTE_INDEX
+            .get(sg.tags[0].as_str())
+            .and_then(|aindex| sg.args.get(*aindex).copied());
+        match index {
+            Some(index) => CDictionary::get_attributes(sg.cd.bind(py), index)?
+                .get()
+                .layout_attributes(py, model),
+            None => Ok(LayoutAttributes::default()),
+        }
+    }
+
+    fn unattributed_layout(slf: &Bound<Self>, model: &CDataModel) -> PyResult<Option<TypeLayout>> {
+        if let Ok(typ) = slf.downcast::<CTypInt>() {
+            Ok(model.ikind_layout(&typ.get().ikind))
+        } else if let Ok(typ) = slf.downcast::<CTypFloat>() {
+            Ok(model.fkind_layout(&typ.get().fkind))
+        } else if slf.is_instance_of::<CTypPtr>() {
+            Ok(Some(model.pointer_layout()))
+        } else if let Ok(typ) = slf.downcast::<CTypEnum>() {
+            Ok(CTypEnum::ikind(typ)?.and_then(|ikind| model.ikind_layout(&ikind)))
+        } else if slf.is_instance_of::<CTypBuiltinVaargs>() {
+            Ok(Some(model.va_list_layout()))
+        } else if let Ok(typ) = slf.downcast::<CTypNamed>() {
+            let (typ, attributes) = CTypNamed::expansion(typ)?;
+            let Some(layout) = Self::unattributed_layout(&typ, model)? else {
+                return Ok(None);
+            };
+            let attributes = CAttributes::layout_attributes_in(slf.py(), &attributes, model)?;
+            Ok(Some(attributes.type_layout(layout)))
+        } else if let Ok(typ) = slf.downcast::<CTypComp>() {
+            CCompInfo::layout(&CTypComp::compinfo(typ)?, model)
+        } else if let Ok(typ) = slf.downcast::<CTypArray>() {
+            let Some(element) = CTypArray::element_layout(typ, model)? else {
+                return Ok(None);
+            };
+            Ok(CTypArray::length(typ, model)?.and_then(|length| {
+                Some(TypeLayout {
+                    size: length.checked_mul(element.size)?,
+                    align: element.align,
+                })
+            }))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Indices in cdictionary of the attributes of this type.
+    pub fn attribute_indices(slf: &Bound<Self>) -> PyResult<Vec<isize>> {
+        Ok(slf.get().attributes(slf.py())?.get().attribute_indices())
+    }
+
+    /// The type with all typedefs at the top expanded. The attributes of the
+    /// typedef names are dropped; see `unroll_with_attributes`.
+    pub fn unroll<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, Self>> {
+        match slf.downcast::<CTypNamed>() {
+            Ok(named) => Ok(CTypNamed::expansion(named)?.0),
+            Err(_) => Ok(slf.clone()),
+        }
+    }
+
+    /// The type with all typedefs at the top expanded, together with its
+    /// attributes and those of the typedef names.
+    pub fn unroll_with_attributes<'a>(
+        slf: &Bound<'a, Self>,
+    ) -> PyResult<(Bound<'a, Self>, Vec<Bound<'a, CAttribute>>)> {
+        match slf.downcast::<CTypNamed>() {
+            Ok(named) => CTypNamed::expansion(named),
+            Err(_) => Ok((
+                slf.clone(),
+                slf.get().attributes(slf.py())?.get().attributes(slf.py())?,
+            )),
+        }
+    }
+
+    /// Alignment of the elements if this is an array without length, which
+    /// is allowed as the last field of a struct.
+    pub fn flexible_array_align(slf: &Bound<Self>, model: &CDataModel) -> PyResult<Option<isize>> {
+        let Ok(typ) = slf.downcast::<CTypArray>() else {
+            return Ok(None);
+        };
+        if CTypArray::length(typ, model)?.is_some() {
+            return Ok(None);
+        }
+        Ok(CTypArray::element_layout(typ, model)?.map(|element| element.align))
+    }
+}
+
 /// Void type.
 ///
 /// * args[0]: attributes
@@ -379,7 +531,16 @@ impl CTypVoid {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CTyp, CTypVoid>("tvoid") }
+impl CDictionaryRecordTrait for CTypVoid {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd, ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CTyp, CTypVoid>("tvoid") }
 
 /// Integer type.
 ///
@@ -408,21 +569,7 @@ impl CTypInt {
 
     // Unvalidated
     #[getter]
-    fn size(&self) -> PyResult<isize> {
-        let binding = INTEGER_NAMES;
-        let name = binding
-            .get(self.ikind.as_str())
-            .ok_or_else(|| PyException::new_err(format!("unknown type '{}'", self.ikind)))?;
-        Ok(if name.contains("char") {
-            1
-        } else {
-            4 // TBD: adjust for other kinds
-        })
-    }
-
-    // Unvalidated
-    #[getter]
-    fn ikind(&self) -> &str {
+    pub fn ikind(&self) -> &str {
         self.ikind.as_str()
     }
 
@@ -443,7 +590,16 @@ impl CTypInt {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CTyp, CTypInt>("tint") }
+impl CDictionaryRecordTrait for CTypInt {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd, ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CTyp, CTypInt>("tint") }
 
 /// Float type.
 ///
@@ -472,13 +628,7 @@ impl CTypFloat {
 
     // Unvalidated
     #[getter]
-    fn size(&self) -> isize {
-        4 // TBD: adjust for kind
-    }
-
-    // Unvalidated
-    #[getter]
-    fn fkind(&self) -> &str {
+    pub fn fkind(&self) -> &str {
         self.fkind.as_str()
     }
 
@@ -496,7 +646,16 @@ impl CTypFloat {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CTyp, CTypFloat>("tfloat") }
+impl CDictionaryRecordTrait for CTypFloat {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd, ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CTyp, CTypFloat>("tfloat") }
 
 /// Type definition
 ///
@@ -521,20 +680,12 @@ impl CTypNamed {
         PyClassInitializer::from(CTyp::new(cd, ixval)).add_subclass(typnamed)
     }
 
-    fn expand<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
-        Ok(self
-            .cd
-            .bind(py)
-            .getattr(intern!(py, "decls"))?
-            .call_method1(intern!(py, "expand"), (self.name.as_str(),))?
-            .downcast()?
-            .clone())
-    }
-
-    // Unvalidated
-    #[getter]
-    fn size(&self, py: Python) -> PyResult<isize> {
-        Ok(self.expand(py)?.getattr(intern!(py, "size"))?.extract()?)
+    /// Type this typedef name stands for, with typedefs expanded.
+    ///
+    /// The type keeps its own attributes only; those of the typedef names in
+    /// the chain are not added to it.
+    pub fn expand<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CTyp>> {
+        Ok(Self::expansion(slf)?.0)
     }
 
     #[getter]
@@ -544,22 +695,22 @@ impl CTypNamed {
 
     // Unvalidated
     #[getter]
-    fn get_opaque_type<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
-        Ok(self
-            .expand(py)?
-            .call_method0(intern!(py, "get_opaque_type"))?
+    fn get_opaque_type<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CTyp>> {
+        Ok(Self::expand(slf)?
+            .call_method0(intern!(slf.py(), "get_opaque_type"))?
             .downcast()?
             .clone())
     }
 
     // Unvalidated
-    fn to_dict<'a>(&self, py: Python<'a>) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+    fn to_dict(slf: &Bound<Self>) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        let py = slf.py();
         Ok(BTreeMap::from([
             ("base", "named".into_py(py)),
-            ("name", self.name.as_str().into_py(py)),
+            ("name", slf.get().name.as_str().into_py(py)),
             (
                 "expand",
-                self.expand(py)?
+                Self::expand(slf)?
                     .call_method0(intern!(py, "to_dict"))?
                     .unbind(),
             ),
@@ -576,7 +727,93 @@ impl CTypNamed {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CTyp, CTypNamed>("tnamed") }
+impl CTypNamed {
+    /// Type this typedef name stands for, together with the attributes of
+    /// all names in the chain and of that type.
+    ///
+    /// The chain of typedefs is followed until a type that is not a typedef
+    /// name. A cyclic chain or a name without type definition raises
+    /// CHCError. No records are added to the dictionary; expansions are
+    /// memoized by the file declarations.
+    pub fn expansion<'a>(
+        slf: &Bound<'a, Self>,
+    ) -> PyResult<(Bound<'a, CTyp>, Vec<Bound<'a, CAttribute>>)> {
+        let py = slf.py();
+        let cd = slf.get().cd.bind(py);
+        let decls = cd.getattr(intern!(py, "decls"))?;
+        let decls = decls
+            .downcast::<CFileDeclarations>()
+            .map_err(|_| CHCError::new_err("Types should be expanded at the file level."))?;
+        let base = slf.clone().into_any().downcast_into::<CTyp>()?;
+        let index = slf.borrow().into_super().into_super().into_super().index();
+        let (expanded, attribute_indices) = match CFileDeclarations::expansion(decls, index)? {
+            Some(expansion) => expansion,
+            None => {
+                let expansion = Self::expand_chain(&base, decls)?;
+                CFileDeclarations::set_expansion(decls, index, expansion.clone())?;
+                expansion
+            }
+        };
+        let attributes = attribute_indices
+            .into_iter()
+            .map(|attribute_index| CDictionary::get_attribute(cd, attribute_index))
+            .collect::<PyResult<Vec<_>>>()?;
+        Ok((base.get().get_typ(py, expanded)?, attributes))
+    }
+
+    /// Follows the chain of typedefs from `typ`; see `expansion`.
+    fn expand_chain(typ: &Bound<CTyp>, decls: &Bound<CFileDeclarations>) -> PyResult<Expansion> {
+        let py = typ.py();
+        let mut chain: Vec<String> = vec![];
+        let mut attribute_indices = vec![];
+        let mut typ = typ.clone();
+        loop {
+            for attribute_index in CTyp::attribute_indices(&typ)? {
+                if !attribute_indices.contains(&attribute_index) {
+                    attribute_indices.push(attribute_index);
+                }
+            }
+            let Ok(named) = typ.downcast::<CTypNamed>() else {
+                break;
+            };
+            let name = named.get().name.clone();
+            if chain.contains(&name) {
+                chain.push(name);
+                return Err(CHCError::new_err(format!(
+                    "Cyclic type definition: {}",
+                    chain.join(" -> ")
+                )));
+            }
+            let Some(definition) = CFileDeclarations::typedef(decls, &name)? else {
+                let origin = if chain.is_empty() {
+                    String::new()
+                } else {
+                    format!(" (expanding {})", chain.join(" -> "))
+                };
+                return Err(CHCError::new_err(format!(
+                    "No type definition found for {name}{origin}"
+                )));
+            };
+            chain.push(name);
+            typ = typ.get().get_typ(py, definition)?;
+        }
+        Ok((
+            typ.borrow().into_super().into_super().index(),
+            attribute_indices,
+        ))
+    }
+}
+
+impl CDictionaryRecordTrait for CTypNamed {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd, ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CTyp, CTypNamed>("tnamed") }
 
 /// Struct type (composite type; also includes union)
 ///
@@ -603,7 +840,7 @@ impl CTypComp {
     }
 
     #[getter]
-    fn compinfo<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CCompInfo>> {
+    pub fn compinfo<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CCompInfo>> {
         Ok(slf
             .getattr(intern!(slf.py(), "decls"))?
             .call_method1(
@@ -624,12 +861,6 @@ impl CTypComp {
         Ok(CCompInfo::is_struct(Self::compinfo(slf)?.borrow()))
     }
 
-    // Unvalidated
-    #[getter]
-    fn size(slf: &Bound<Self>) -> PyResult<isize> {
-        CCompInfo::size(&Self::compinfo(slf)?)
-    }
-
     #[getter]
     fn is_comp(&self) -> bool {
         true
@@ -679,7 +910,16 @@ impl CTypComp {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CTyp, CTypComp>("tcomp") }
+impl CDictionaryRecordTrait for CTypComp {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd, ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CTyp, CTypComp>("tcomp") }
 
 /// Enum type.
 ///
@@ -703,26 +943,20 @@ impl CTypEnum {
         PyClassInitializer::from(CTyp::new(cd, ixval)).add_subclass(typenum)
     }
 
-    // Unvalidated
-    #[getter]
-    fn size(&self) -> isize {
-        4
-    }
-
     #[getter]
     fn is_enum(&self) -> bool {
         true
     }
 
     // Unvalidated
-    fn get_opaque_type<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
-        let tags = ["tint", "iint"];
+    fn get_opaque_type<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CTyp>> {
+        let py = slf.py();
+        let ikind = Self::ikind(slf)?.unwrap_or_else(|| "iint".to_string());
+        let tags = ["tint", ikind.as_str()];
         let args: [isize; 0] = [];
-        let cd = self.cd.bind(py);
+        let cd = slf.get().cd.bind(py);
         let typ_index = cd.call_method1(intern!(py, "mk_typ_index"), (tags, args))?;
-        Ok(self
-            .cd
-            .bind(py)
+        Ok(cd
             .call_method1(intern!(py, "get_typ"), (typ_index,))?
             .downcast()?
             .clone())
@@ -739,7 +973,29 @@ impl CTypEnum {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CTyp, CTypEnum>("tenum") }
+impl CTypEnum {
+    /// Integer kind of the enum, from its definition in the file
+    /// declarations; None if there is no definition.
+    pub fn ikind(slf: &Bound<Self>) -> PyResult<Option<String>> {
+        let py = slf.py();
+        let decls = slf.get().cd.bind(py).getattr(intern!(py, "decls"))?;
+        let Ok(decls) = decls.downcast::<CFileDeclarations>() else {
+            return Ok(None);
+        };
+        CFileDeclarations::enum_ikind(decls, &slf.get().name)
+    }
+}
+
+impl CDictionaryRecordTrait for CTypEnum {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd, ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CTyp, CTypEnum>("tenum") }
 
 // Unvalidated
 #[pyclass(extends = CTyp, frozen, subclass)]
@@ -768,8 +1024,17 @@ impl CTypBuiltinVaargs {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CTyp, CTypBuiltinVaargs>("tbuiltinvaargs") }
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CTyp, CTypBuiltinVaargs>("tbuiltin-va-list") }
+impl CDictionaryRecordTrait for CTypBuiltinVaargs {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd, ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CTyp, CTypBuiltinVaargs>("tbuiltinvaargs") }
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CTyp, CTypBuiltinVaargs>("tbuiltin-va-list") }
 
 /// Pointer type
 ///
@@ -793,16 +1058,10 @@ impl CTypPtr {
     }
 
     #[getter]
-    fn pointedto_type<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
+    pub fn pointedto_type<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
         slf.as_super().get_typ(py, slf.pointed_to_index)
     }
 
-    // Unvalidated
-    #[getter]
-    fn size(&self) -> isize {
-        4
-    }
-
     #[getter]
     fn is_pointer(&self) -> bool {
         true
@@ -844,7 +1103,16 @@ impl CTypPtr {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CTyp, CTypPtr>("tptr") }
+impl CDictionaryRecordTrait for CTypPtr {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd, ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CTyp, CTypPtr>("tptr") }
 
 /// Array type
 ///
@@ -871,7 +1139,7 @@ impl CTypArray {
     }
 
     #[getter]
-    fn array_basetype<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
+    pub fn array_basetype<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
         slf.as_super().get_typ(py, slf.base_type_index)
     }
 
@@ -888,22 +1156,11 @@ impl CTypArray {
         self.size_expression_index >= 0
     }
 
-    // Unvalidated
+    /// Number of elements in the current data model, if the size expression
+    /// is an integer constant expression.
     #[getter]
-    fn size(slf: &Bound<Self>) -> isize {
-        if !slf.borrow().has_array_size_expr() {
-            return -1000;
-        }
-        (|| {
-            let array_size_const = Self::array_size_expr(slf.borrow(), slf.py())?
-                .downcast::<CExpConst>()?
-                .clone();
-            let array_size_int = CExpConst::constant(&array_size_const)?
-                .downcast::<CConstInt>()?
-                .clone();
-            CConstInt::intvalue(array_size_int.borrow())
-        })()
-        .unwrap_or(-1000)
+    pub fn array_length(slf: &Bound<Self>) -> PyResult<Option<isize>> {
+        Self::length(slf, &data_model())
     }
 
     #[getter]
@@ -970,4 +1227,200 @@ impl CTypArray {
     }
 }
 
-inventory::submit! { CDictionaryRegistryEntry::python_type::<CTyp, CTypArray>("tarray") }
+impl CTypArray {
+    /// Number of elements in `model`, if the size expression is an integer
+    /// constant expression. A negative length raises CHCError.
+    pub fn length(slf: &Bound<Self>, model: &CDataModel) -> PyResult<Option<isize>> {
+        if !slf.borrow().has_array_size_expr() {
+            return Ok(None);
+        }
+        let size_expr = Self::array_size_expr(slf.borrow(), slf.py())?;
+        let Some(value) = c_exp_eval::evaluate(&size_expr, model)? else {
+            return Ok(None);
+        };
+        match isize::try_from(value.value) {
+            Ok(length) if length >= 0 => Ok(Some(length)),
+            _ => Err(CHCError::new_err(format!(
+                "Invalid array length: {}",
+                value.value
+            ))),
+        }
+    }
+
+    pub fn element_layout(slf: &Bound<Self>, model: &CDataModel) -> PyResult<Option<TypeLayout>> {
+        CTyp::layout(&Self::array_basetype(slf.borrow(), slf.py())?, model)
+    }
+}
+
+impl CDictionaryRecordTrait for CTypArray {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Ok(Self::new(cd, ixval))
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CTyp, CTypArray>("tarray") }
+
+/// Function type
+///
+/// * args[0]: index of return type in cdictionary
+/// * args[1]: index of argument types list in cdictionary (optional)
+/// * args[2]: 1 = varargs
+/// * args[3]: index of attributes in cdictionary
+#[pyclass(extends = CTyp, frozen, subclass)]
+pub struct CTypFun {
+    cd: Py<CDictionary>,
+    return_type_index: isize,
+    funargs_index: isize,
+    vararg: bool,
+}
+
+#[pymethods]
+impl CTypFun {
+    #[new]
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        let fun = CTypFun {
+            cd: cd.clone().unbind(),
+            return_type_index: ixval.arg(0)?,
+            funargs_index: ixval.arg(1)?,
+            vararg: ixval.arg(2)? == 1,
+        };
+        Ok(PyClassInitializer::from(CTyp::new(cd, ixval)).add_subclass(fun))
+    }
+
+    #[getter]
+    pub fn return_type<'a>(slf: PyRef<Self>, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
+        slf.as_super().get_typ(py, slf.return_type_index)
+    }
+
+    /// Argument list (a python CFunArgs), or None if there is none.
+    #[getter]
+    pub fn funargs<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        self.cd
+            .bind(py)
+            .call_method1(intern!(py, "get_funargs_opt"), (self.funargs_index,))
+    }
+
+    #[getter]
+    fn is_function(&self) -> bool {
+        true
+    }
+
+    #[getter]
+    pub fn is_vararg(&self) -> bool {
+        self.vararg
+    }
+
+    /// True if there is no argument list, or all arguments have the names
+    /// that CIL generates for missing prototypes.
+    #[getter]
+    fn is_default_function_prototype(&self, py: Python) -> PyResult<bool> {
+        let funargs = self.funargs(py)?;
+        if funargs.is_none() {
+            return Ok(true);
+        }
+        let mut count = 0;
+        for arg in funargs.getattr(intern!(py, "arguments"))?.iter()? {
+            let name: String = arg?.getattr(intern!(py, "name"))?.extract()?;
+            if !name.starts_with("$par$") {
+                return Ok(false);
+            }
+            count += 1;
+        }
+        Ok(count > 0)
+    }
+
+    fn get_opaque_type<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, CTyp>> {
+        let tags = ["tvoid"];
+        let args: [isize; 0] = [];
+        let cd = self.cd.bind(py);
+        let typ_index = cd.call_method1(intern!(py, "mk_typ_index"), (tags, args))?;
+        Ok(cd
+            .call_method1(intern!(py, "get_typ"), (typ_index,))?
+            .downcast()?
+            .clone())
+    }
+
+    /// This type with the attributes of the return type removed.
+    fn strip_attributes<'a>(slf: &Bound<'a, Self>) -> PyResult<Bound<'a, CTyp>> {
+        let py = slf.py();
+        let return_type = Self::return_type(slf.borrow(), py)?;
+        let rtype = return_type
+            .call_method0(intern!(py, "strip_attributes"))?
+            .downcast_into::<CTyp>()?;
+        let return_index = return_type.borrow().into_super().into_super().index();
+        let rtype_index = rtype.borrow().into_super().into_super().index();
+        let styp = slf.borrow().into_super();
+        if rtype_index == return_index {
+            return Ok(slf.clone().into_any().downcast_into()?);
+        }
+        let mut newargs = styp.args.clone();
+        newargs[0] = rtype_index;
+        let cd = slf.get().cd.bind(py);
+        let newtypix =
+            cd.call_method1(intern!(py, "mk_typ_index"), (styp.tags.clone(), newargs))?;
+        let newtyp = cd
+            .call_method1(intern!(py, "get_typ"), (newtypix,))?
+            .downcast_into::<CTyp>()?;
+        chklogger_info(
+            py,
+            format!(
+                "Change function type from {} to {}",
+                slf.str()?,
+                newtyp.str()?
+            ),
+        )?;
+        Ok(newtyp)
+    }
+
+    fn to_dict(slf: &Bound<Self>) -> PyResult<BTreeMap<&'static str, Py<PyAny>>> {
+        let py = slf.py();
+        let mut map = BTreeMap::from([
+            ("base", "fun".into_py(py)),
+            (
+                "rvtype",
+                Self::return_type(slf.borrow(), py)?
+                    .call_method0(intern!(py, "to_dict"))?
+                    .unbind(),
+            ),
+        ]);
+        if slf.get().is_default_function_prototype(py)? {
+            map.insert("default", "true".into_py(py));
+        } else {
+            let funargs = slf.get().funargs(py)?;
+            if !funargs.is_none() {
+                map.insert(
+                    "args",
+                    funargs.call_method0(intern!(py, "to_dict"))?.unbind(),
+                );
+            }
+        }
+        Ok(map)
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(slf: &Bound<Self>) -> PyResult<String> {
+        let py = slf.py();
+        Ok(format!(
+            "({}):{}",
+            slf.get().funargs(py)?.str()?,
+            Self::return_type(slf.borrow(), py)?.str()?
+        ))
+    }
+}
+
+impl CDictionaryRecordTrait for CTypFun {
+    fn new(
+        cd: &Bound<CDictionary>,
+        ixval: IndexedTableValue,
+    ) -> PyResult<PyClassInitializer<Self>> {
+        Self::new(cd, ixval)
+    }
+}
+
+inventory::submit! { CDictionaryRegistryEntry::rust_record::<CTyp, CTypFun>("tfun") }
@@ -0,0 +1,335 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Compatibility of types (C11 6.2.7).
+//!
+//! Two types are compatible if they could be the types of the same object or
+//! function in two declarations, e.g. in two different files. Types are
+//! compared by structure rather than by index, so the types may come from
+//! different file dictionaries.
+
+use std::collections::HashSet;
+
+use pyo3::{intern, prelude::*};
+
+use crate::app::{
+    c_comp_info::CCompInfo,
+    c_field_info::CFieldInfo,
+    c_typ::{
+        CTyp, CTypArray, CTypBuiltinVaargs, CTypComp, CTypEnum, CTypFloat, CTypInt, CTypPtr,
+        CTypVoid,
+    },
+};
+
+/// True if `typ1` and `typ2` are compatible.
+///
+/// Typedefs are expanded. Qualifiers and other attributes must be the same,
+/// unless `ignore_attributes` is set. Arrays are compatible if their
+/// element types are, and their lengths if both are known. Structs and
+/// unions must have the same tag and, if both are complete, fields with the
+/// same names, widths and compatible types. A function without parameter
+/// list (see `is_default_function_prototype`) is compatible with a prototype
+/// that is not variadic and whose parameters are not changed by the default
+/// argument promotions.
+pub fn compatible(
+    typ1: &Bound<CTyp>,
+    typ2: &Bound<CTyp>,
+    ignore_attributes: bool,
+) -> PyResult<bool> {
+    TypCompatibility {
+        ignore_attributes,
+        identical: false,
+        assumed: HashSet::new(),
+    }
+    .compatible(typ1, typ2, true)
+}
+
+/// True if `typ1` and `typ2` are the same type once typedefs are expanded.
+///
+/// This is `compatible` without the allowances for types that differ:
+/// attributes (also those of parameters) must be the same, an enum is not
+/// its integer kind, and array lengths, completeness of structs and
+/// parameter lists must agree.
+pub fn identical(typ1: &Bound<CTyp>, typ2: &Bound<CTyp>) -> PyResult<bool> {
+    TypCompatibility {
+        ignore_attributes: false,
+        identical: true,
+        assumed: HashSet::new(),
+    }
+    .compatible(typ1, typ2, true)
+}
+
+struct TypCompatibility {
+    ignore_attributes: bool,
+    /// Compare for identity rather than compatibility (see `identical`).
+    identical: bool,
+    /// Pairs of ckeys of structs being compared; nested occurrences (through
+    /// pointers) are assumed to be compatible.
+    assumed: HashSet<(isize, isize)>,
+}
+
+impl TypCompatibility {
+    /// `compare_attributes` is false for parameters, whose top-level
+    /// qualifiers do not take part in the comparison.
+    fn compatible(
+        &mut self,
+        typ1: &Bound<CTyp>,
+        typ2: &Bound<CTyp>,
+        compare_attributes: bool,
+    ) -> PyResult<bool> {
+        if same_record(typ1, typ2)? {
+            return Ok(true);
+        }
+        let (typ1, attributes1) = unroll(typ1)?;
+        let (typ2, attributes2) = unroll(typ2)?;
+        if compare_attributes && !self.ignore_attributes && attributes1 != attributes2 {
+            return Ok(false);
+        }
+        let py = typ1.py();
+        if typ1.is_instance_of::<CTypVoid>() {
+            Ok(typ2.is_instance_of::<CTypVoid>())
+        } else if typ1.is_instance_of::<CTypBuiltinVaargs>() {
+            Ok(typ2.is_instance_of::<CTypBuiltinVaargs>())
+        } else if let Ok(int1) = typ1.downcast::<CTypInt>() {
+            if let Ok(int2) = typ2.downcast::<CTypInt>() {
+                Ok(int1.get().ikind() == int2.get().ikind())
+            } else if let Ok(enum2) = typ2.downcast::<CTypEnum>() {
+                self.enum_int_compatible(enum2, int1)
+            } else {
+                Ok(false)
+            }
+        } else if let Ok(float1) = typ1.downcast::<CTypFloat>() {
+            let Ok(float2) = typ2.downcast::<CTypFloat>() else {
+                return Ok(false);
+            };
+            Ok(float1.get().fkind() == float2.get().fkind())
+        } else if let Ok(enum1) = typ1.downcast::<CTypEnum>() {
+            if let Ok(int2) = typ2.downcast::<CTypInt>() {
+                return self.enum_int_compatible(enum1, int2);
+            }
+            if !typ2.is_instance_of::<CTypEnum>() {
+                return Ok(false);
+            }
+            let name = intern!(py, "name");
+            Ok(typ1.getattr(name)?.eq(typ2.getattr(name)?)?)
+        } else if let Ok(ptr1) = typ1.downcast::<CTypPtr>() {
+            let Ok(ptr2) = typ2.downcast::<CTypPtr>() else {
+                return Ok(false);
+            };
+            self.compatible(
+                &CTypPtr::pointedto_type(ptr1.borrow(), py)?,
+                &CTypPtr::pointedto_type(ptr2.borrow(), py)?,
+                true,
+            )
+        } else if let Ok(array1) = typ1.downcast::<CTypArray>() {
+            let Ok(array2) = typ2.downcast::<CTypArray>() else {
+                return Ok(false);
+            };
+            match (
+                CTypArray::array_length(array1)?,
+                CTypArray::array_length(array2)?,
+            ) {
+                (Some(length1), Some(length2)) if length1 != length2 => return Ok(false),
+                (Some(_), None) | (None, Some(_)) if self.identical => return Ok(false),
+                _ => {}
+            }
+            self.compatible(
+                &CTypArray::array_basetype(array1.borrow(), py)?,
+                &CTypArray::array_basetype(array2.borrow(), py)?,
+                true,
+            )
+        } else if let Ok(comp1) = typ1.downcast::<CTypComp>() {
+            let Ok(comp2) = typ2.downcast::<CTypComp>() else {
+                return Ok(false);
+            };
+            self.compinfos_compatible(&CTypComp::compinfo(comp1)?, &CTypComp::compinfo(comp2)?)
+        } else if is_function(&typ1)? {
+            if !is_function(&typ2)? {
+                return Ok(false);
+            }
+            self.functions_compatible(&typ1, &typ2)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// An enum is compatible with its integer kind, but not identical to it.
+    fn enum_int_compatible(&self, typ: &Bound<CTypEnum>, int: &Bound<CTypInt>) -> PyResult<bool> {
+        Ok(!self.identical && CTypEnum::ikind(typ)?.as_deref() == Some(int.get().ikind()))
+    }
+
+    fn compinfos_compatible(
+        &mut self,
+        compinfo1: &Bound<CCompInfo>,
+        compinfo2: &Bound<CCompInfo>,
+    ) -> PyResult<bool> {
+        if CCompInfo::is_struct(compinfo1.borrow()) != CCompInfo::is_struct(compinfo2.borrow())
+            || CCompInfo::name(compinfo1)? != CCompInfo::name(compinfo2)?
+        {
+            return Ok(false);
+        }
+        let key = (
+            CCompInfo::ckey(compinfo1.borrow()),
+            CCompInfo::ckey(compinfo2.borrow()),
+        );
+        if !self.assumed.insert(key) {
+            return Ok(true);
+        }
+        let result = self.fields_compatible(
+            &CCompInfo::fields(compinfo1)?,
+            &CCompInfo::fields(compinfo2)?,
+        );
+        self.assumed.remove(&key);
+        result
+    }
+
+    fn fields_compatible(
+        &mut self,
+        fields1: &[Bound<CFieldInfo>],
+        fields2: &[Bound<CFieldInfo>],
+    ) -> PyResult<bool> {
+        // An incomplete struct is compatible with any struct with the same tag
+        if !self.identical && (fields1.is_empty() || fields2.is_empty()) {
+            return Ok(true);
+        }
+        if fields1.len() != fields2.len() {
+            return Ok(false);
+        }
+        for (field1, field2) in fields1.iter().zip(fields2) {
+            if CFieldInfo::fname(field1.borrow()) != CFieldInfo::fname(field2.borrow())
+                || CFieldInfo::bitfield(field1.borrow()) != CFieldInfo::bitfield(field2.borrow())
+                || !self.compatible(
+                    &CFieldInfo::ftype(field1)?,
+                    &CFieldInfo::ftype(field2)?,
+                    true,
+                )?
+            {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn functions_compatible(&mut self, fun1: &Bound<CTyp>, fun2: &Bound<CTyp>) -> PyResult<bool> {
+        let py = fun1.py();
+        let return_type = intern!(py, "return_type");
+        if !self.compatible(
+            fun1.getattr(return_type)?.downcast()?,
+            fun2.getattr(return_type)?.downcast()?,
+            true,
+        )? {
+            return Ok(false);
+        }
+        match (parameter_types(fun1)?, parameter_types(fun2)?) {
+            (None, None) => Ok(true),
+            (Some(_), None) | (None, Some(_)) if self.identical => Ok(false),
+            (Some(params), None) => promotion_invariant(fun1, &params),
+            (None, Some(params)) => promotion_invariant(fun2, &params),
+            (Some(params1), Some(params2)) => {
+                if params1.len() != params2.len() || is_vararg(fun1)? != is_vararg(fun2)? {
+                    return Ok(false);
+                }
+                for (param1, param2) in params1.iter().zip(&params2) {
+                    if !self.compatible(param1, param2, self.identical)? {
+                        return Ok(false);
+                    }
+                }
+                Ok(true)
+            }
+        }
+    }
+}
+
+/// True if both types are the same record of the same dictionary.
+fn same_record(typ1: &Bound<CTyp>, typ2: &Bound<CTyp>) -> PyResult<bool> {
+    let record1 = typ1.borrow().into_super();
+    let record2 = typ2.borrow().into_super();
+    Ok(record1.cd().is(record2.cd()) && record1.as_super().index() == record2.as_super().index())
+}
+
+/// Type with typedefs expanded, together with the attributes of the type and
+/// of the typedefs, sorted.
+fn unroll<'a>(typ: &Bound<'a, CTyp>) -> PyResult<(Bound<'a, CTyp>, Vec<String>)> {
+    let (unrolled, attributes) = CTyp::unroll_with_attributes(typ)?;
+    let mut attributes = attributes
+        .iter()
+        .map(|attribute| Ok(attribute.str()?.to_string()))
+        .collect::<PyResult<Vec<_>>>()?;
+    attributes.sort();
+    attributes.dedup();
+    Ok((unrolled, attributes))
+}
+
+fn is_function(typ: &Bound<CTyp>) -> PyResult<bool> {
+    typ.getattr(intern!(typ.py(), "is_function"))?.extract()
+}
+
+fn is_vararg(fun: &Bound<CTyp>) -> PyResult<bool> {
+    fun.getattr(intern!(fun.py(), "is_vararg"))?.extract()
+}
+
+/// Types of the parameters, or None if the function has no parameter list.
+fn parameter_types<'a>(fun: &Bound<'a, CTyp>) -> PyResult<Option<Vec<Bound<'a, CTyp>>>> {
+    let py = fun.py();
+    let funargs = fun.getattr(intern!(py, "funargs"))?;
+    if funargs.is_none() {
+        return Ok(None);
+    }
+    funargs
+        .getattr(intern!(py, "arguments"))?
+        .iter()?
+        .map(|funarg| Ok(funarg?.getattr(intern!(py, "typ"))?.downcast_into()?))
+        .collect::<PyResult<Vec<_>>>()
+        .map(Some)
+}
+
+/// True if `fun` with parameter types `params` could be called through a
+/// declaration without parameter list.
+fn promotion_invariant(fun: &Bound<CTyp>, params: &[Bound<CTyp>]) -> PyResult<bool> {
+    if is_vararg(fun)? {
+        return Ok(false);
+    }
+    for param in params {
+        let param = CTyp::unroll(param)?;
+        if let Ok(int) = param.downcast::<CTypInt>() {
+            if matches!(
+                int.get().ikind(),
+                "ichar" | "ischar" | "iuchar" | "ibool" | "ishort" | "iushort"
+            ) {
+                return Ok(false);
+            }
+        } else if let Ok(float) = param.downcast::<CTypFloat>() {
+            if float.get().fkind() == "float" {
+                return Ok(false);
+            }
+        }
+    }
+    Ok(true)
+}
@@ -0,0 +1,352 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Generic traversal of expressions, lvalues, lhosts, offsets and types.
+//!
+//! A [`Visitor`] is called when a node is entered and when it is left, and
+//! can skip the children of a node or stop the traversal. A [`Fold`]
+//! computes a value for each node from the values of its children.
+//!
+//! Named types, struct/union types and enum types are leaves: their
+//! definitions are not part of the type and may be recursive. Likewise the
+//! value of an enumeration constant is not a child of the constant.
+
+use std::{collections::BTreeSet, ops::ControlFlow};
+
+use pyo3::{intern, prelude::*, types::PyString};
+
+use crate::app::{c_exp::CExp, c_lhost::CLHost, c_lval::CLval, c_offset::COffset, c_typ::CTyp};
+
+/// Node of the C abstract syntax tree.
+#[derive(Clone, Debug)]
+pub enum Node<'py> {
+    Exp(Bound<'py, CExp>),
+    Lval(Bound<'py, CLval>),
+    LHost(Bound<'py, CLHost>),
+    Offset(Bound<'py, COffset>),
+    Typ(Bound<'py, CTyp>),
+}
+
+/// What to do after a node has been entered.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Walk {
+    Children,
+    SkipChildren,
+}
+
+/// Visitor with hooks called before (`enter`) and after (`leave`) the
+/// children of a node are visited; either can break off the traversal.
+pub trait Visitor<'py> {
+    type Break;
+
+    fn enter(&mut self, _node: &Node<'py>) -> PyResult<ControlFlow<Self::Break, Walk>> {
+        Ok(ControlFlow::Continue(Walk::Children))
+    }
+
+    fn leave(&mut self, _node: &Node<'py>) -> PyResult<ControlFlow<Self::Break>> {
+        Ok(ControlFlow::Continue(()))
+    }
+}
+
+/// Bottom-up computation over a tree.
+pub trait Fold<'py> {
+    type Output;
+
+    /// Result for `node`, given the results for its children in order.
+    fn fold(&mut self, node: &Node<'py>, children: Vec<Self::Output>) -> PyResult<Self::Output>;
+
+    /// Called before the children of `node` are folded; returning a result
+    /// skips them.
+    fn pre(&mut self, _node: &Node<'py>) -> PyResult<Option<Self::Output>> {
+        Ok(None)
+    }
+}
+
+impl<'py> Node<'py> {
+    pub fn as_any(&self) -> &Bound<'py, PyAny> {
+        match self {
+            Node::Exp(exp) => exp.as_any(),
+            Node::Lval(lval) => lval.as_any(),
+            Node::LHost(lhost) => lhost.as_any(),
+            Node::Offset(offset) => offset.as_any(),
+            Node::Typ(typ) => typ.as_any(),
+        }
+    }
+
+    /// Visits this node and its descendants in depth-first order.
+    pub fn walk<V: Visitor<'py>>(&self, visitor: &mut V) -> PyResult<ControlFlow<V::Break>> {
+        match visitor.enter(self)? {
+            ControlFlow::Break(result) => return Ok(ControlFlow::Break(result)),
+            ControlFlow::Continue(Walk::SkipChildren) => {}
+            ControlFlow::Continue(Walk::Children) => {
+                for child in self.children()? {
+                    if let ControlFlow::Break(result) = child.walk(visitor)? {
+                        return Ok(ControlFlow::Break(result));
+                    }
+                }
+            }
+        }
+        visitor.leave(self)
+    }
+
+    /// Result of `fold` for this node.
+    pub fn fold<F: Fold<'py>>(&self, fold: &mut F) -> PyResult<F::Output> {
+        if let Some(result) = fold.pre(self)? {
+            return Ok(result);
+        }
+        let children = self
+            .children()?
+            .iter()
+            .map(|child| child.fold(fold))
+            .collect::<PyResult<Vec<_>>>()?;
+        fold.fold(self, children)
+    }
+
+    /// Direct subterms of this node, in source order.
+    pub fn children(&self) -> PyResult<Vec<Node<'py>>> {
+        let item = self.as_any();
+        let py = item.py();
+        let get = |name: &Bound<PyString>| item.getattr(name);
+        let mut children = Vec::new();
+        match self {
+            Node::Exp(_) => {
+                if flag(item, intern!(py, "is_lval"))?
+                    || flag(item, intern!(py, "is_addrof"))?
+                    || flag(item, intern!(py, "is_startof"))?
+                {
+                    children.push(Node::Lval(get(intern!(py, "lval"))?.downcast_into()?));
+                } else if flag(item, intern!(py, "is_sizeof"))?
+                    || flag(item, intern!(py, "is_alignof"))?
+                {
+                    children.push(Node::Typ(get(intern!(py, "typ"))?.downcast_into()?));
+                } else if flag(item, intern!(py, "is_sizeofe"))?
+                    || flag(item, intern!(py, "is_alignofe"))?
+                {
+                    children.push(Node::Exp(get(intern!(py, "exp"))?.downcast_into()?));
+                } else if flag(item, intern!(py, "is_unop"))? {
+                    children.push(Node::Exp(get(intern!(py, "exp"))?.downcast_into()?));
+                    children.push(Node::Typ(get(intern!(py, "typ"))?.downcast_into()?));
+                } else if flag(item, intern!(py, "is_binop"))? {
+                    children.push(Node::Exp(get(intern!(py, "exp1"))?.downcast_into()?));
+                    children.push(Node::Exp(get(intern!(py, "exp2"))?.downcast_into()?));
+                    children.push(Node::Typ(get(intern!(py, "typ"))?.downcast_into()?));
+                } else if flag(item, intern!(py, "is_question"))? {
+                    for name in [
+                        intern!(py, "condition"),
+                        intern!(py, "true_exp"),
+                        intern!(py, "false_exp"),
+                    ] {
+                        children.push(Node::Exp(get(name)?.downcast_into()?));
+                    }
+                    children.push(Node::Typ(get(intern!(py, "typ"))?.downcast_into()?));
+                } else if flag(item, intern!(py, "is_caste"))? {
+                    children.push(Node::Typ(get(intern!(py, "typ"))?.downcast_into()?));
+                    children.push(Node::Exp(get(intern!(py, "exp"))?.downcast_into()?));
+                } else if flag(item, intern!(py, "is_fn_app"))? {
+                    children.push(Node::Exp(get(intern!(py, "exp"))?.downcast_into()?));
+                    push_arguments(&mut children, item)?;
+                } else if flag(item, intern!(py, "is_cn_app"))? {
+                    push_arguments(&mut children, item)?;
+                    children.push(Node::Typ(get(intern!(py, "typ"))?.downcast_into()?));
+                }
+            }
+            Node::Lval(_) => {
+                children.push(Node::LHost(get(intern!(py, "lhost"))?.downcast_into()?));
+                children.push(Node::Offset(get(intern!(py, "offset"))?.downcast_into()?));
+            }
+            Node::LHost(_) => {
+                if flag(item, intern!(py, "is_mem"))? {
+                    children.push(Node::Exp(get(intern!(py, "exp"))?.downcast_into()?));
+                }
+            }
+            Node::Offset(_) => {
+                if flag(item, intern!(py, "is_index"))? {
+                    let index = get(intern!(py, "index_exp"))?;
+                    children.push(Node::Exp(index.downcast_into()?));
+                }
+                if !flag(item, intern!(py, "is_no_offset"))? {
+                    let offset = get(intern!(py, "offset"))?;
+                    children.push(Node::Offset(offset.downcast_into()?));
+                }
+            }
+            Node::Typ(_) => {
+                if flag(item, intern!(py, "is_pointer"))? {
+                    let target = get(intern!(py, "pointedto_type"))?;
+                    children.push(Node::Typ(target.downcast_into()?));
+                } else if flag(item, intern!(py, "is_array"))? {
+                    let base = get(intern!(py, "array_basetype"))?;
+                    children.push(Node::Typ(base.downcast_into()?));
+                    let has_size: bool = item
+                        .call_method0(intern!(py, "has_array_size_expr"))?
+                        .extract()?;
+                    if has_size {
+                        let size = get(intern!(py, "array_size_expr"))?;
+                        children.push(Node::Exp(size.downcast_into()?));
+                    }
+                } else if flag(item, intern!(py, "is_function"))? {
+                    let return_type = get(intern!(py, "return_type"))?;
+                    children.push(Node::Typ(return_type.downcast_into()?));
+                    let funargs = get(intern!(py, "funargs"))?;
+                    if !funargs.is_none() {
+                        for funarg in funargs.getattr(intern!(py, "arguments"))?.iter()? {
+                            let typ = funarg?.getattr(intern!(py, "typ"))?;
+                            children.push(Node::Typ(typ.downcast_into()?));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(children)
+    }
+}
+
+fn flag(item: &Bound<PyAny>, name: &Bound<PyString>) -> PyResult<bool> {
+    item.getattr(name)?.extract()
+}
+
+/// Known arguments of a function or constructor application.
+fn push_arguments<'py>(children: &mut Vec<Node<'py>>, exp: &Bound<'py, PyAny>) -> PyResult<()> {
+    for argument in exp.getattr(intern!(exp.py(), "arguments"))?.iter()? {
+        let argument = argument?;
+        if !argument.is_none() {
+            children.push(Node::Exp(argument.downcast_into()?));
+        }
+    }
+    Ok(())
+}
+
+/// Visitor that calls python hooks with each node; see `CExp.walk`.
+pub struct PyVisitor<'py> {
+    enter: Option<Bound<'py, PyAny>>,
+    leave: Option<Bound<'py, PyAny>>,
+}
+
+impl<'py> PyVisitor<'py> {
+    /// Visits `node` with the hooks; returns the value a hook stopped the
+    /// traversal with, or None.
+    pub fn walk(
+        node: &Node<'py>,
+        enter: Option<Bound<'py, PyAny>>,
+        leave: Option<Bound<'py, PyAny>>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        Ok(match node.walk(&mut PyVisitor { enter, leave })? {
+            ControlFlow::Break(result) => Some(result),
+            ControlFlow::Continue(()) => None,
+        })
+    }
+
+    /// Result of `hook` for `node`, if it is given and does not return None.
+    fn call(
+        hook: &Option<Bound<'py, PyAny>>,
+        node: &Node<'py>,
+    ) -> PyResult<Option<Bound<'py, PyAny>>> {
+        let Some(hook) = hook else {
+            return Ok(None);
+        };
+        let result = hook.call1((node.as_any(),))?;
+        Ok((!result.is_none()).then_some(result))
+    }
+}
+
+impl<'py> Visitor<'py> for PyVisitor<'py> {
+    type Break = Bound<'py, PyAny>;
+
+    fn enter(&mut self, node: &Node<'py>) -> PyResult<ControlFlow<Self::Break, Walk>> {
+        Ok(match Self::call(&self.enter, node)? {
+            Some(result) => ControlFlow::Break(result),
+            None => ControlFlow::Continue(Walk::Children),
+        })
+    }
+
+    fn leave(&mut self, node: &Node<'py>) -> PyResult<ControlFlow<Self::Break>> {
+        Ok(match Self::call(&self.leave, node)? {
+            Some(result) => ControlFlow::Break(result),
+            None => ControlFlow::Continue(()),
+        })
+    }
+}
+
+/// Collects the address expressions of memory references, outermost first;
+/// array sizes in types are not included.
+pub struct Dereferences<'py> {
+    exps: Vec<Bound<'py, CExp>>,
+}
+
+impl<'py> Dereferences<'py> {
+    pub fn of(node: &Node<'py>) -> PyResult<Vec<Bound<'py, CExp>>> {
+        let mut dereferences = Dereferences { exps: Vec::new() };
+        // The traversal is never broken off
+        let _ = node.walk(&mut dereferences)?;
+        Ok(dereferences.exps)
+    }
+}
+
+impl<'py> Visitor<'py> for Dereferences<'py> {
+    type Break = ();
+
+    fn enter(&mut self, node: &Node<'py>) -> PyResult<ControlFlow<(), Walk>> {
+        if let Node::Typ(_) = node {
+            return Ok(ControlFlow::Continue(Walk::SkipChildren));
+        }
+        if let Node::LHost(lhost) = node {
+            if flag(lhost.as_any(), intern!(lhost.py(), "is_mem"))? {
+                let exp = lhost.as_any().getattr(intern!(lhost.py(), "exp"))?;
+                self.exps.push(exp.downcast_into()?);
+            }
+        }
+        Ok(ControlFlow::Continue(Walk::Children))
+    }
+}
+
+/// Ids of the variables in a tree; array sizes in types are not included.
+pub struct VariableIds;
+
+impl<'py> Fold<'py> for VariableIds {
+    type Output = BTreeSet<isize>;
+
+    fn pre(&mut self, node: &Node<'py>) -> PyResult<Option<BTreeSet<isize>>> {
+        Ok(matches!(node, Node::Typ(_)).then(BTreeSet::new))
+    }
+
+    fn fold(
+        &mut self,
+        node: &Node<'py>,
+        children: Vec<BTreeSet<isize>>,
+    ) -> PyResult<BTreeSet<isize>> {
+        let mut vids: BTreeSet<isize> = children.into_iter().flatten().collect();
+        if let Node::LHost(lhost) = node {
+            let py = lhost.py();
+            if flag(lhost.as_any(), intern!(py, "is_var"))? {
+                vids.insert(lhost.as_any().getattr(intern!(py, "vid"))?.extract()?);
+            }
+        }
+        Ok(vids)
+    }
+}
@@ -0,0 +1,283 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Global symbols of an application across its files.
+
+use std::collections::BTreeMap;
+
+use pyo3::{intern, prelude::*};
+
+use crate::app::{
+    c_typ::{CTyp, CTypFun},
+    index_manager::{FileVarReference, IndexManager},
+};
+
+pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
+    let module = PyModule::new_bound(py, "global_symbol_table")?;
+    module.add_class::<GlobalSymbol>()?;
+    module.add_class::<GlobalSymbolTable>()?;
+    Ok(module)
+}
+
+/// Global function or variable, with the (fid, vid) references of its
+/// declarations and definitions in the files of the application.
+#[pyclass(frozen)]
+#[derive(Clone)]
+pub struct GlobalSymbol {
+    #[pyo3(get)]
+    name: String,
+    #[pyo3(get)]
+    gvid: isize,
+    #[pyo3(get)]
+    is_function: bool,
+    declared: Vec<(isize, isize)>,
+    defined: Vec<(isize, isize)>,
+}
+
+#[pymethods]
+impl GlobalSymbol {
+    #[getter]
+    fn declarations(&self) -> Vec<FileVarReference> {
+        to_references(&self.declared)
+    }
+
+    #[getter]
+    fn definitions(&self) -> Vec<FileVarReference> {
+        to_references(&self.defined)
+    }
+
+    #[getter]
+    fn is_defined(&self) -> bool {
+        !self.defined.is_empty()
+    }
+
+    #[getter]
+    fn is_multiply_defined(&self) -> bool {
+        self.defined.len() > 1
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self) -> String {
+        let kind = if self.is_function {
+            "function"
+        } else {
+            "variable"
+        };
+        let files = |refs: &[(isize, isize)]| {
+            refs.iter()
+                .map(|(fid, _)| fid.to_string())
+                .collect::<Vec<_>>()
+                .join(",")
+        };
+        format!(
+            "{kind} {} (gvid: {}; declared in: [{}]; defined in: [{}])",
+            self.name,
+            self.gvid,
+            files(&self.declared),
+            files(&self.defined)
+        )
+    }
+}
+
+fn to_references(refs: &[(isize, isize)]) -> Vec<FileVarReference> {
+    refs.iter()
+        .map(|&(fid, vid)| FileVarReference::new(fid, vid))
+        .collect()
+}
+
+/// Functions and global variables of all files, by global vid and by name.
+///
+/// The symbols are collected from the files when the table is built, and
+/// resolved through the maps of the index manager. The table is stale once
+/// the maps changed, when `is_current` returns False.
+#[pyclass(frozen)]
+pub struct GlobalSymbolTable {
+    indexmanager: Py<IndexManager>,
+    version: u64,                           // version of the index manager
+    symbols: BTreeMap<isize, GlobalSymbol>, // gvid -> symbol
+    names: BTreeMap<String, Vec<isize>>,    // name -> gvids
+}
+
+impl GlobalSymbolTable {
+    /// Table of the global declarations and definitions of `cfiles`.
+    pub fn new(
+        indexmanager: &Bound<IndexManager>,
+        cfiles: &[Bound<PyAny>],
+    ) -> PyResult<GlobalSymbolTable> {
+        let mut table = GlobalSymbolTable {
+            indexmanager: indexmanager.clone().unbind(),
+            version: indexmanager.borrow().version,
+            symbols: BTreeMap::new(),
+            names: BTreeMap::new(),
+        };
+        let indexmanager = indexmanager.borrow();
+        for cfile in cfiles {
+            table.add_file(&indexmanager, cfile)?;
+        }
+        Ok(table)
+    }
+
+    /// Adds the global declarations and definitions of `cfile`.
+    fn add_file(&mut self, indexmanager: &IndexManager, cfile: &Bound<PyAny>) -> PyResult<()> {
+        let py = cfile.py();
+        let fid: isize = cfile.getattr(intern!(py, "index"))?.extract()?;
+        for (attr, defined) in [
+            (intern!(py, "gfunctions"), true),
+            (intern!(py, "gvardefs"), true),
+            (intern!(py, "gvardecls"), false),
+        ] {
+            for global in cfile
+                .getattr(attr)?
+                .call_method0(intern!(py, "values"))?
+                .iter()?
+            {
+                let varinfo = global?.getattr(intern!(py, "varinfo"))?;
+                let vid: isize = varinfo.getattr(intern!(py, "vid"))?.extract()?;
+                let Some(gvid) = indexmanager.get_gvid(FileVarReference::new(fid, vid)) else {
+                    continue;
+                };
+                let symbol = match self.symbols.get_mut(&gvid) {
+                    Some(symbol) => symbol,
+                    None => {
+                        let name: String = varinfo.getattr(intern!(py, "vname"))?.extract()?;
+                        let vtype = varinfo.getattr(intern!(py, "vtype"))?;
+                        // A variable of a typedef'd function type is a function
+                        let is_function =
+                            CTyp::unroll(vtype.downcast()?)?.is_instance_of::<CTypFun>();
+                        self.names.entry(name.clone()).or_default().push(gvid);
+                        self.symbols.entry(gvid).or_insert(GlobalSymbol {
+                            name,
+                            gvid,
+                            is_function,
+                            declared: Vec::new(),
+                            defined: Vec::new(),
+                        })
+                    }
+                };
+                if defined {
+                    symbol.defined.push((fid, vid));
+                } else {
+                    symbol.declared.push((fid, vid));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn symbols_named(&self, name: &str) -> impl Iterator<Item = &GlobalSymbol> {
+        self.names
+            .get(name)
+            .into_iter()
+            .flatten()
+            .filter_map(|gvid| self.symbols.get(gvid))
+    }
+}
+
+#[pymethods]
+impl GlobalSymbolTable {
+    /// True if the maps of the index manager did not change since the table
+    /// was built.
+    #[getter]
+    fn is_current(&self, py: Python) -> bool {
+        self.indexmanager.borrow(py).version == self.version
+    }
+
+    /// Names of all symbols, in sorted order.
+    #[getter]
+    fn names(&self) -> Vec<String> {
+        self.names.keys().cloned().collect()
+    }
+
+    fn __len__(&self) -> usize {
+        self.symbols.len()
+    }
+
+    fn has_symbol(&self, name: &str) -> bool {
+        self.names.contains_key(name)
+    }
+
+    /// Symbols named `name`; more than one if files define distinct
+    /// (static) symbols with the same name.
+    fn lookup(&self, name: &str) -> Vec<GlobalSymbol> {
+        self.symbols_named(name).cloned().collect()
+    }
+
+    fn get_symbol(&self, gvid: isize) -> Option<GlobalSymbol> {
+        self.symbols.get(&gvid).cloned()
+    }
+
+    /// Files and vids where `name` is declared.
+    fn get_declarations(&self, name: &str) -> Vec<FileVarReference> {
+        self.symbols_named(name)
+            .flat_map(|symbol| symbol.declarations())
+            .collect()
+    }
+
+    /// Files and vids where `name` is defined.
+    fn get_definitions(&self, name: &str) -> Vec<FileVarReference> {
+        self.symbols_named(name)
+            .flat_map(|symbol| symbol.definitions())
+            .collect()
+    }
+
+    /// Symbols defined in more than one file.
+    fn multiply_defined(&self) -> Vec<GlobalSymbol> {
+        self.symbols
+            .values()
+            .filter(|symbol| symbol.is_multiply_defined())
+            .cloned()
+            .collect()
+    }
+
+    /// Symbols that are declared, but not defined in any file.
+    fn undefined_externals(&self) -> Vec<GlobalSymbol> {
+        self.symbols
+            .values()
+            .filter(|symbol| !symbol.is_defined())
+            .cloned()
+            .collect()
+    }
+
+    /// Returns the global vid that corresponds to the file var reference.
+    fn get_gvid(&self, py: Python, filevar: FileVarReference) -> Option<isize> {
+        self.indexmanager.borrow(py).get_gvid(filevar)
+    }
+
+    /// Returns the local reference of the definition of (fid, vid).
+    ///
+    /// Same as IndexManager.resolve_vid.
+    fn resolve_vid(
+        &self,
+        py: Python,
+        filevar: FileVarReference,
+    ) -> PyResult<Option<FileVarReference>> {
+        self.indexmanager.borrow(py).resolve_vid(py, filevar)
+    }
+}
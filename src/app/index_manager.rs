@@ -30,7 +30,9 @@ SOFTWARE.
 */
 use std::collections::BTreeMap;
 
-use pyo3::{intern, prelude::*, types::PyDict};
+use pyo3::prelude::*;
+
+use crate::app::{c_file::chklogger, global_symbol_table::GlobalSymbolTable};
 
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "index_manager")?;
@@ -53,12 +55,12 @@ pub struct FileVarReference {
 #[pymethods]
 impl FileVarReference {
     #[new]
-    fn new(fid: isize, vid: isize) -> FileVarReference {
+    pub(crate) fn new(fid: isize, vid: isize) -> FileVarReference {
         FileVarReference { fid, vid }
     }
 
     #[getter]
-    fn tuple(&self) -> (isize, isize) {
+    pub(crate) fn tuple(&self) -> (isize, isize) {
         (self.fid, self.vid)
     }
 
@@ -131,61 +133,151 @@ impl CKeyReference {
 }
 
 fn chklogger_debug(py: Python, text: String) -> PyResult<()> {
-    let chc = PyModule::import_bound(py, intern!(py, "chc"))?;
-    let util = chc.getattr(intern!(py, "util"))?;
-    let loggingutil = util.getattr(intern!(py, "loggingutil"))?;
-    let chklogger = loggingutil.getattr(intern!(py, "chklogger"))?;
-    let logger = chklogger.getattr(intern!(py, "logger"))?;
-    logger.call_method1(intern!(py, "debug"), (text,))?;
-    Ok(())
+    chklogger(py, "debug", text)
 }
 
-#[pyclass(get_all, subclass)]
+/// Maps between the vids and ckeys of the files and the global vids and
+/// ckeys of the application.
+///
+/// The maps are read from the xref files of the files and extended by the
+/// linker. Every change increments `version`, by which tables derived from
+/// the maps, such as the global symbol table, detect that they are stale.
+#[pyclass(subclass)]
 pub struct IndexManager {
-    is_single_file: bool,   // application consists of a single file
-    vid2gvid: Py<PyDict>,   // fid -> vid -> gvid
-    gvid2vid: Py<PyDict>,   // gvid -> fid -> vid
-    fidvidmax: Py<PyDict>,  // fid -> maximum vid in file with index fid
-    ckey2gckey: Py<PyDict>, // fid -> ckey -> gckey
-    gckey2ckey: Py<PyDict>, // gckey -> fid -> ckey
-    gviddefs: Py<PyDict>,   // gvid -> fid  (file in which gvid is defined)
+    #[pyo3(get)]
+    is_single_file: bool, // application consists of a single file
+    vid2gvid: BTreeMap<isize, BTreeMap<isize, isize>>, // fid -> vid -> gvid
+    gvid2vid: BTreeMap<isize, BTreeMap<isize, isize>>, // gvid -> fid -> vid
+    fidvidmax: BTreeMap<isize, isize>,                 // fid -> maximum vid in file with index fid
+    ckey2gckey: BTreeMap<isize, BTreeMap<isize, isize>>, // fid -> ckey -> gckey
+    gckey2ckey: BTreeMap<isize, BTreeMap<isize, isize>>, // gckey -> fid -> ckey
+    gviddefs: BTreeMap<isize, isize>, // gvid -> fid  (file in which gvid is defined)
+    #[pyo3(get)]
+    pub(crate) version: u64,
 }
 
 #[pymethods]
 impl IndexManager {
     #[new]
-    fn new(py: Python, issinglefile: bool) -> IndexManager {
+    fn new(issinglefile: bool) -> IndexManager {
         IndexManager {
             is_single_file: issinglefile,
-            vid2gvid: PyDict::new_bound(py).unbind(),
-            gvid2vid: PyDict::new_bound(py).unbind(),
-            fidvidmax: PyDict::new_bound(py).unbind(),
-            ckey2gckey: PyDict::new_bound(py).unbind(),
-            gckey2ckey: PyDict::new_bound(py).unbind(),
-            gviddefs: PyDict::new_bound(py).unbind(),
+            vid2gvid: BTreeMap::new(),
+            gvid2vid: BTreeMap::new(),
+            fidvidmax: BTreeMap::new(),
+            ckey2gckey: BTreeMap::new(),
+            gckey2ckey: BTreeMap::new(),
+            gviddefs: BTreeMap::new(),
+            version: 0,
         }
     }
 
-    // Seems unused
-    fn get_vid_gvid_subst(&self, py: Python, fid: isize) -> PyResult<BTreeMap<isize, isize>> {
-        Ok(self.vid2gvid.bind(py).as_any().get_item(fid)?.extract()?)
+    /// Copy of the map fid -> vid -> gvid.
+    #[getter]
+    fn vid2gvid(&self) -> BTreeMap<isize, BTreeMap<isize, isize>> {
+        self.vid2gvid.clone()
     }
 
-    // Seems unused
-    fn get_fid_gvid_subset(
-        &self,
-        py: Python,
-        fileindex: isize,
-    ) -> PyResult<BTreeMap<isize, isize>> {
-        let mut result = BTreeMap::new();
-        for (gvid, table) in self.gvid2vid.bind(py) {
-            for (fid, value) in table.downcast::<PyDict>()? {
-                if fid.extract::<isize>()? == fileindex {
-                    result.insert(gvid.extract()?, value.extract()?);
-                }
+    /// Copy of the map gvid -> fid -> vid.
+    #[getter]
+    fn gvid2vid(&self) -> BTreeMap<isize, BTreeMap<isize, isize>> {
+        self.gvid2vid.clone()
+    }
+
+    /// Copy of the map fid -> maximum vid.
+    #[getter]
+    fn fidvidmax(&self) -> BTreeMap<isize, isize> {
+        self.fidvidmax.clone()
+    }
+
+    /// Copy of the map fid -> ckey -> gckey.
+    #[getter]
+    fn ckey2gckey(&self) -> BTreeMap<isize, BTreeMap<isize, isize>> {
+        self.ckey2gckey.clone()
+    }
+
+    /// Copy of the map gckey -> fid -> ckey.
+    #[getter]
+    fn gckey2ckey(&self) -> BTreeMap<isize, BTreeMap<isize, isize>> {
+        self.gckey2ckey.clone()
+    }
+
+    /// Copy of the map gvid -> fid of the file that defines gvid.
+    #[getter]
+    fn gviddefs(&self) -> BTreeMap<isize, isize> {
+        self.gviddefs.clone()
+    }
+
+    /// Registers a local file vid with a global vid.
+    pub fn add_vid2gvid(&mut self, filevar: FileVarReference, gvid: isize) {
+        let (fid, vid) = filevar.tuple();
+        self.vid2gvid.entry(fid).or_default().insert(vid, gvid);
+        self.gvid2vid.entry(gvid).or_default().insert(fid, vid);
+        self.version += 1;
+    }
+
+    /// Registers a local file ckey with a global ckey.
+    pub fn add_ckey2gckey(&mut self, filekey: PyRef<FileKeyReference>, gckey: isize) {
+        let (fid, ckey) = (filekey.fid, filekey.ckey);
+        self.ckey2gckey.entry(fid).or_default().insert(ckey, gckey);
+        self.gckey2ckey.entry(gckey).or_default().insert(fid, ckey);
+        self.version += 1;
+    }
+
+    /// Records that gvid is defined in the file with index fid.
+    pub fn add_gviddef(&mut self, gvid: isize, fid: isize) {
+        self.gviddefs.insert(gvid, fid);
+        self.version += 1;
+    }
+
+    /// Resets the maximum vid of the file with index fid.
+    fn set_fidvidmax(&mut self, fid: isize, vid: isize) {
+        self.fidvidmax.insert(fid, vid);
+    }
+
+    /// Removes the cross references and definitions of file fid.
+    pub fn remove_file(&mut self, fid: isize) {
+        for gvid in self
+            .vid2gvid
+            .remove(&fid)
+            .into_iter()
+            .flat_map(|m| m.into_values())
+        {
+            if let Some(vids) = self.gvid2vid.get_mut(&gvid) {
+                vids.remove(&fid);
+            }
+        }
+        for gckey in self
+            .ckey2gckey
+            .remove(&fid)
+            .into_iter()
+            .flat_map(|m| m.into_values())
+        {
+            if let Some(ckeys) = self.gckey2ckey.get_mut(&gckey) {
+                ckeys.remove(&fid);
             }
         }
-        Ok(result)
+        self.gviddefs.retain(|_, deffid| *deffid != fid);
+        self.fidvidmax.remove(&fid);
+        self.version += 1;
+    }
+
+    /// Returns the map vid -> gvid of the file with index fid.
+    fn get_vid_gvid_subst(&self, fid: isize) -> BTreeMap<isize, isize> {
+        self.vid2gvid.get(&fid).cloned().unwrap_or_default()
+    }
+
+    /// Returns the map ckey -> gckey of the file with index fid.
+    fn get_ckey_gckey_subst(&self, fid: isize) -> BTreeMap<isize, isize> {
+        self.ckey2gckey.get(&fid).cloned().unwrap_or_default()
+    }
+
+    // Seems unused
+    fn get_fid_gvid_subset(&self, fileindex: isize) -> BTreeMap<isize, isize> {
+        self.gvid2vid
+            .iter()
+            .filter_map(|(gvid, vids)| Some((*gvid, *vids.get(&fileindex)?)))
+            .collect()
     }
 
     /// Returns the local reference of the definition of (fid, vid).
@@ -193,8 +285,8 @@ impl IndexManager {
     /// An object (variable or function) may be declared in one file (fid) and referenced by vid,
     /// but defined in another file, with file index def-fid and variable reference def-vid. If the
     /// definition is found this method returns (def-fid, def-vid).
-    fn resolve_vid(
-        &mut self,
+    pub fn resolve_vid(
+        &self,
         py: Python,
         filevar: FileVarReference,
     ) -> PyResult<Option<FileVarReference>> {
@@ -202,127 +294,188 @@ impl IndexManager {
             return Ok(Some(filevar)); // there is only one file, so all objects must be defined there.
         }
         let (fid, vid) = filevar.tuple();
-        let Some(vid2gvid_fid) = self.vid2gvid.bind(py).get_item(fid)? else {
+        let Some(vid2gvid_fid) = self.vid2gvid.get(&fid) else {
             chklogger_debug(py, format!("file id {fid} not found in vid2gvid"))?;
             return Ok(None);
         };
-        let vid2gvid_fid = vid2gvid_fid.downcast::<PyDict>()?;
-        let Some(gvid) = vid2gvid_fid.get_item(vid)? else {
+        let Some(&gvid) = vid2gvid_fid.get(&vid) else {
             chklogger_debug(
                 py,
                 format!("local vid {vid} not found in vid2gvid[{fid}] for ({fid}, {vid})"),
             )?;
             return Ok(None);
         };
-        let gvid: isize = gvid.extract()?;
-        let Some(deffid) = self.gviddefs.bind(py).get_item(gvid)? else {
+        let Some(&deffid) = self.gviddefs.get(&gvid) else {
             chklogger_debug(
                 py,
                 format!("global vid {gvid} not found gviddefs for ({fid}, {vid})"),
             )?;
             return Ok(None);
         };
-        let deffid: isize = deffid.extract()?;
-        let Some(gvid2vid_gvid) = self.gvid2vid.bind(py).get_item(gvid)? else {
+        let Some(gvid2vid_gvid) = self.gvid2vid.get(&gvid) else {
             chklogger_debug(
                 py,
                 format!("global vid {gvid} not found in gvid2vid for ({fid}, {vid})"),
             )?;
             return Ok(None);
         };
-        let gvid2vid_gvid = gvid2vid_gvid.downcast::<PyDict>()?;
-        let Some(defvid) = gvid2vid_gvid.get_item(deffid)? else {
+        let Some(&defvid) = gvid2vid_gvid.get(&deffid) else {
             chklogger_debug(
                 py,
                 format!("target fid: {deffid} not found in gvid2vid[{gvid}] for ({fid}, {vid})"),
             )?;
             return Ok(None);
         };
-        let defvid: isize = defvid.extract()?;
         Ok(Some(FileVarReference::new(deffid, defvid)))
     }
 
     // Seems unused
     /// Returns a list all file variables that refer to the same global var.
-    fn get_gvid_references(&self, py: Python, gvid: isize) -> PyResult<Vec<Py<FileVarReference>>> {
-        let Some(gvid2vid_gvid) = self.gvid2vid.bind(py).get_item(gvid)? else {
-            return Ok(vec![]);
-        };
-        let gvid2vid_gvid = gvid2vid_gvid.downcast::<PyDict>()?;
-        gvid2vid_gvid
+    fn get_gvid_references(&self, gvid: isize) -> Vec<FileVarReference> {
+        self.gvid2vid
+            .get(&gvid)
             .into_iter()
-            .map(|(fid, vid)| Py::new(py, FileVarReference::new(fid.extract()?, vid.extract()?)))
+            .flatten()
+            .map(|(&fid, &vid)| FileVarReference::new(fid, vid))
             .collect()
     }
 
     // Seems unused
-    fn has_gvid_reference(&self, py: Python, gvid: isize, fid: isize) -> PyResult<bool> {
-        let Some(gvid2vid_gvid) = self.gvid2vid.bind(py).get_item(gvid)? else {
-            return Ok(false);
-        };
-        let gvid2vid_gvid = gvid2vid_gvid.downcast::<PyDict>()?;
-        gvid2vid_gvid.contains(fid)
+    fn has_gvid_reference(&self, gvid: isize, fid: isize) -> bool {
+        self.get_gvid_reference(gvid, fid).is_some()
     }
 
     // Seems unused
     /// Returns the vid that corresponds to gvid in the file with index fid.
-    fn get_gvid_reference(&self, py: Python, gvid: isize, fid: isize) -> PyResult<Option<isize>> {
-        let Some(gvid2vid_gvid) = self.gvid2vid.bind(py).get_item(gvid)? else {
-            return Ok(None);
-        };
-        let gvid2vid_gvid = gvid2vid_gvid.downcast::<PyDict>()?;
-        if let Some(ret) = gvid2vid_gvid.get_item(fid)? {
-            Ok(Some(ret.extract()?))
-        } else {
-            Ok(None)
-        }
+    fn get_gvid_reference(&self, gvid: isize, fid: isize) -> Option<isize> {
+        self.gvid2vid.get(&gvid)?.get(&fid).copied()
     }
 
     // Seems unused
     /// Returns a list of file vars that refer to the same variable as filevar.
     ///
     /// Note: does not include filevar itself.
-    fn get_vid_references(
-        &self,
-        py: Python,
-        filevar: Bound<FileVarReference>,
-    ) -> PyResult<Vec<Py<FileVarReference>>> {
+    fn get_vid_references(&self, filevar: FileVarReference) -> Vec<FileVarReference> {
+        let Some(gvid) = self.get_gvid(filevar.clone()) else {
+            return vec![];
+        };
         if self.is_single_file {
-            return Ok(vec![]);
+            return vec![];
         }
-        let Some(vid2gvid_fid) = self.vid2gvid.bind(py).get_item(filevar.borrow().fid)? else {
-            return Ok(vec![]);
-        };
-        let vid2gvid_fid = vid2gvid_fid.downcast::<PyDict>()?;
-        let Some(gvid) = vid2gvid_fid.get_item(filevar.borrow().vid)? else {
-            return Ok(vec![]);
-        };
-        let gvid: isize = gvid.extract()?;
-        let Some(gvid2vid_gvid) = self.gvid2vid.bind(py).get_item(gvid)? else {
-            return Ok(vec![]);
-        };
-        let gvid2vid_gvid = gvid2vid_gvid.downcast::<PyDict>()?;
-        gvid2vid_gvid
+        self.get_gvid_references(gvid)
             .into_iter()
-            .filter(|(fid, _)| fid.extract().unwrap_or(-1) != filevar.borrow().fid)
-            .map(|(fid, vid)| Py::new(py, FileVarReference::new(fid.extract()?, vid.extract()?)))
+            .filter(|r| r.fid != filevar.fid)
             .collect()
     }
 
+    /// Symbol table of the functions and global variables declared or defined
+    /// in `cfiles`, resolved through the vid/gvid maps of this index manager.
+    fn symbol_table(slf: &Bound<Self>, cfiles: Vec<Bound<PyAny>>) -> PyResult<GlobalSymbolTable> {
+        GlobalSymbolTable::new(slf, &cfiles)
+    }
+
     /// Returns the global vid that corresponds to the file var reference.
-    fn get_gvid(&self, py: Python, varref: Bound<FileVarReference>) -> PyResult<Option<isize>> {
-        let varref = varref.borrow();
+    pub fn get_gvid(&self, filevar: FileVarReference) -> Option<isize> {
+        let (fid, vid) = filevar.tuple();
+        if self.is_single_file {
+            return Some(vid);
+        }
+        self.vid2gvid.get(&fid)?.get(&vid).copied()
+    }
+
+    /// Returns the vid of the gvid in the file with index fid.
+    fn get_vid(&self, fid: isize, gvid: isize) -> Option<isize> {
         if self.is_single_file {
+            return Some(gvid);
+        }
+        self.get_gvid_reference(gvid, fid)
+    }
+
+    /// Returns the vid of the var reference in (another) file tgtfid.
+    fn convert_vid(
+        &self,
+        py: Python,
+        varref: FileVarReference,
+        tgtfid: isize,
+    ) -> PyResult<Option<isize>> {
+        if varref.fid == tgtfid {
+            // same file
             return Ok(Some(varref.vid));
         }
-        let Some(vid2gvid_fid) = self.vid2gvid.bind(py).get_item(varref.fid)? else {
+        let Some(gvid) = self.get_gvid(varref.clone()) else {
+            return Ok(None);
+        };
+        let Some(vids) = self.gvid2vid.get(&gvid) else {
+            return Ok(None);
+        };
+        match vids.get(&tgtfid) {
+            Some(&vid) => Ok(Some(vid)),
+            None => {
+                chklogger(
+                    py,
+                    "warning",
+                    format!(
+                        "failed to convert {} for file {tgtfid} (found gvid: {gvid})",
+                        varref.str()
+                    ),
+                )?;
+                Ok(None)
+            }
+        }
+    }
+
+    /// Returns the global ckey index for a file ckey reference.
+    fn get_gckey(&self, py: Python, filekey: PyRef<FileKeyReference>) -> PyResult<Option<isize>> {
+        if self.is_single_file {
+            // for a single file the global ckey is the same the file ckey
+            return Ok(Some(filekey.ckey));
+        }
+        let gckey = self
+            .ckey2gckey
+            .get(&filekey.fid)
+            .and_then(|ckeys| ckeys.get(&filekey.ckey));
+        if gckey.is_none() {
+            chklogger(
+                py,
+                "warning",
+                format!("No global key found for file key {}", filekey.str()),
+            )?;
+        }
+        Ok(gckey.copied())
+    }
+
+    /// Returns the ckey of filekey of the same struct in the file tgtfid.
+    fn convert_ckey(
+        &self,
+        py: Python,
+        filekey: PyRef<FileKeyReference>,
+        tgtfid: isize,
+    ) -> PyResult<Option<isize>> {
+        if filekey.fid == tgtfid {
+            // same file
+            return Ok(Some(filekey.ckey));
+        }
+        let Some(gckey) = self.get_gckey(py, filekey)? else {
             return Ok(None);
         };
-        let vid2gvid_fid = vid2gvid_fid.downcast::<PyDict>()?;
-        if let Some(gvid) = vid2gvid_fid.get_item(varref.vid)? {
-            Ok(Some(gvid.extract()?))
-        } else {
-            Ok(None)
+        let Some(ckeys) = self.gckey2ckey.get(&gckey) else {
+            chklogger(
+                py,
+                "warning",
+                format!("Global key {gckey} not found in converter"),
+            )?;
+            return Ok(None);
+        };
+        match ckeys.get(&tgtfid) {
+            Some(&ckey) => Ok(Some(ckey)),
+            None => {
+                chklogger(
+                    py,
+                    "warning",
+                    format!("Target fid {tgtfid} not found for global key {gckey}"),
+                )?;
+                Ok(None)
+            }
         }
     }
 }
@@ -3,16 +3,22 @@ use pyo3::prelude::*;
 mod assign_dictionary_record;
 pub mod c_application;
 mod c_attributes;
+mod c_cfg;
 mod c_comp_info;
 mod c_const;
 mod c_context;
 mod c_context_dictionary;
+pub mod c_data_model;
 mod c_declarations;
 mod c_dictionary;
 mod c_dictionary_record;
+mod c_dictionary_schema;
+mod c_dictionary_view;
 mod c_enum_info;
 mod c_enum_item;
 mod c_exp;
+mod c_exp_eval;
+mod c_exp_rewrite;
 mod c_field_info;
 mod c_file;
 mod c_file_assignment;
@@ -28,10 +34,14 @@ mod c_lhost;
 mod c_location;
 mod c_lval;
 mod c_offset;
+mod c_pretty;
 mod c_stmt;
 mod c_typ;
+mod c_typ_compat;
 mod c_type_info;
 mod c_var_info;
+mod c_visitor;
+mod global_symbol_table;
 mod index_manager;
 
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
@@ -39,13 +49,16 @@ pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     module.add_submodule(&assign_dictionary_record::module(py)?)?;
     module.add_submodule(&c_application::module(py)?)?;
     module.add_submodule(&c_attributes::module(py)?)?;
+    module.add_submodule(&c_cfg::module(py)?)?;
     module.add_submodule(&c_const::module(py)?)?;
     module.add_submodule(&c_context::module(py)?)?;
     module.add_submodule(&c_context_dictionary::module(py)?)?;
     module.add_submodule(&c_comp_info::module(py)?)?;
+    module.add_submodule(&c_data_model::module(py)?)?;
     module.add_submodule(&c_declarations::module(py)?)?;
     module.add_submodule(&c_dictionary::module(py)?)?;
     module.add_submodule(&c_dictionary_record::module(py)?)?;
+    module.add_submodule(&c_dictionary_view::module(py)?)?;
     module.add_submodule(&c_enum_info::module(py)?)?;
     module.add_submodule(&c_enum_item::module(py)?)?;
     module.add_submodule(&c_exp::module(py)?)?;
@@ -64,10 +77,12 @@ pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     module.add_submodule(&c_lval::module(py)?)?;
     module.add_submodule(&c_location::module(py)?)?;
     module.add_submodule(&c_offset::module(py)?)?;
+    module.add_submodule(&c_pretty::module(py)?)?;
     module.add_submodule(&c_stmt::module(py)?)?;
     module.add_submodule(&c_typ::module(py)?)?;
     module.add_submodule(&c_type_info::module(py)?)?;
     module.add_submodule(&c_var_info::module(py)?)?;
+    module.add_submodule(&global_symbol_table::module(py)?)?;
     module.add_submodule(&index_manager::module(py)?)?;
     Ok(module)
 }
@@ -30,7 +30,10 @@ SOFTWARE.
 */
 use pyo3::prelude::*;
 
-use crate::app::c_application::CApplication;
+use crate::app::{
+    c_application::CApplication,
+    c_data_model::{set_data_model, CDataModel},
+};
 
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "analysis_manager")?;
@@ -63,6 +66,9 @@ impl AnalysisManager {
     ///     thirdpartysummaries (string list): names of function summary jars
     ///     verbose (bool): display analyzer output (default True)
     ///     nofilter (bool): don't remove functions with absolute filename (default True)
+    ///
+    /// Sizes and layouts of types are computed in the data model of wordsize
+    /// from here on, if there is a predefined model for it.
     #[new]
     #[pyo3(signature = (capp, wordsize = 0, unreachability = false, thirdpartysummaries = vec![], nofilter = true, verbose = false))]
     fn new(
@@ -73,6 +79,9 @@ impl AnalysisManager {
         nofilter: bool,
         verbose: bool,
     ) -> AnalysisManager {
+        if let Some(model) = CDataModel::for_wordsize(wordsize) {
+            set_data_model(model);
+        }
         AnalysisManager {
             capp,
             wordsize,
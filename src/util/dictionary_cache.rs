@@ -0,0 +1,323 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Binary snapshots of the indexed tables read from dictionary xml files.
+//!
+//! A snapshot records the modification time, length, and hash of the xml file
+//! it was made from. It is used as long as either the modification time and
+//! length, or the hash, still match; otherwise the xml file is parsed again and
+//! the snapshot is replaced.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
+use pyo3::prelude::*;
+
+use crate::util::{
+    dictionary_xml::{self, XmlTables},
+    indexed_table::IndexedTableValue,
+};
+
+pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
+    let module = PyModule::new_bound(py, "dictionary_cache")?;
+    module.add_function(wrap_pyfunction!(read_xml_tables_cached, &module)?)?;
+    Ok(module)
+}
+
+const MAGIC: &[u8; 8] = b"chctbls\0";
+
+/// Bump whenever the layout below or the decoding of the tables changes;
+/// snapshots with another version are ignored.
+const VERSION: u32 = 3;
+
+/// Reads the indexed tables in the xml file at `path`, going through a
+/// snapshot in `cachedir` when it is up to date.
+///
+/// Failures to read or write the snapshot are not errors: the xml file is
+/// always the authoritative source.
+#[pyfunction]
+pub fn read_xml_tables_cached(path: PathBuf, cachedir: PathBuf) -> PyResult<XmlTables> {
+    let Ok(source) = SourceKey::of_file(&path) else {
+        return dictionary_xml::read_xml_tables(path);
+    };
+    let cachefile = cache_filename(&path, &cachedir);
+    let contents = match load(&cachefile, &path, &source) {
+        Snapshot::Current(tables) => return Ok(tables),
+        Snapshot::Touched(tables, hash) => {
+            // Re-stamp the snapshot so the next load does not hash again.
+            let _ = save(&cachefile, &SourceKey { hash, ..source }, &tables);
+            return Ok(tables);
+        }
+        Snapshot::Stale(Some(contents)) => contents,
+        Snapshot::Stale(None) => match fs::read(&path) {
+            Ok(contents) => contents,
+            Err(_) => return dictionary_xml::read_xml_tables(path),
+        },
+    };
+    // Parse and hash the same bytes, so the snapshot always matches the hash
+    // it is stored under, even if the file changes while it is being read.
+    let tables = dictionary_xml::read_xml_tables_from_bytes(&contents, &path)?;
+    let source = SourceKey {
+        hash: fnv1a(&contents),
+        ..source
+    };
+    let _ = save(&cachefile, &source, &tables);
+    Ok(tables)
+}
+
+/// Snapshot name: the xml file name, prefixed with the hash of its full path
+/// to keep files with the same name in different directories apart.
+fn cache_filename(path: &Path, cachedir: &Path) -> PathBuf {
+    let fullpath = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let pathhash = fnv1a(fullpath.to_string_lossy().as_bytes());
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    cachedir.join(format!("{pathhash:016x}_{name}.bin"))
+}
+
+/// 64-bit FNV-1a.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |h, b| {
+        (h ^ u64::from(*b)).wrapping_mul(0x100000001b3)
+    })
+}
+
+struct SourceKey {
+    mtime_secs: u64,
+    mtime_nanos: u32,
+    length: u64,
+    /// Only computed when the snapshot is written or mtime/length differ.
+    hash: u64,
+}
+
+impl SourceKey {
+    fn of_file(path: &Path) -> std::io::Result<SourceKey> {
+        let metadata = fs::metadata(path)?;
+        let mtime = metadata
+            .modified()?
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        Ok(SourceKey {
+            mtime_secs: mtime.as_secs(),
+            mtime_nanos: mtime.subsec_nanos(),
+            length: metadata.len(),
+            hash: 0,
+        })
+    }
+
+    fn same_mtime(&self, other: &SourceKey) -> bool {
+        self.mtime_secs == other.mtime_secs
+            && self.mtime_nanos == other.mtime_nanos
+            && self.length == other.length
+    }
+}
+
+enum Snapshot {
+    /// Modification time and length match.
+    Current(XmlTables),
+    /// Modification time or length differ, but the hash of the contents,
+    /// given here, matches.
+    Touched(XmlTables, u64),
+    /// Missing, unreadable, or out of date; holds the contents of the xml file
+    /// if they were read to compare hashes.
+    Stale(Option<Vec<u8>>),
+}
+
+fn load(cachefile: &Path, path: &Path, source: &SourceKey) -> Snapshot {
+    let Ok(bytes) = fs::read(cachefile) else {
+        return Snapshot::Stale(None);
+    };
+    let mut r = Decoder { bytes: &bytes };
+    let Some(cached) = decode_header(&mut r) else {
+        return Snapshot::Stale(None);
+    };
+    if cached.same_mtime(source) {
+        return match decode_tables(&mut r) {
+            Some(tables) => Snapshot::Current(tables),
+            None => Snapshot::Stale(None),
+        };
+    }
+    let Ok(contents) = fs::read(path) else {
+        return Snapshot::Stale(None);
+    };
+    let hash = fnv1a(&contents);
+    if cached.hash != hash {
+        return Snapshot::Stale(Some(contents));
+    }
+    match decode_tables(&mut r) {
+        Some(tables) => Snapshot::Touched(tables, hash),
+        None => Snapshot::Stale(Some(contents)),
+    }
+}
+
+fn decode_header(r: &mut Decoder) -> Option<SourceKey> {
+    if r.take(MAGIC.len())? != MAGIC || r.u32()? != VERSION {
+        return None;
+    }
+    Some(SourceKey {
+        mtime_secs: r.u64()?,
+        mtime_nanos: r.u32()?,
+        length: r.u64()?,
+        hash: r.u64()?,
+    })
+}
+
+fn save(cachefile: &Path, source: &SourceKey, tables: &XmlTables) -> std::io::Result<()> {
+    let mut w = Vec::new();
+    w.extend_from_slice(MAGIC);
+    w.extend_from_slice(&VERSION.to_le_bytes());
+    w.extend_from_slice(&source.mtime_secs.to_le_bytes());
+    w.extend_from_slice(&source.mtime_nanos.to_le_bytes());
+    w.extend_from_slice(&source.length.to_le_bytes());
+    w.extend_from_slice(&source.hash.to_le_bytes());
+    encode_tables(&mut w, tables);
+    if let Some(dir) = cachefile.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    // Write to a temporary file first so a concurrent reader never sees a
+    // partial snapshot.
+    let tmpfile = cachefile.with_extension(format!("tmp{}", std::process::id()));
+    fs::File::create(&tmpfile)?.write_all(&w)?;
+    fs::rename(&tmpfile, cachefile)
+}
+
+fn put_len(w: &mut Vec<u8>, n: usize) {
+    w.extend_from_slice(&(n as u64).to_le_bytes());
+}
+
+fn put_isize(w: &mut Vec<u8>, n: isize) {
+    w.extend_from_slice(&(n as i64).to_le_bytes());
+}
+
+fn put_str(w: &mut Vec<u8>, s: &str) {
+    put_len(w, s.len());
+    w.extend_from_slice(s.as_bytes());
+}
+
+fn encode_tables(w: &mut Vec<u8>, tables: &XmlTables) {
+    put_len(w, tables.tables.len());
+    for (name, values) in tables.tables.iter() {
+        put_str(w, name);
+        put_len(w, values.len());
+        for value in values {
+            put_isize(w, value.index());
+            put_len(w, value.tags().len());
+            for tag in value.tags() {
+                put_str(w, tag);
+            }
+            put_len(w, value.args().len());
+            for arg in value.args() {
+                put_isize(w, *arg);
+            }
+        }
+    }
+    put_len(w, tables.string_tables.len());
+    for (name, entries) in tables.string_tables.iter() {
+        put_str(w, name);
+        put_len(w, entries.len());
+        for (index, s) in entries {
+            put_isize(w, *index);
+            put_str(w, s);
+        }
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if n > self.bytes.len() {
+            return None;
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Some(head)
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn len(&mut self) -> Option<usize> {
+        usize::try_from(self.u64()?).ok()
+    }
+
+    fn isize(&mut self) -> Option<isize> {
+        isize::try_from(i64::from_le_bytes(self.take(8)?.try_into().ok()?)).ok()
+    }
+
+    fn string(&mut self) -> Option<String> {
+        let n = self.len()?;
+        String::from_utf8(self.take(n)?.to_vec()).ok()
+    }
+}
+
+fn decode_tables(r: &mut Decoder) -> Option<XmlTables> {
+    let mut result = XmlTables::default();
+    for _ in 0..r.len()? {
+        let name = r.string()?;
+        let count = r.len()?;
+        let mut values = Vec::with_capacity(count.min(r.bytes.len()));
+        for _ in 0..count {
+            let index = r.isize()?;
+            let tags = (0..r.len()?)
+                .map(|_| r.string())
+                .collect::<Option<Vec<_>>>()?;
+            let args = (0..r.len()?)
+                .map(|_| r.isize())
+                .collect::<Option<Vec<_>>>()?;
+            values.push(IndexedTableValue::new(index, tags, args));
+        }
+        result.tables.insert(name, values);
+    }
+    let mut string_tables = BTreeMap::new();
+    for _ in 0..r.len()? {
+        let name = r.string()?;
+        let entries = (0..r.len()?)
+            .map(|_| Some((r.isize()?, r.string()?)))
+            .collect::<Option<Vec<_>>>()?;
+        string_tables.insert(name, entries);
+    }
+    result.string_tables = string_tables;
+    r.bytes.is_empty().then_some(result)
+}
@@ -0,0 +1,377 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Reading and writing of the indexed tables in dictionary files without
+//! ElementTree.
+
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    io::BufRead,
+    path::{Path, PathBuf},
+};
+
+use itertools::Itertools;
+use pyo3::prelude::*;
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+
+use crate::util::indexed_table::{IndexedTableError, IndexedTableValue};
+
+pyo3::import_exception!(chc.util.fileutil, CHCFileNotFoundError);
+
+pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
+    let module = PyModule::new_bound(py, "dictionary_xml")?;
+    module.add_class::<XmlTables>()?;
+    module.add_function(wrap_pyfunction!(read_xml_tables, &module)?)?;
+    Ok(module)
+}
+
+/// All indexed tables found in one xml file, keyed by table name.
+///
+/// A table is any element whose children are `<n>` entries with an `ix`
+/// attribute, e.g. `<typ-table><n t="tint,iint" a="1" ix="3"/></typ-table>`,
+/// and any element named `*-table`, which may be empty. Entries with a `v`
+/// attribute (string-table, filename-table) are kept as decoded strings; an
+/// empty table is both an empty indexed table and an empty string table.
+#[pyclass(frozen)]
+#[derive(Clone, Default)]
+pub struct XmlTables {
+    pub(crate) tables: BTreeMap<String, Vec<IndexedTableValue>>,
+    pub(crate) string_tables: BTreeMap<String, Vec<(isize, String)>>,
+}
+
+#[pymethods]
+impl XmlTables {
+    #[getter]
+    fn table_names(&self) -> Vec<String> {
+        self.tables
+            .keys()
+            .chain(self.string_tables.keys())
+            .cloned()
+            .collect()
+    }
+
+    fn has_table(&self, name: &str) -> bool {
+        self.tables.contains_key(name) || self.string_tables.contains_key(name)
+    }
+
+    #[pyo3(name = "table")]
+    fn py_table(&self, name: &str) -> Option<Vec<IndexedTableValue>> {
+        self.tables.get(name).cloned()
+    }
+
+    #[pyo3(name = "string_table")]
+    fn py_string_table(&self, name: &str) -> Option<Vec<(isize, String)>> {
+        self.string_table(name).map(|t| t.to_vec())
+    }
+}
+
+impl XmlTables {
+    pub fn table(&self, name: &str) -> Option<&[IndexedTableValue]> {
+        self.tables.get(name).map(|t| &t[..])
+    }
+
+    pub fn take_table(&mut self, name: &str) -> Option<Vec<IndexedTableValue>> {
+        self.tables.remove(name)
+    }
+
+    pub fn string_table(&self, name: &str) -> Option<&[(isize, String)]> {
+        match self.string_tables.get(name) {
+            Some(t) => Some(&t[..]),
+            None => self
+                .tables
+                .get(name)
+                .filter(|t| t.is_empty())
+                .map(|_| &[][..]),
+        }
+    }
+
+    /// Records an element that is a table by its name, whether or not it has
+    /// entries.
+    fn open_element(&mut self, name: &str) {
+        if name.ends_with("-table") && !self.string_tables.contains_key(name) {
+            self.tables.entry(name.to_string()).or_default();
+        }
+    }
+}
+
+/// Reads every indexed table in the xml file at `path`.
+#[pyfunction]
+pub fn read_xml_tables(path: PathBuf) -> PyResult<XmlTables> {
+    if !path.is_file() {
+        return Err(CHCFileNotFoundError::new_err(
+            path.to_string_lossy().to_string(),
+        ));
+    }
+    let reader = Reader::from_file(&path).map_err(|e| xml_error(&path, 0, e))?;
+    read_tables(reader, &path)
+}
+
+/// Reads every indexed table in `contents`, the contents of the xml file at
+/// `path`; `path` is only used in error messages.
+pub fn read_xml_tables_from_bytes(contents: &[u8], path: &Path) -> PyResult<XmlTables> {
+    read_tables(Reader::from_reader(contents), path)
+}
+
+fn xml_error(path: &Path, position: u64, e: impl std::fmt::Display) -> PyErr {
+    IndexedTableError::new_err(format!(
+        "Error reading {} at byte {position}: {e}",
+        path.display()
+    ))
+}
+
+fn read_tables<R: BufRead>(mut reader: Reader<R>, path: &Path) -> PyResult<XmlTables> {
+    let mut result = XmlTables::default();
+    // Names of the currently open elements; the last one is the parent of
+    // any `<n>` entry encountered.
+    let mut open: Vec<String> = Vec::new();
+    let mut buf = Vec::new();
+    loop {
+        let position = reader.buffer_position();
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| xml_error(path, position, e))?;
+        match event {
+            Event::Start(e) => {
+                if e.name().as_ref() == b"n" {
+                    read_entry(&mut result, open.last(), &e, path, position)?;
+                }
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                result.open_element(&name);
+                open.push(name);
+            }
+            Event::Empty(e) if e.name().as_ref() == b"n" => {
+                read_entry(&mut result, open.last(), &e, path, position)?;
+            }
+            Event::Empty(e) => {
+                result.open_element(&String::from_utf8_lossy(e.name().as_ref()));
+            }
+            Event::End(_) => {
+                open.pop();
+            }
+            Event::Eof => break,
+            _ => (),
+        }
+        buf.clear();
+    }
+    Ok(result)
+}
+
+fn read_entry(
+    result: &mut XmlTables,
+    table: Option<&String>,
+    node: &BytesStart,
+    path: &Path,
+    position: u64,
+) -> PyResult<()> {
+    let Some(table) = table else {
+        return Ok(());
+    };
+    let mut tags = None;
+    let mut args = None;
+    let mut index = None;
+    let mut value = None;
+    let mut ishex = false;
+    for attr in node.attributes() {
+        let attr = attr.map_err(|e| xml_error(path, position, e))?;
+        let text = attr
+            .unescape_value()
+            .map_err(|e| xml_error(path, position, e))?;
+        match attr.key.as_ref() {
+            b"t" => tags = Some(text.into_owned()),
+            b"a" => args = Some(text.into_owned()),
+            b"ix" => index = Some(text.into_owned()),
+            b"v" => value = Some(text.into_owned()),
+            b"hex" => ishex = text == "yes",
+            _ => (),
+        }
+    }
+    let Some(index) = index else {
+        // Not a table entry, e.g. an `<n>` element in a ppo diagnostic.
+        return Ok(());
+    };
+    let index: isize = index.parse()?;
+    if let Some(value) = value {
+        let s = if ishex {
+            dehexstring(&value)
+                .ok_or_else(|| xml_error(path, position, format!("invalid hex string {value}")))?
+        } else {
+            value
+        };
+        if result.tables.get(table).is_some_and(|t| t.is_empty()) {
+            result.tables.remove(table);
+        }
+        result
+            .string_tables
+            .entry(table.clone())
+            .or_default()
+            .push((index, s));
+        return Ok(());
+    }
+    let taglist = match tags {
+        Some(tags) => tags.split(',').map(|x| x.to_string()).collect(),
+        None => Vec::new(),
+    };
+    let arglist = match args {
+        Some(args) if !args.is_empty() => args
+            .split(',')
+            .map(|x| Ok(x.parse::<isize>()?))
+            .collect::<PyResult<Vec<isize>>>()?,
+        _ => Vec::new(),
+    };
+    result
+        .tables
+        .entry(table.clone())
+        .or_default()
+        .push(IndexedTableValue::new(index, taglist, arglist));
+    Ok(())
+}
+
+/// Inverse of `hexstring`: one char per pair of hex digits, the byte read as
+/// a latin-1 char, as StringIndexedTable does.
+///
+/// String literals may hold bytes that are not valid UTF-8 (e.g., `\xff`
+/// escapes written by the parser), so the bytes are not decoded as UTF-8.
+fn dehexstring(h: &str) -> Option<String> {
+    (0..h.len() / 2)
+        .map(|i| {
+            h.get(2 * i..2 * i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+                .map(char::from)
+        })
+        .collect()
+}
+
+/// Hexstring encoding used for strings with control characters: two hex
+/// digits per char, as StringIndexedTable does; the inverse of `dehexstring`
+/// for strings of latin-1 chars.
+fn hexstring(s: &str) -> String {
+    s.chars().fold(String::new(), |mut acc, c| {
+        let _ = write!(acc, "{:02x}", c as u32);
+        acc
+    })
+}
+
+/// Same escapes as `xmlutil.sanitize`.
+fn sanitize(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn has_control_characters(s: &str) -> bool {
+    s.chars().any(|c| (c as u32) < 32 || (c as u32) > 126)
+}
+
+/// Writes one `<n>` element; `attrs` must be sorted by key, as in
+/// `xmlutil.attributes_to_pretty`.
+fn write_entry(out: &mut String, attrs: &[(&str, &str)], indent: usize) {
+    let _ = write!(out, "{:indent$}<n", "");
+    for (key, value) in attrs {
+        let _ = write!(out, " {key}=\"{}\"", sanitize(value));
+    }
+    out.push_str("/>\n");
+}
+
+/// Writes the table `name` with entries produced by `f` in the layout of
+/// `xmlutil.doc_to_pretty`: two spaces of indentation per level, and an empty
+/// table as a single self-closing element.
+fn write_table_with(
+    out: &mut String,
+    name: &str,
+    indent: usize,
+    is_empty: bool,
+    f: impl FnOnce(&mut String),
+) {
+    if is_empty {
+        let _ = writeln!(out, "{:indent$}<{name}/>", "");
+    } else {
+        let _ = writeln!(out, "{:indent$}<{name}>", "");
+        f(out);
+        let _ = writeln!(out, "{:indent$}</{name}>", "");
+    }
+}
+
+/// Writes `values` as the indexed table `name`, the inverse of `read_xml_tables`.
+pub fn write_table<'a>(
+    out: &mut String,
+    name: &str,
+    values: impl Iterator<Item = &'a IndexedTableValue>,
+    indent: usize,
+) {
+    let mut values = values.peekable();
+    let is_empty = values.peek().is_none();
+    write_table_with(out, name, indent, is_empty, |out| {
+        for value in values {
+            let tagstr = value.tags().join(",");
+            let argstr = value.args().iter().join(",");
+            let index = value.index().to_string();
+            let mut attrs = Vec::with_capacity(3);
+            if !argstr.is_empty() {
+                attrs.push(("a", argstr.as_str()));
+            }
+            attrs.push(("ix", index.as_str()));
+            if !tagstr.is_empty() {
+                attrs.push(("t", tagstr.as_str()));
+            }
+            write_entry(out, &attrs, indent + 2);
+        }
+    })
+}
+
+/// Writes `entries` as the string table `name`; strings with control
+/// characters are hex-encoded.
+pub fn write_string_table(
+    out: &mut String,
+    name: &str,
+    entries: &[(isize, String)],
+    indent: usize,
+) {
+    write_table_with(out, name, indent, entries.is_empty(), |out| {
+        for (index, s) in entries {
+            let index = index.to_string();
+            if has_control_characters(s) {
+                let encoded = hexstring(s);
+                write_entry(
+                    out,
+                    &[("hex", "yes"), ("ix", &index), ("v", &encoded)],
+                    indent + 2,
+                );
+            } else {
+                write_entry(out, &[("ix", &index), ("v", s)], indent + 2);
+            }
+        }
+    })
+}
@@ -0,0 +1,141 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Fingerprints of the analysis results of a c file.
+//!
+//! A fingerprint records the modification time, length and hash of every xml
+//! file under a results directory. A file whose modification time changed is
+//! hashed again, and counts as changed only if its length or hash differs
+//! from the previous fingerprint. A fingerprint taken without hashes, which
+//! only needs the file metadata, counts every such file as changed.
+
+use std::{
+    collections::BTreeMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::util::dictionary_cache::fnv1a;
+
+#[derive(Clone, Copy)]
+struct Stamp {
+    modified: SystemTime,
+    length: u64,
+    hash: Option<u64>,
+}
+
+pub struct Fingerprint {
+    files: BTreeMap<PathBuf, Stamp>,
+}
+
+impl Fingerprint {
+    /// Fingerprint of the xml files under `dir`.
+    pub fn of_dir(dir: &Path) -> io::Result<Fingerprint> {
+        Self::read_dir(dir, true)
+    }
+
+    /// Fingerprint of the xml files under `dir` without their hashes.
+    pub fn of_dir_unhashed(dir: &Path) -> io::Result<Fingerprint> {
+        Self::read_dir(dir, false)
+    }
+
+    fn read_dir(dir: &Path, hashed: bool) -> io::Result<Fingerprint> {
+        let mut files = BTreeMap::new();
+        for path in xml_files(dir)? {
+            let metadata = fs::metadata(&path)?;
+            let hash = if hashed {
+                Some(fnv1a(&fs::read(&path)?))
+            } else {
+                None
+            };
+            let stamp = Stamp {
+                modified: metadata.modified()?,
+                length: metadata.len(),
+                hash,
+            };
+            files.insert(path, stamp);
+        }
+        Ok(Fingerprint { files })
+    }
+
+    /// Fingerprint of the xml files under `dir` now, and whether any of them
+    /// was added, removed or changed since this fingerprint.
+    pub fn refresh(&self, dir: &Path) -> io::Result<(Fingerprint, bool)> {
+        let mut files = BTreeMap::new();
+        let mut changed = false;
+        for path in xml_files(dir)? {
+            let metadata = fs::metadata(&path)?;
+            let modified = metadata.modified()?;
+            let length = metadata.len();
+            let stamp = match self.files.get(&path) {
+                Some(old) if old.modified == modified && old.length == length => *old,
+                old => {
+                    let hash = Some(fnv1a(&fs::read(&path)?));
+                    changed |= !old.is_some_and(|old| {
+                        old.length == length && old.hash.is_some() && old.hash == hash
+                    });
+                    Stamp {
+                        modified,
+                        length,
+                        hash,
+                    }
+                }
+            };
+            files.insert(path, stamp);
+        }
+        changed |= self.files.keys().any(|path| !files.contains_key(path));
+        Ok((Fingerprint { files }, changed))
+    }
+}
+
+/// Xml files under `dir` and its subdirectories; none if `dir` does not
+/// exist.
+fn xml_files(dir: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut result = Vec::new();
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(dir) = pending.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+            Err(e) => return Err(e),
+        };
+        for entry in entries {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                pending.push(path);
+            } else if path.extension().is_some_and(|ext| ext == "xml") {
+                result.push(path);
+            }
+        }
+    }
+    Ok(result)
+}
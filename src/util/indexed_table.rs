@@ -28,19 +28,31 @@ OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
 SOFTWARE.
 ------------------------------------------------------------------------------
 */
-use std::{borrow::Cow, collections::BTreeMap};
+use std::{
+    borrow::Cow,
+    collections::BTreeMap,
+    fmt::Write,
+    sync::atomic::{AtomicU64, Ordering},
+};
 
 use itertools::Itertools;
 use pyo3::{
     exceptions::{PyException, PyValueError},
     intern,
     prelude::*,
-    types::PyFunction,
+    types::{PyFunction, PyString},
+};
+
+use crate::util::{
+    dictionary_xml::XmlTables,
+    table_check::IntegrityReport,
+    table_diff::TableDiff,
+    table_key::{KeyParts, KeyTable, KeyView, TableKey},
 };
 
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "indexed_table")?;
-    module.add_class::<IndexedTable>()?;
+    module.add_class::<PyIndexedTable>()?;
     module.add_class::<IndexedTableValue>()?;
     module.add_function(wrap_pyfunction!(get_key, &module)?)?;
     module.add_function(wrap_pyfunction!(get_rep, &module)?)?;
@@ -82,10 +94,31 @@ fn get_rep(node: &Bound<PyAny>, /* ET.Element */) -> PyResult<(isize, Vec<String
     Ok((index, taglist, arglist))
 }
 
-// TODO: use python types to avoid allocating a String for every tag
+/// The (tags, args) strings python uses as key.
+///
+/// The tags are read in place from the python strings, so only the two joined
+/// strings are allocated.
 #[pyfunction]
-fn get_key(tags: Vec<String>, args: Vec<isize>) -> (String, String) {
-    (tags.iter().join(","), args.iter().join(","))
+fn get_key(tags: &Bound<PyAny>, args: &Bound<PyAny>) -> PyResult<(String, String)> {
+    let mut tagstr = String::new();
+    for (i, tag) in tags.iter()?.enumerate() {
+        if i > 0 {
+            tagstr.push(',');
+        }
+        tagstr.push_str(tag?.downcast::<PyString>()?.to_str()?);
+    }
+    let mut argstr = String::new();
+    for (i, arg) in args.iter()?.enumerate() {
+        if i > 0 {
+            argstr.push(',');
+        }
+        let _ = write!(argstr, "{}", arg?.extract::<isize>()?);
+    }
+    Ok((tagstr, argstr))
+}
+
+fn join_key(tags: &[String], args: &[isize]) -> (String, String) {
+    (tags.join(","), args.iter().join(","))
 }
 
 #[derive(Clone)]
@@ -102,13 +135,13 @@ pub struct IndexedTableValue {
 #[pymethods]
 impl IndexedTableValue {
     #[new]
-    fn new(index: isize, tags: Vec<String>, args: Vec<isize>) -> IndexedTableValue {
+    pub fn new(index: isize, tags: Vec<String>, args: Vec<isize>) -> IndexedTableValue {
         IndexedTableValue { index, tags, args }
     }
 
     #[getter]
     fn key(&self) -> (String, String) {
-        get_key(self.tags.clone(), self.args.clone())
+        join_key(&self.tags, &self.args)
     }
 
     fn check_key(&self, reqtagcount: usize, reqargcount: usize, name: String) -> PyResult<()> {
@@ -168,6 +201,38 @@ impl IndexedTableValue {
     pub fn index(&self) -> isize {
         self.index
     }
+
+    /// Argument `n`, or IndexedTableError if the record is too short.
+    pub fn arg(&self, n: usize) -> PyResult<isize> {
+        self.args
+            .get(n)
+            .copied()
+            .ok_or_else(|| self.too_short("argument", n))
+    }
+
+    /// Arguments from `n` on, or IndexedTableError if the record has fewer
+    /// than `n` arguments.
+    pub fn args_from(&self, n: usize) -> PyResult<&[isize]> {
+        self.args
+            .get(n..)
+            .ok_or_else(|| self.too_short("argument", n))
+    }
+
+    /// Tag `n`, or IndexedTableError if the record is too short.
+    pub fn tag(&self, n: usize) -> PyResult<&str> {
+        self.tags
+            .get(n)
+            .map(|tag| tag.as_str())
+            .ok_or_else(|| self.too_short("tag", n))
+    }
+
+    pub(crate) fn too_short(&self, kind: &str, n: usize) -> PyErr {
+        IndexedTableError::new_err(format!(
+            "Record {} ({}) has no {kind} {n}",
+            self.index,
+            self.tags.join(",")
+        ))
+    }
 }
 
 pub trait IndexedTableValueTrait {
@@ -283,107 +348,536 @@ fn element_tree_element<'a, 'py>(py: Python<'py>, tag: &'a str) -> PyResult<Boun
 
 /// Table to provide unique indices to objects represented by a key string.
 ///
-/// The table can be checkpointed and reset to that checkpoint with
-/// - set_checkpoint
-/// - reset_to_checkpoint
+/// Additions can be made tentatively with a stack of named checkpoints:
+/// - begin: push a checkpoint
+/// - commit: keep everything added since the checkpoint and pop it
+/// - rollback: undo every change made since the checkpoint, including keys
+///   mapped to another index and reservations committed since, and pop it
+///
+/// Checkpoints nest; committing or rolling back a checkpoint also pops the
+/// checkpoints begun after it. The older single checkpoint (set_checkpoint,
+/// reset_to_checkpoint, remove_checkpoint) is an unnamed checkpoint on the
+/// same stack, so it cannot collide with a named one.
+///
+/// This is the Rust-side table: it never calls into python, so it can be filled
+/// and queried without holding the GIL. Python sees it through `PyIndexedTable`.
 ///
 /// Note: the string encodings use the comma as a concatenation character, hence
 ///       the comma character cannot be used in any string representation.
-#[pyclass(subclass)]
-pub struct IndexedTable {
-    #[pyo3(get)]
+#[derive(Clone)]
+pub struct IndexedTable<T> {
     name: String,
-    keytable: BTreeMap<(String, String), isize>,
-    indextable: BTreeMap<isize, Py<IndexedTableValue>>,
+    keytable: KeyTable,
+    indextable: BTreeMap<isize, T>,
     next: isize,
     reserved: Vec<isize>,
-    checkpoint: Option<isize>,
+    checkpoints: Vec<Checkpoint>,
+    /// Changes made while a checkpoint is open, oldest first.
+    undo: Vec<Undo<T>>,
+    /// Changes whenever a value is added, replaced or removed. Versions come
+    /// from one counter, so a new table does not repeat the version of a
+    /// table it replaces.
+    version: u64,
 }
 
-#[pymethods]
-impl IndexedTable {
-    #[new]
+/// Source of the versions of all tables.
+static NEXT_VERSION: AtomicU64 = AtomicU64::new(1);
+
+fn next_version() -> u64 {
+    NEXT_VERSION.fetch_add(1, Ordering::Relaxed)
+}
+
+/// State of an `IndexedTable` at the start of a checkpoint.
+#[derive(Clone)]
+struct Checkpoint {
+    /// None for the checkpoint of set_checkpoint.
+    name: Option<String>,
+    next: isize,
+    reserved: Vec<isize>,
+    /// Length of the undo log when the checkpoint was begun.
+    undo: usize,
+}
+
+/// A change to an `IndexedTable`, with what it replaced.
+#[derive(Clone)]
+enum Undo<T> {
+    Key(TableKey, Option<isize>),
+    Value(isize, Option<T>),
+}
+
+impl<T> IndexedTable<T> {
     pub fn new(name: String) -> Self {
         IndexedTable {
             name,
-            keytable: BTreeMap::new(),
+            keytable: KeyTable::default(),
             indextable: BTreeMap::new(),
             next: 1,
             reserved: Vec::new(),
-            checkpoint: None,
+            checkpoints: Vec::new(),
+            undo: Vec::new(),
+            version: next_version(),
         }
     }
 
-    fn size(&self) -> isize {
+    pub fn name(&self) -> &str {
+        self.name.as_str()
+    }
+
+    pub fn size(&self) -> isize {
         self.next - 1
     }
 
-    fn reset(&mut self) -> PyResult<()> {
+    pub fn next(&self) -> isize {
+        self.next
+    }
+
+    pub fn reserved(&self) -> &[isize] {
+        &self.reserved[..]
+    }
+
+    /// Version of the values in the table; data derived from the table is
+    /// stale once this changes.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    pub fn checkpoint(&self) -> Option<isize> {
+        self.checkpoints
+            .iter()
+            .find(|cp| cp.name.is_none())
+            .map(|cp| cp.next)
+    }
+
+    /// Names of the open named checkpoints, outermost first.
+    pub fn checkpoints(&self) -> impl Iterator<Item = &str> {
+        self.checkpoints.iter().filter_map(|cp| cp.name.as_deref())
+    }
+
+    pub fn reset(&mut self) {
         self.keytable.clear();
         self.indextable.clear();
         self.next = 1;
         self.reserved.clear();
-        self.checkpoint = None;
+        self.checkpoints.clear();
+        self.undo.clear();
+        self.version = next_version();
+    }
+
+    fn push_checkpoint(&mut self, name: Option<&str>) -> isize {
+        self.checkpoints.push(Checkpoint {
+            name: name.map(|n| n.to_string()),
+            next: self.next,
+            reserved: self.reserved.clone(),
+            undo: self.undo.len(),
+        });
+        self.next
+    }
+
+    /// Pushes a checkpoint named `name`; returns the first index that will be
+    /// assigned after it.
+    pub fn begin(&mut self, name: &str) -> PyResult<isize> {
+        if let Some(cp) = self
+            .checkpoints
+            .iter()
+            .find(|cp| cp.name.as_deref() == Some(name))
+        {
+            return Err(IndexedTableError::new_err(format!(
+                "Checkpoint {name} has already been set at {} in {}",
+                cp.next, self.name
+            )));
+        }
+        Ok(self.push_checkpoint(Some(name)))
+    }
+
+    /// Pops checkpoint `name` and all checkpoints begun after it.
+    fn pop_checkpoint(&mut self, name: Option<&str>) -> PyResult<Checkpoint> {
+        self.checkpoints
+            .iter()
+            .rposition(|cp| cp.name.as_deref() == name)
+            .and_then(|position| self.checkpoints.drain(position..).next())
+            .ok_or_else(|| {
+                IndexedTableError::new_err(format!(
+                    "No checkpoint {} in {}",
+                    name.unwrap_or("(unnamed)"),
+                    self.name
+                ))
+            })
+    }
+
+    fn commit_checkpoint(&mut self, name: Option<&str>) -> PyResult<()> {
+        self.pop_checkpoint(name)?;
+        if self.checkpoints.is_empty() {
+            self.undo.clear();
+        }
         Ok(())
     }
 
-    fn set_checkpoint(&mut self) -> PyResult<isize> {
-        if let Some(n) = self.checkpoint {
+    fn rollback_checkpoint(&mut self, name: Option<&str>) -> PyResult<isize> {
+        let cp = self.pop_checkpoint(name)?;
+        for undo in self.undo.drain(cp.undo..).rev() {
+            match undo {
+                Undo::Key(key, Some(index)) => {
+                    self.keytable.insert(&key, index);
+                }
+                Undo::Key(key, None) => {
+                    self.keytable.remove(&key);
+                }
+                Undo::Value(index, Some(obj)) => {
+                    self.indextable.insert(index, obj);
+                }
+                Undo::Value(index, None) => {
+                    self.indextable.remove(&index);
+                }
+            }
+        }
+        self.reserved = cp.reserved;
+        self.next = cp.next;
+        self.version = next_version();
+        Ok(cp.next)
+    }
+
+    /// Keeps all entries added since checkpoint `name` and pops it.
+    pub fn commit(&mut self, name: &str) -> PyResult<()> {
+        self.commit_checkpoint(Some(name))
+    }
+
+    /// Undoes all changes made since checkpoint `name` and pops it; returns
+    /// the index the table continues from.
+    ///
+    /// Indices that were reserved at the checkpoint and committed after it
+    /// are reserved again.
+    pub fn rollback(&mut self, name: &str) -> PyResult<isize> {
+        self.rollback_checkpoint(Some(name))
+    }
+
+    pub fn set_checkpoint(&mut self) -> PyResult<isize> {
+        if let Some(n) = self.checkpoint() {
             let message = format!("Checkpoint has already been set at {n}");
             Err(IndexedTableError::new_err(message))
         } else {
-            self.checkpoint = Some(self.next);
-            Ok(self.next)
+            Ok(self.push_checkpoint(None))
+        }
+    }
+
+    /// Remove all entries added since the checkpoint was set.
+    pub fn reset_to_checkpoint(&mut self) -> PyResult<isize> {
+        if self.checkpoint().is_none() {
+            return Err(PyValueError::new_err(
+                "Cannot reset non-existent checkpoint",
+            ));
+        }
+        self.rollback_checkpoint(None)
+    }
+
+    pub fn remove_checkpoint(&mut self) {
+        let _ = self.commit_checkpoint(None);
+    }
+
+    /// Returns the index of `key`, or None if the key has not been added.
+    pub fn get_index(&self, key: &(String, String)) -> Option<isize> {
+        self.keytable.get(key)
+    }
+
+    /// Returns the index of the key with `tags` and `args`, or None if the key
+    /// has not been added.
+    pub fn get_index_tags_args<S: AsRef<str>>(&self, tags: &[S], args: &[isize]) -> Option<isize> {
+        self.keytable.get(&KeyParts(tags, args))
+    }
+
+    fn insert_key<K: KeyView>(&mut self, key: &K, index: isize) {
+        let previous = self.keytable.get(key);
+        self.keytable.insert(key, index);
+        if !self.checkpoints.is_empty() && previous != Some(index) {
+            if let Some(key) = self.keytable.get_key(key) {
+                self.undo.push(Undo::Key(key.clone(), previous));
+            }
+        }
+    }
+
+    fn add_key<K: KeyView, F>(&mut self, key: &K, f: F) -> PyResult<isize>
+    where
+        F: FnOnce(isize) -> PyResult<T>,
+    {
+        if let Some(index) = self.keytable.get(key) {
+            return Ok(index);
+        }
+        let index = self.next;
+        let obj = f(index)?;
+        self.insert_key(key, index);
+        self.insert_value_at(index, obj);
+        self.next += 1;
+        Ok(index)
+    }
+
+    /// Stores `obj` at `index`, recording what it replaces while a checkpoint
+    /// is open.
+    fn insert_value_at(&mut self, index: isize, obj: T) {
+        let previous = self.indextable.insert(index, obj);
+        if !self.checkpoints.is_empty() {
+            self.undo.push(Undo::Value(index, previous));
+        }
+        self.version = next_version();
+    }
+
+    /// Returns the index of `key`, creating the value with `f` if it is new.
+    ///
+    /// `f` receives the index the new value will be stored under.
+    pub fn add<F>(&mut self, key: (String, String), f: F) -> PyResult<isize>
+    where
+        F: FnOnce(isize) -> PyResult<T>,
+    {
+        self.add_key(&key, f)
+    }
+
+    pub fn add_tags_args<S, F>(&mut self, tags: &[S], args: &[isize], f: F) -> PyResult<isize>
+    where
+        S: AsRef<str>,
+        F: FnOnce(isize) -> PyResult<T>,
+    {
+        self.add_key(&KeyParts(tags, args), f)
+    }
+
+    pub fn reserve(&mut self) -> isize {
+        let index = self.next;
+        self.reserved.push(index);
+        self.next += 1;
+        index
+    }
+
+    pub fn commit_reserved(&mut self, index: isize, key: (String, String), obj: T) -> PyResult<()> {
+        let Some(position) = self.reserved.iter().position(|x| *x == index) else {
+            return Err(IndexedTableError::new_err(format!(
+                "Trying to commit nonexisting index: {index}"
+            )));
+        };
+        self.insert_key(&key, index);
+        self.insert_value_at(index, obj);
+        self.reserved.remove(position);
+        Ok(())
+    }
+
+    fn insert_at<K: KeyView>(&mut self, key: &K, index: isize, obj: T) -> PyResult<()> {
+        self.insert_key(key, index);
+        self.insert_value_at(index, obj);
+        if index >= self.next {
+            self.next = index + 1
+        }
+        Ok(())
+    }
+
+    /// Stores `obj` under an index that was assigned elsewhere, e.g. read from
+    /// a dictionary file.
+    pub fn insert(&mut self, key: (String, String), index: isize, obj: T) -> PyResult<()> {
+        self.insert_at(&key, index, obj)
+    }
+
+    pub fn get(&self, index: isize) -> Option<&T> {
+        self.indextable.get(&index)
+    }
+
+    pub fn retrieve(&self, index: isize) -> PyResult<&T> {
+        if let Some(item) = self.indextable.get(&index) {
+            return Ok(item);
+        }
+        let message = format!(
+            "Unable to retrieve item {} from table {} (size {})",
+            index,
+            self.name,
+            self.size()
+        );
+        Err(IndexedTableError::new_err(message))
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &isize> {
+        self.indextable.keys()
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.indextable.values()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&isize, &T)> {
+        self.indextable.iter()
+    }
+
+    /// Returns the (key, index) pairs in the order of the python key strings.
+    pub fn key_iter(&self) -> impl Iterator<Item = ((String, String), isize)> {
+        self.keytable
+            .iter()
+            .map(|(key, ix)| (key.to_strings(), ix))
+            .sorted()
+    }
+
+    /// Iterates over (key, index) pairs in no particular order.
+    pub fn table_keys(&self) -> impl Iterator<Item = (&TableKey, isize)> {
+        self.keytable.iter()
+    }
+
+    pub fn len(&self) -> usize {
+        self.indextable.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.indextable.is_empty()
+    }
+
+    /// Copy of the table with every value replaced by `f(value)`.
+    pub fn map_values<U>(&self, f: impl Fn(&T) -> U) -> IndexedTable<U> {
+        IndexedTable {
+            name: self.name.clone(),
+            keytable: self.keytable.clone(),
+            indextable: self.indextable.iter().map(|(k, v)| (*k, f(v))).collect(),
+            next: self.next,
+            reserved: self.reserved.clone(),
+            checkpoints: self.checkpoints.clone(),
+            undo: self
+                .undo
+                .iter()
+                .map(|undo| match undo {
+                    Undo::Key(key, index) => Undo::Key(key.clone(), *index),
+                    Undo::Value(index, obj) => Undo::Value(*index, obj.as_ref().map(&f)),
+                })
+                .collect(),
+            version: self.version,
         }
     }
+}
+
+impl<T: IndexedTableValueTrait> IndexedTable<T> {
+    /// Stores `obj` under its own index and key.
+    pub fn insert_value(&mut self, obj: T) -> PyResult<()> {
+        let index = obj.index();
+        self.insert_key(&KeyParts(&obj.tags(), &obj.args()), index);
+        self.insert_value_at(index, obj);
+        if index >= self.next {
+            self.next = index + 1
+        }
+        Ok(())
+    }
+}
+
+impl IndexedTableValueTrait for Py<IndexedTableValue> {
+    fn tags(&self) -> Cow<[Cow<str>]> {
+        Cow::Owned(
+            self.get()
+                .tags
+                .iter()
+                .map(|s| Cow::Borrowed(s.as_str()))
+                .collect(),
+        )
+    }
+    fn args(&self) -> Cow<[isize]> {
+        Cow::Borrowed(&self.get().args[..])
+    }
+    fn index(&self) -> isize {
+        self.get().index
+    }
+}
+
+/// Python view of an `IndexedTable`.
+///
+/// Values are python objects (IndexedTableValue or one of its subclasses), so
+/// the callbacks that create them still run in python; the bookkeeping does not.
+#[pyclass(name = "IndexedTable", subclass)]
+pub struct PyIndexedTable {
+    table: IndexedTable<Py<IndexedTableValue>>,
+}
+
+#[pymethods]
+impl PyIndexedTable {
+    #[new]
+    pub fn new(name: String) -> Self {
+        PyIndexedTable {
+            table: IndexedTable::new(name),
+        }
+    }
+
+    #[getter]
+    fn name(&self) -> &str {
+        self.table.name()
+    }
+
+    fn size(&self) -> isize {
+        self.table.size()
+    }
+
+    fn reset(&mut self) -> PyResult<()> {
+        self.table.reset();
+        Ok(())
+    }
+
+    fn set_checkpoint(&mut self) -> PyResult<isize> {
+        self.table.set_checkpoint()
+    }
 
     fn iter(&self, f: &Bound<PyFunction>) -> PyResult<()> {
-        self.indextable
+        self.table
             .iter()
             .map(|(k, v)| f.call1((*k, v)))
             .collect::<PyResult<Vec<_>>>()?;
         Ok(())
     }
 
-    // Unvalidated
     /// Remove all entries added since the checkpoint was set.
     fn reset_to_checkpoint(&mut self) -> PyResult<isize> {
-        let cp = self.checkpoint;
-        let Some(cp) = cp else {
-            return Err(PyValueError::new_err(
-                "Cannot reset non-existent checkpoint",
-            ));
-        };
-        self.indextable.retain(|k, _| {
-            if !(cp <= *k && *k < self.next) {
-                return true;
-            }
-            self.reserved.contains(k)
-        });
-        self.keytable.retain(|_, v| *v < cp);
-        self.reserved.clear();
-        self.checkpoint.take();
-        self.next = cp;
-        Ok(cp)
+        self.table.reset_to_checkpoint()
     }
 
     fn remove_checkpoint(&mut self) {
-        self.checkpoint.take();
+        self.table.remove_checkpoint()
+    }
+
+    fn begin(&mut self, name: &str) -> PyResult<isize> {
+        self.table.begin(name)
+    }
+
+    fn commit(&mut self, name: &str) -> PyResult<()> {
+        self.table.commit(name)
+    }
+
+    /// Remove all entries added since checkpoint `name` was begun.
+    fn rollback(&mut self, name: &str) -> PyResult<isize> {
+        self.table.rollback(name)
+    }
+
+    #[getter]
+    fn checkpoints(&self) -> Vec<&str> {
+        self.table.checkpoints().collect()
+    }
+
+    /// Checks the table for duplicate keys, gaps, and uncommitted reserved
+    /// indices.
+    fn check(&self) -> IntegrityReport {
+        let mut report = IntegrityReport::default();
+        self.table.check(&mut report);
+        report
+    }
+
+    /// Entries added, removed, and re-indexed in `other` relative to this
+    /// table, matched by key.
+    fn diff(&self, other: PyRef<PyIndexedTable>) -> PyResult<TableDiff> {
+        if self.table.name() != other.table.name() {
+            return Err(IndexedTableError::new_err(format!(
+                "Cannot compare table {} with table {}",
+                self.table.name(),
+                other.table.name()
+            )));
+        }
+        Ok(TableDiff::new(
+            self.table.name(),
+            self.table.key_iter(),
+            other.table.key_iter(),
+        ))
     }
 
     // Unvalidated
     fn add(&mut self, key: (String, String), f: &Bound<PyFunction>) -> PyResult<isize> {
-        if let Some(value) = self.keytable.get(&key) {
-            return Ok(*value);
-        }
-        let index = self.next;
-        let obj = f
-            .call1((index, key.clone()))?
-            .downcast_into::<IndexedTableValue>()?;
-        self.keytable.insert(key, index);
-        self.indextable.insert(index, obj.unbind());
-        self.next += 1;
-        Ok(index)
+        let pykey = key.clone();
+        self.table.add(key, |index| {
+            Ok(f.call1((index, pykey))?
+                .downcast_into::<IndexedTableValue>()?
+                .unbind())
+        })
     }
 
     fn add_tags_args(
@@ -392,32 +886,23 @@ impl IndexedTable {
         args: Vec<isize>,
         f: Bound<PyFunction>,
     ) -> PyResult<isize> {
-        let key = get_key(tags.clone(), args.clone());
-        if let Some(item) = self.keytable.get(&key) {
-            Ok(*item)
-        } else {
-            let index = self.next;
-            let obj = f.call1((index, tags, args))?.downcast()?.clone();
-            self.keytable.insert(key, index);
-            self.indextable.insert(index, obj.unbind());
-            self.next += 1;
-            Ok(index)
-        }
+        self.table.add_tags_args(&tags, &args, |index| {
+            Ok(f.call1((index, tags.clone(), args.clone()))?
+                .downcast_into::<IndexedTableValue>()?
+                .unbind())
+        })
     }
 
     fn reserve(&mut self) -> isize {
-        let index = self.next;
-        self.reserved.push(index);
-        self.next += 1;
-        index
+        self.table.reserve()
     }
 
     fn values(&self) -> Vec<&Py<IndexedTableValue>> {
-        self.indextable.values().collect()
+        self.table.values().collect()
     }
 
     fn items(&self) -> Vec<(isize, &Py<IndexedTableValue>)> {
-        self.indextable.iter().map(|(k, v)| (*k, v)).collect()
+        self.table.iter().map(|(k, v)| (*k, v)).collect()
     }
 
     fn commit_reserved(
@@ -426,33 +911,11 @@ impl IndexedTable {
         key: (String, String),
         obj: &Bound<IndexedTableValue>,
     ) -> PyResult<()> {
-        if !self.reserved.contains(&index) {
-            return Err(IndexedTableError::new_err(format!(
-                "Trying to commit nonexisting index: {index}"
-            )));
-        }
-        self.keytable.insert(key, index);
-        self.indextable.insert(index, obj.clone().unbind());
-        self.reserved.remove(
-            self.reserved
-                .iter()
-                .position(|x| *x == index)
-                .ok_or_else(|| PyException::new_err("{index} not in `reserved`"))?,
-        );
-        Ok(())
+        self.table.commit_reserved(index, key, obj.clone().unbind())
     }
 
     pub fn retrieve(&self, index: isize) -> PyResult<&Py<IndexedTableValue>> {
-        if let Some(item) = self.indextable.get(&index) {
-            return Ok(item);
-        }
-        let message = format!(
-            "Unable to retrieve item {} from table {} (size {})",
-            index,
-            self.name,
-            self.size()
-        );
-        Err(IndexedTableError::new_err(message))
+        self.table.retrieve(index)
     }
 
     fn retrieve_by_key<'a>(
@@ -460,12 +923,12 @@ impl IndexedTable {
         f: &Bound<'a, PyFunction>,
     ) -> PyResult<Vec<((String, String), Bound<'a, IndexedTableValue>)>> {
         let mut result = Vec::new();
-        for (key, index) in self.keytable.iter() {
+        for (key, index) in self.table.key_iter() {
             if f.call1((key.clone(),))?.extract()? {
                 result.push((
-                    key.clone(),
-                    self.indextable
-                        .get(&index)
+                    key,
+                    self.table
+                        .get(index)
                         .ok_or_else(|| PyException::new_err("No element at {index}"))?
                         .bind(f.py())
                         .clone(),
@@ -483,7 +946,7 @@ impl IndexedTable {
         tag: Option<&str>,
     ) -> PyResult<()> {
         let tag = tag.unwrap_or("n");
-        for value in self.indextable.values() {
+        for value in self.table.values() {
             let snode = element_tree_element(node.py(), tag)?;
             f.call1((&snode, value))?;
             node.call_method1("append", (snode,))?;
@@ -503,7 +966,7 @@ impl IndexedTable {
         if node.is_none() {
             return Err(IndexedTableError::new_err(format!(
                 "Xml node not present in {}",
-                self.name
+                self.table.name()
             )));
         }
         for snode in node
@@ -527,12 +990,24 @@ impl IndexedTable {
                 obj.getattr(intern!(node.py(), "index"))?
             }
             .extract()?;
-            self.keytable.insert(key, index);
-            self.indextable
-                .insert(index, obj.downcast()?.clone().unbind());
-            if index >= self.next {
-                self.next = index + 1
-            }
+            self.table
+                .insert(key, index, obj.downcast()?.clone().unbind())?;
+        }
+        Ok(())
+    }
+
+    /// Replaces the contents of the table with the entries of the table with
+    /// the same name read by `read_xml_tables`.
+    pub fn read_xml_tables(&mut self, py: Python, tables: &XmlTables) -> PyResult<()> {
+        let Some(values) = tables.table(self.table.name()) else {
+            return Err(IndexedTableError::new_err(format!(
+                "Xml node not present in {}",
+                self.table.name()
+            )));
+        };
+        self.table.reset();
+        for value in values {
+            self.table.insert_value(Py::new(py, value.clone())?)?;
         }
         Ok(())
     }
@@ -541,7 +1016,7 @@ impl IndexedTable {
         &self,
         p: &'a Bound<'b, PyAny>, // int -> IndexedTableValue, but sometimes non-PyFunction
     ) -> PyResult<BTreeMap<isize, Bound<'b, IndexedTableValue>>> {
-        self.indextable
+        self.table
             .keys()
             .map(|ix| Ok((*ix, p.call1((*ix,))?.downcast()?.clone())))
             .collect()
@@ -551,22 +1026,35 @@ impl IndexedTable {
     #[pyo3(name = "__str__")]
     fn str(slf: &Bound<Self>) -> PyResult<String> {
         let slf_borrow = slf.borrow();
+        let table = &slf_borrow.table;
         let mut lines = Vec::new();
-        lines.push(format!("\n{}", slf_borrow.name));
-        for (ix, obj) in slf_borrow.indextable.iter() {
+        lines.push(format!("\n{}", table.name()));
+        for (ix, obj) in table.iter() {
             lines.push(format!("{ix:>4} {}", obj.bind(slf.py()).str()?));
         }
-        if !slf_borrow.reserved.is_empty() {
-            lines.push(format!("Reserved: {:?}", slf_borrow.reserved));
+        if !table.reserved().is_empty() {
+            lines.push(format!("Reserved: {:?}", table.reserved()));
         }
-        if let Some(cp) = slf_borrow.checkpoint {
+        if let Some(cp) = table.checkpoint() {
             lines.push(format!("Checkpoint: {cp}"));
         }
+        let names = table.checkpoints().join(", ");
+        if !names.is_empty() {
+            lines.push(format!("Checkpoints: {names}"));
+        }
         Ok(lines.join("\n"))
     }
 }
 
-impl IndexedTable {
+impl PyIndexedTable {
+    pub fn table(&self) -> &IndexedTable<Py<IndexedTableValue>> {
+        &self.table
+    }
+
+    pub fn table_mut(&mut self) -> &mut IndexedTable<Py<IndexedTableValue>> {
+        &mut self.table
+    }
+
     pub fn retrieve_bound<'a>(
         &self,
         py: Python<'a>,
@@ -576,6 +1064,6 @@ impl IndexedTable {
     }
 
     pub fn keys(&self) -> impl Iterator<Item = &isize> {
-        self.indextable.keys()
+        self.table.keys()
     }
 }
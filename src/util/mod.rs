@@ -1,9 +1,20 @@
 use pyo3::prelude::*;
 
+pub mod dictionary_cache;
+pub mod dictionary_xml;
+pub mod file_fingerprint;
 pub mod indexed_table;
+pub mod table_check;
+pub mod table_diff;
+pub mod table_key;
+pub mod xml_element;
 
 pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
     let module = PyModule::new_bound(py, "util")?;
+    module.add_submodule(&dictionary_cache::module(py)?)?;
+    module.add_submodule(&dictionary_xml::module(py)?)?;
     module.add_submodule(&indexed_table::module(py)?)?;
+    module.add_submodule(&table_check::module(py)?)?;
+    module.add_submodule(&table_diff::module(py)?)?;
     Ok(module)
 }
@@ -0,0 +1,218 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Integrity checks on indexed tables.
+//!
+//! The checks only report: nothing is changed, and a table with issues can
+//! still be used as far as it goes.
+
+use std::collections::BTreeMap;
+
+use itertools::Itertools;
+use pyo3::prelude::*;
+
+use crate::util::indexed_table::{IndexedTable, IndexedTableValueTrait};
+
+pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
+    let module = PyModule::new_bound(py, "table_check")?;
+    module.add_class::<IntegrityReport>()?;
+    module.add_class::<TableIssue>()?;
+    Ok(module)
+}
+
+/// An arg refers to an index that is not present in its target table.
+pub const DANGLING_ARG: &str = "dangling-arg";
+/// Two entries have the same key.
+pub const DUPLICATE_KEY: &str = "duplicate-key";
+/// Indices below `next` that are neither used nor reserved.
+pub const GAP: &str = "gap";
+/// An index was reserved but never committed.
+pub const UNCOMMITTED_RESERVED: &str = "uncommitted-reserved";
+/// The tag of an entry has no registered record type.
+pub const UNKNOWN_TAG: &str = "unknown-tag";
+/// An entry has fewer args than its record type requires.
+pub const TOO_FEW_ARGS: &str = "too-few-args";
+
+/// One problem found in a table.
+///
+/// `index` is the entry the problem was found in, or the first index of a gap.
+#[pyclass(frozen, get_all)]
+#[derive(Clone)]
+pub struct TableIssue {
+    kind: String,
+    table: String,
+    index: Option<isize>,
+    message: String,
+}
+
+#[pymethods]
+impl TableIssue {
+    #[pyo3(name = "__str__")]
+    fn str(&self) -> String {
+        match self.index {
+            Some(index) => format!("{} [{}:{index}]: {}", self.kind, self.table, self.message),
+            None => format!("{} [{}]: {}", self.kind, self.table, self.message),
+        }
+    }
+}
+
+impl TableIssue {
+    pub fn new(kind: &str, table: &str, index: Option<isize>, message: String) -> TableIssue {
+        TableIssue {
+            kind: kind.to_string(),
+            table: table.to_string(),
+            index,
+            message,
+        }
+    }
+
+    pub fn kind(&self) -> &str {
+        &self.kind
+    }
+}
+
+/// Result of checking one or more tables.
+#[pyclass(frozen)]
+#[derive(Clone, Default)]
+pub struct IntegrityReport {
+    tables: Vec<String>,
+    issues: Vec<TableIssue>,
+}
+
+#[pymethods]
+impl IntegrityReport {
+    /// Names of the tables that were checked.
+    #[getter]
+    fn tables(&self) -> Vec<String> {
+        self.tables.clone()
+    }
+
+    #[getter]
+    fn issues(&self) -> Vec<TableIssue> {
+        self.issues.clone()
+    }
+
+    #[getter]
+    pub fn is_ok(&self) -> bool {
+        self.issues.is_empty()
+    }
+
+    /// Number of issues of each kind.
+    fn counts(&self) -> BTreeMap<String, usize> {
+        self.issues
+            .iter()
+            .counts_by(|i| i.kind.clone())
+            .into_iter()
+            .collect()
+    }
+
+    #[pyo3(signature = (kind=None, table=None))]
+    fn select(&self, kind: Option<&str>, table: Option<&str>) -> Vec<TableIssue> {
+        self.issues
+            .iter()
+            .filter(|i| kind.is_none_or(|k| i.kind == k))
+            .filter(|i| table.is_none_or(|t| i.table == t))
+            .cloned()
+            .collect()
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self) -> String {
+        let mut lines = vec![format!(
+            "Checked {} tables: {} issues",
+            self.tables.len(),
+            self.issues.len()
+        )];
+        for (kind, count) in self.counts() {
+            lines.push(format!("  {kind:<22} {count:>6}"));
+        }
+        lines.extend(self.issues.iter().map(|i| format!("  {}", i.str())));
+        lines.join("\n")
+    }
+}
+
+impl IntegrityReport {
+    pub fn add_table(&mut self, name: &str) {
+        self.tables.push(name.to_string());
+    }
+
+    pub fn push(&mut self, issue: TableIssue) {
+        self.issues.push(issue);
+    }
+
+    pub fn issues_iter(&self) -> impl Iterator<Item = &TableIssue> {
+        self.issues.iter()
+    }
+}
+
+impl<T: IndexedTableValueTrait> IndexedTable<T> {
+    /// Adds the issues that can be found from this table alone to `report`:
+    /// duplicate keys, gaps, and uncommitted reserved indices.
+    pub fn check(&self, report: &mut IntegrityReport) {
+        let name = self.name();
+        report.add_table(name);
+        for (ix, value) in self.iter() {
+            match self.get_index_tags_args(&value.tags(), &value.args()) {
+                Some(other) if other == *ix => (),
+                other => report.push(TableIssue::new(
+                    DUPLICATE_KEY,
+                    name,
+                    Some(*ix),
+                    format!(
+                        "key ({}, {}) maps to {}",
+                        value.tags().iter().join(","),
+                        value.args().iter().join(","),
+                        other.map_or("no index".to_string(), |o| o.to_string())
+                    ),
+                )),
+            }
+        }
+        let missing =
+            (1..self.next()).filter(|ix| self.get(*ix).is_none() && !self.reserved().contains(ix));
+        for (_, mut gap) in &missing.enumerate().chunk_by(|(i, ix)| ix - *i as isize) {
+            let (_, first) = gap.next().unwrap();
+            let last = gap.last().map_or(first, |(_, ix)| ix);
+            let message = if first == last {
+                format!("index {first} is missing")
+            } else {
+                format!("indices {first}..{last} are missing")
+            };
+            report.push(TableIssue::new(GAP, name, Some(first), message));
+        }
+        for ix in self.reserved() {
+            report.push(TableIssue::new(
+                UNCOMMITTED_RESERVED,
+                name,
+                Some(*ix),
+                format!("index {ix} was reserved but not committed"),
+            ));
+        }
+    }
+}
@@ -0,0 +1,143 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Differences between two versions of an indexed table, matched by key.
+
+use std::collections::BTreeMap;
+
+use pyo3::prelude::*;
+
+pub fn module(py: Python) -> PyResult<Bound<PyModule>> {
+    let module = PyModule::new_bound(py, "table_diff")?;
+    module.add_class::<TableDiff>()?;
+    Ok(module)
+}
+
+/// Entries added, removed, and re-indexed between an old and a new table.
+///
+/// Keys are (tags, args) strings, as in `get_key`; a re-indexed entry has
+/// the same key in both tables but a different index.
+#[pyclass(frozen, get_all)]
+#[derive(Clone)]
+pub struct TableDiff {
+    table: String,
+    /// (index in new table, key)
+    added: Vec<(isize, (String, String))>,
+    /// (index in old table, key)
+    removed: Vec<(isize, (String, String))>,
+    /// (key, index in old table, index in new table)
+    reindexed: Vec<((String, String), isize, isize)>,
+}
+
+#[pymethods]
+impl TableDiff {
+    #[getter]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.reindexed.is_empty()
+    }
+
+    #[pyo3(name = "__str__")]
+    fn str(&self) -> String {
+        let mut lines = vec![format!(
+            "{}: {} added, {} removed, {} re-indexed",
+            self.table,
+            self.added.len(),
+            self.removed.len(),
+            self.reindexed.len()
+        )];
+        for (ix, (tags, args)) in &self.added {
+            lines.push(format!("  + {ix:>5} ({tags}) [{args}]"));
+        }
+        for (ix, (tags, args)) in &self.removed {
+            lines.push(format!("  - {ix:>5} ({tags}) [{args}]"));
+        }
+        for ((tags, args), old, new) in &self.reindexed {
+            lines.push(format!("  ~ {old:>5} -> {new:<5} ({tags}) [{args}]"));
+        }
+        lines.join("\n")
+    }
+}
+
+impl TableDiff {
+    /// Compares the (key, index) pairs of two versions of table `name`.
+    ///
+    /// A key may occur more than once on either side. Occurrences with the
+    /// same index in both tables are unchanged; the others are paired in
+    /// index order as re-indexed, and the rest are added or removed.
+    pub fn new(
+        name: &str,
+        old: impl IntoIterator<Item = ((String, String), isize)>,
+        new: impl IntoIterator<Item = ((String, String), isize)>,
+    ) -> TableDiff {
+        let mut indices: BTreeMap<(String, String), (Vec<isize>, Vec<isize>)> = BTreeMap::new();
+        for (key, ix) in old {
+            indices.entry(key).or_default().0.push(ix);
+        }
+        for (key, ix) in new {
+            indices.entry(key).or_default().1.push(ix);
+        }
+        let mut added = Vec::new();
+        let mut removed = Vec::new();
+        let mut reindexed = Vec::new();
+        for (key, (mut old_ixs, mut new_ixs)) in indices {
+            old_ixs.sort();
+            new_ixs.sort();
+            let old_only = old_ixs
+                .iter()
+                .filter(|ix| new_ixs.binary_search(ix).is_err())
+                .copied()
+                .collect::<Vec<_>>();
+            let new_only = new_ixs
+                .iter()
+                .filter(|ix| old_ixs.binary_search(ix).is_err())
+                .copied()
+                .collect::<Vec<_>>();
+            let paired = old_only.len().min(new_only.len());
+            for (old_ix, new_ix) in old_only.iter().zip(&new_only) {
+                reindexed.push((key.clone(), *old_ix, *new_ix));
+            }
+            removed.extend(old_only[paired..].iter().map(|ix| (*ix, key.clone())));
+            added.extend(new_only[paired..].iter().map(|ix| (*ix, key.clone())));
+        }
+        added.sort();
+        removed.sort();
+        reindexed.sort_by_key(|(_, old_ix, _)| *old_ix);
+        TableDiff {
+            table: name.to_string(),
+            added,
+            removed,
+            reindexed,
+        }
+    }
+
+    pub fn table(&self) -> &str {
+        &self.table
+    }
+}
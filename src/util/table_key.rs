@@ -0,0 +1,277 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Structured keys for `IndexedTable`.
+//!
+//! A key is a list of tags and a list of args. Python knows it as the pair of
+//! comma-joined strings produced by `get_key`; both forms are hashed directly
+//! from their parts, so looking up a key never builds the other form.
+//!
+//! Keys given as strings whose args are not all canonical integers (e.g. "01",
+//! "-0", or "x") keep their args string, so they are only equal to keys with
+//! the same args string, as before.
+
+use std::{
+    collections::{hash_map::RandomState, HashMap, HashSet},
+    hash::{BuildHasher, Hasher},
+    sync::Arc,
+};
+
+use itertools::Itertools;
+
+/// Key of an entry in an `IndexedTable`.
+///
+/// Tags are shared by all keys of the same table that use them.
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub struct TableKey {
+    tags: Box<[Arc<str>]>,
+    args: KeyArgs,
+}
+
+#[derive(Clone, PartialEq, Eq, Debug)]
+enum KeyArgs {
+    Ints(Box<[isize]>),
+    /// Args string with a part that is not a canonical integer.
+    Raw(Box<str>),
+}
+
+impl TableKey {
+    pub fn tags(&self) -> &[Arc<str>] {
+        &self.tags
+    }
+
+    /// The args, or None if they are not all canonical integers.
+    pub fn args(&self) -> Option<&[isize]> {
+        match &self.args {
+            KeyArgs::Ints(args) => Some(args),
+            KeyArgs::Raw(_) => None,
+        }
+    }
+
+    /// The (tags, args) strings python uses as key, as returned by `get_key`.
+    pub fn to_strings(&self) -> (String, String) {
+        let args = match &self.args {
+            KeyArgs::Ints(args) => args.iter().join(","),
+            KeyArgs::Raw(args) => args.to_string(),
+        };
+        (self.tags.iter().join(","), args)
+    }
+}
+
+/// Anything that can be looked up in a `KeyTable`.
+///
+/// Implementations must agree with `get_key`: two views are the same key if
+/// and only if their joined strings are equal.
+pub trait KeyView {
+    fn tags(&self) -> impl Iterator<Item = &str>;
+
+    /// The args string, if one of the args is not a canonical integer; such a
+    /// key is compared by this string.
+    fn raw_args(&self) -> Option<&str>;
+
+    /// The args; only used if `raw_args` is None.
+    fn args(&self) -> impl Iterator<Item = isize>;
+}
+
+/// Splits a comma-joined string; the empty string has no parts.
+fn split(s: &str) -> impl Iterator<Item = &str> {
+    (!s.is_empty()).then(|| s.split(',')).into_iter().flatten()
+}
+
+/// The value of `s` if it is an integer as `isize::to_string` writes it, i.e.,
+/// without sign or leading zeros that would join to another string.
+fn canonical_int(s: &str) -> Option<isize> {
+    let digits = s.strip_prefix('-').unwrap_or(s);
+    let canonical = match digits.as_bytes() {
+        [b'0'] => digits.len() == s.len(),
+        [b'1'..=b'9', rest @ ..] => rest.iter().all(u8::is_ascii_digit),
+        _ => false,
+    };
+    if canonical {
+        s.parse().ok()
+    } else {
+        None
+    }
+}
+
+impl KeyView for (String, String) {
+    fn tags(&self) -> impl Iterator<Item = &str> {
+        split(&self.0)
+    }
+
+    fn raw_args(&self) -> Option<&str> {
+        split(&self.1)
+            .any(|a| canonical_int(a).is_none())
+            .then_some(self.1.as_str())
+    }
+
+    fn args(&self) -> impl Iterator<Item = isize> {
+        split(&self.1).map(|a| canonical_int(a).unwrap_or_default())
+    }
+}
+
+/// A key given as separate tags and args.
+pub struct KeyParts<'a, S>(pub &'a [S], pub &'a [isize]);
+
+impl<S: AsRef<str>> KeyView for KeyParts<'_, S> {
+    fn tags(&self) -> impl Iterator<Item = &str> {
+        // Joining and splitting again is the identity, except that a single
+        // empty tag joins to the empty string, which has no tags.
+        let empty = matches!(self.0, [t] if t.as_ref().is_empty());
+        self.0
+            .iter()
+            .filter(move |_| !empty)
+            .flat_map(|t| t.as_ref().split(','))
+    }
+
+    fn raw_args(&self) -> Option<&str> {
+        None
+    }
+
+    fn args(&self) -> impl Iterator<Item = isize> {
+        self.1.iter().copied()
+    }
+}
+
+impl KeyView for TableKey {
+    fn tags(&self) -> impl Iterator<Item = &str> {
+        self.tags.iter().map(|t| t.as_ref())
+    }
+
+    fn raw_args(&self) -> Option<&str> {
+        match &self.args {
+            KeyArgs::Ints(_) => None,
+            KeyArgs::Raw(args) => Some(args),
+        }
+    }
+
+    fn args(&self) -> impl Iterator<Item = isize> {
+        self.args().unwrap_or_default().iter().copied()
+    }
+}
+
+/// Map from keys to indices.
+#[derive(Clone, Default)]
+pub struct KeyTable {
+    hasher: RandomState,
+    interned: HashSet<Arc<str>>,
+    buckets: HashMap<u64, Vec<(TableKey, isize)>>,
+}
+
+impl KeyTable {
+    fn hash<K: KeyView>(&self, key: &K) -> u64 {
+        let mut hasher = self.hasher.build_hasher();
+        for tag in key.tags() {
+            hasher.write(tag.as_bytes());
+            // 0xff and 0xfe never occur in utf-8, so they delimit unambiguously.
+            hasher.write_u8(0xff);
+        }
+        hasher.write_u8(0xfe);
+        match key.raw_args() {
+            Some(args) => hasher.write(args.as_bytes()),
+            None => key.args().for_each(|arg| hasher.write_isize(arg)),
+        }
+        hasher.finish()
+    }
+
+    fn matches<K: KeyView>(key: &K, other: &TableKey) -> bool {
+        key.tags().eq(other.tags().iter().map(|t| t.as_ref()))
+            && match (key.raw_args(), &other.args) {
+                (None, KeyArgs::Ints(args)) => key.args().eq(args.iter().copied()),
+                (Some(raw), KeyArgs::Raw(args)) => raw == args.as_ref(),
+                _ => false,
+            }
+    }
+
+    fn find<K: KeyView>(&self, key: &K) -> Option<&(TableKey, isize)> {
+        self.buckets
+            .get(&self.hash(key))?
+            .iter()
+            .find(|(k, _)| Self::matches(key, k))
+    }
+
+    pub fn get<K: KeyView>(&self, key: &K) -> Option<isize> {
+        self.find(key).map(|(_, ix)| *ix)
+    }
+
+    /// The stored form of `key`, if it is present.
+    pub fn get_key<K: KeyView>(&self, key: &K) -> Option<&TableKey> {
+        self.find(key).map(|(k, _)| k)
+    }
+
+    /// Maps `key` to `index`, replacing any index it had.
+    pub fn insert<K: KeyView>(&mut self, key: &K, index: isize) {
+        let hash = self.hash(key);
+        let bucket = self.buckets.entry(hash).or_default();
+        if let Some(entry) = bucket.iter_mut().find(|(k, _)| Self::matches(key, k)) {
+            entry.1 = index;
+            return;
+        }
+        let interned = &mut self.interned;
+        let tags = key
+            .tags()
+            .map(|t| match interned.get(t) {
+                Some(t) => t.clone(),
+                None => {
+                    let t: Arc<str> = Arc::from(t);
+                    interned.insert(t.clone());
+                    t
+                }
+            })
+            .collect();
+        let args = match key.raw_args() {
+            Some(args) => KeyArgs::Raw(args.into()),
+            None => KeyArgs::Ints(key.args().collect()),
+        };
+        bucket.push((TableKey { tags, args }, index));
+    }
+
+    /// Removes `key`; returns the index it mapped to.
+    pub fn remove<K: KeyView>(&mut self, key: &K) -> Option<isize> {
+        let hash = self.hash(key);
+        let bucket = self.buckets.get_mut(&hash)?;
+        let position = bucket.iter().position(|(k, _)| Self::matches(key, k))?;
+        let (_, index) = bucket.swap_remove(position);
+        if bucket.is_empty() {
+            self.buckets.remove(&hash);
+        }
+        Some(index)
+    }
+
+    pub fn clear(&mut self) {
+        self.interned.clear();
+        self.buckets.clear();
+    }
+
+    /// Iterates over (key, index) pairs in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&TableKey, isize)> {
+        self.buckets.values().flatten().map(|(key, ix)| (key, *ix))
+    }
+}
@@ -0,0 +1,147 @@
+/*
+------------------------------------------------------------------------------
+CodeHawk C Analyzer
+Author: Henny Sipma
+------------------------------------------------------------------------------
+The MIT License (MIT)
+
+Copyright (c) 2017-2020 Kestrel Technology LLC
+Copyright (c) 2020-2022 Henny B. Sipma
+Copyright (c) 2023-2024 Aarno Labs LLC
+
+Permission is hereby granted, free of charge, to any person obtaining a copy
+of this software and associated documentation files (the "Software"), to deal
+in the Software without restriction, including without limitation the rights
+to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+copies of the Software, and to permit persons to whom the Software is
+furnished to do so, subject to the following conditions:
+
+The above copyright notice and this permission notice shall be included in all
+copies or substantial portions of the Software.
+
+THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+SOFTWARE.
+------------------------------------------------------------------------------
+*/
+//! Xml files read into a tree without holding the GIL.
+//!
+//! Files are parsed on worker threads into `XmlElement` trees, which are
+//! turned into ElementTree elements on a thread that holds the GIL.
+
+use std::path::Path;
+
+use pyo3::{intern, prelude::*, types::PyDict};
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+
+pyo3::import_exception!(chc.util.fileutil, CHCXmlParseError);
+
+/// An xml element with its attributes, leading text and children.
+pub struct XmlElement {
+    tag: String,
+    attributes: Vec<(String, String)>,
+    text: Option<String>,
+    children: Vec<XmlElement>,
+}
+
+impl XmlElement {
+    /// Reads the root element of the xml file at `path`.
+    pub fn read(path: &Path) -> PyResult<XmlElement> {
+        let error = || CHCXmlParseError::new_err((path.to_string_lossy().into_owned(), 0, (0, 0)));
+        let mut reader = Reader::from_file(path).map_err(|_| error())?;
+        let mut open: Vec<XmlElement> = Vec::new();
+        let mut buf = Vec::new();
+        loop {
+            match reader.read_event_into(&mut buf).map_err(|_| error())? {
+                Event::Start(e) => open.push(Self::from_start(&e).ok_or_else(error)?),
+                Event::Empty(e) => {
+                    let element = Self::from_start(&e).ok_or_else(error)?;
+                    match open.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                Event::End(_) => {
+                    let element = open.pop().ok_or_else(error)?;
+                    match open.last_mut() {
+                        Some(parent) => parent.children.push(element),
+                        None => return Ok(element),
+                    }
+                }
+                Event::Text(e) => {
+                    // Only the text before the first child, as in ElementTree
+                    if let Some(element) = open.last_mut() {
+                        if element.children.is_empty() {
+                            let text = e.unescape().map_err(|_| error())?;
+                            element.text.get_or_insert_with(String::new).push_str(&text);
+                        }
+                    }
+                }
+                Event::CData(e) => {
+                    if let Some(element) = open.last_mut() {
+                        if element.children.is_empty() {
+                            let text = String::from_utf8_lossy(&e);
+                            element.text.get_or_insert_with(String::new).push_str(&text);
+                        }
+                    }
+                }
+                Event::Eof => return Err(error()),
+                _ => (),
+            }
+            buf.clear();
+        }
+    }
+
+    fn from_start(e: &BytesStart) -> Option<XmlElement> {
+        let mut attributes = Vec::new();
+        for attr in e.attributes() {
+            let attr = attr.ok()?;
+            let value = attr.unescape_value().ok()?.into_owned();
+            attributes.push((
+                String::from_utf8_lossy(attr.key.as_ref()).into_owned(),
+                value,
+            ));
+        }
+        Some(XmlElement {
+            tag: String::from_utf8_lossy(e.name().as_ref()).into_owned(),
+            attributes,
+            text: None,
+            children: Vec::new(),
+        })
+    }
+
+    /// The first child with tag `tag`, as ElementTree's find.
+    pub fn find(&self, tag: &str) -> Option<&XmlElement> {
+        self.children.iter().find(|child| child.tag == tag)
+    }
+
+    /// Builds the ElementTree element with the same contents.
+    pub fn to_element<'a>(&self, py: Python<'a>) -> PyResult<Bound<'a, PyAny>> {
+        let element = PyModule::import_bound(py, intern!(py, "xml.etree.ElementTree"))?
+            .getattr(intern!(py, "Element"))?;
+        self.build(&element)
+    }
+
+    fn build<'a>(&self, element: &Bound<'a, PyAny>) -> PyResult<Bound<'a, PyAny>> {
+        let py = element.py();
+        let attrib = PyDict::new_bound(py);
+        for (key, value) in &self.attributes {
+            attrib.set_item(key, value)?;
+        }
+        let node = element.call1((&self.tag, attrib))?;
+        if let Some(text) = &self.text {
+            node.setattr(intern!(py, "text"), text)?;
+        }
+        for child in &self.children {
+            node.call_method1(intern!(py, "append"), (child.build(element)?,))?;
+        }
+        Ok(node)
+    }
+}
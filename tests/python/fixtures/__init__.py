@@ -0,0 +1,201 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Fixture files and stubs shared by the tests."""
+
+import os
+import shutil
+import tempfile
+import xml.etree.ElementTree as ET
+
+from typing import Optional
+
+from chc.app.CFileDeclarations import CFileDeclarations
+from chc.app.CFileDictionary import CFileDictionary
+import chc.util.fileutil as UF
+
+FIXTURES = os.path.dirname(os.path.abspath(__file__))
+
+TYPES = (
+    '<n a="1" ix="1" t="tint,iint"/>'
+    '<n a="1,1" ix="2" t="tptr"/>'
+    '<n a="1,-1,0" ix="3" t="tfun"/>'
+    '<n ix="4" t="tnamed,fn_t"/>')
+
+DECLARATIONS = (
+    '<c-declarations>'
+    '<location-table><n a="1,10,3" ix="1"/></location-table>'
+    '<initinfo-table/>'
+    '<offset-init-table/>'
+    '<typeinfo-table><n a="3" ix="1" t="fn_t"/></typeinfo-table>'
+    '<varinfo-table>'
+    '<n a="{vid},{vtype},-1,1,0,1,0,0" ix="1" t="{name},?"/>'
+    '</varinfo-table>'
+    '<fieldinfo-table/>'
+    '<compinfo-table/>'
+    '<enumitem-table/>'
+    '<enuminfo-table/>'
+    '<filename-table><n ix="1" v="{cfile}.c"/></filename-table>'
+    '</c-declarations>')
+
+CFILE = (
+    '<c-analysis><c-file>'
+    '<functions><gfun iloc="1" ivinfo="1"/></functions>'
+    '</c-file></c-analysis>')
+
+CFUN = '<c-analysis><function fname="{name}"/></c-analysis>'
+
+XREFS = (
+    '<c-analysis><global-xrefs><varinfo-xrefs>'
+    '<vxref gvid="{gvid}" vid="{vid}"/>'
+    '</varinfo-xrefs></global-xrefs></c-analysis>')
+
+
+DECLARATION_TABLES = [
+    "location-table",
+    "initinfo-table",
+    "offset-init-table",
+    "typeinfo-table",
+    "varinfo-table",
+    "fieldinfo-table",
+    "compinfo-table",
+    "enumitem-table",
+    "enuminfo-table",
+    "filename-table"]
+
+
+def table_name(keyword: str) -> str:
+    """Return the table name for a keyword argument, e.g. typ_table."""
+    return keyword.replace("_", "-")
+
+
+def replace_table(root: ET.Element, name: str, entries: str) -> None:
+    """Replace the entries of table name in root by entries.
+
+    entries is the xml text of the <n> elements of the table.
+    """
+    table = root.find(".//" + name)
+    if table is None:
+        raise ValueError("No table " + name + " in fixture")
+    table.clear()
+    table.extend(ET.fromstring("<" + name + ">" + entries + "</" + name + ">"))
+
+
+class StubCFile:
+    """Stands in for the CFile of a dictionary loaded from a fixture.
+
+    Declaration tables not given as keyword arguments are empty; dictionary
+    is set by load_cdict.
+    """
+
+    name = "small"
+    dictionary: Optional[CFileDictionary] = None
+
+    def __init__(self, **tables: str) -> None:
+        xnode = ET.Element("declarations")
+        for name in DECLARATION_TABLES:
+            xnode.append(ET.Element(name))
+        for (keyword, entries) in tables.items():
+            replace_table(xnode, table_name(keyword), entries)
+        self.declarations = CFileDeclarations(self, xnode)
+
+
+def fixture_tree(
+        fixture: str = "small_cdict.xml", **tables: str) -> ET.ElementTree:
+    """Return the parsed fixture file with the given tables replaced.
+
+    Tables are given by keyword, with underscores for dashes, e.g.,
+    fixture_tree(typ_table='<n a="1" ix="1" t="tint,iint"/>').
+    """
+    tree = ET.parse(os.path.join(FIXTURES, fixture))
+    for (keyword, entries) in tables.items():
+        replace_table(tree.getroot(), table_name(keyword), entries)
+    return tree
+
+
+def write_tree(tree: ET.ElementTree, filename: str) -> None:
+    tree.write(filename, encoding="UTF-8", xml_declaration=True)
+
+
+def load_cdict(
+        fixture: str = "small_cdict.xml",
+        cfile: Optional[StubCFile] = None,
+        **tables: str) -> CFileDictionary:
+    """Return the dictionary of fixture with the given tables replaced."""
+    tree = fixture_tree(fixture, **tables)
+    tmpdir = tempfile.mkdtemp()
+    try:
+        filename = os.path.join(tmpdir, fixture)
+        write_tree(tree, filename)
+        cfile = cfile or StubCFile()
+        cdict = CFileDictionary(cfile, None)
+        cdict.initialize_from_file(filename)
+        cfile.dictionary = cdict
+    finally:
+        shutil.rmtree(tmpdir)
+    return cdict
+
+
+def write_project(targetpath: str) -> None:
+    """Write the results of the two-file project proj into targetpath.
+
+    src/first defines main (vid 1) with the typedef'd function type fn_t,
+    src/second defines helper (vid 2); their global vids are 10 and 20.
+    """
+    resultspath = UF.get_analysisresults_path(targetpath, "proj")
+    os.makedirs(resultspath)
+    cfiles = [("first", "main", 1, 4), ("second", "helper", 2, 3)]
+    with open(os.path.join(resultspath, "target_files.xml"), "w") as fp:
+        fp.write(
+            "<c-analysis><c-files>"
+            + "".join(
+                f'<c-file id="{ix}" name="src/{cfile}.c"/>'
+                for (ix, (cfile, _, _, _)) in enumerate(cfiles, 1))
+            + "</c-files></c-analysis>")
+    for (cfile, name, vid, vtype) in cfiles:
+        args = (targetpath, "proj", "src", cfile)
+        os.makedirs(UF.get_cfile_fnpath(*args, name))
+        # A function directory without function file
+        os.makedirs(UF.get_cfile_fnpath(*args, "unused"))
+        decls = DECLARATIONS.format(
+            vid=vid, vtype=vtype, name=name, cfile=cfile)
+        cdict = fixture_tree(typ_table=TYPES)
+        cfilenode = cdict.find("cfile")
+        assert cfilenode is not None
+        cfilenode.append(ET.fromstring(decls))
+        write_tree(cdict, UF.get_cfile_dictionaryname(*args))
+        with open(UF.get_cfile_cfile(*args), "w") as fp:
+            fp.write(CFILE)
+        with open(UF.get_cfun_filename(*args, name), "w") as fp:
+            fp.write(CFUN.format(name=name))
+        write_xrefs(targetpath, cfile, vid, 10 * vid)
+
+
+def write_xrefs(targetpath: str, cfile: str, vid: int, gvid: int) -> None:
+    """Write the global xrefs of cfile in proj, linking vid to gvid."""
+    filename = UF.get_cxreffile_filename(targetpath, "proj", "src", cfile)
+    with open(filename, "w") as fp:
+        fp.write(XREFS.format(vid=vid, gvid=gvid))
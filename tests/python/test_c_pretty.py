@@ -0,0 +1,64 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""C source text of expressions."""
+
+import unittest
+
+from fixtures import load_cdict
+
+CONSTANTS = (
+    '<n ix="1" t="int,42,iint"/>'
+    '<n a="1" ix="2" t="str"/>'
+    '<n a="2" ix="3" t="str"/>')
+
+EXPS = (
+    '<n a="1" ix="1" t="const"/>'
+    '<n a="2" ix="2" t="const"/>'
+    '<n a="3" ix="3" t="const"/>')
+
+STRINGS = (
+    '<n ix="1" v="hello"/>'
+    '<n hex="yes" ix="2" v="0031c4800132"/>')
+
+
+class TestCPretty(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.cdict = load_cdict(
+            constant_table=CONSTANTS, exp_table=EXPS, string_table=STRINGS)
+
+    def test_constant(self) -> None:
+        self.assertEqual(self.cdict.get_exp(1).to_c(), "42")
+
+    def test_string_literal(self) -> None:
+        self.assertEqual(self.cdict.get_exp(2).to_c(), '"hello"')
+        self.assertEqual(
+            self.cdict.get_exp(3).to_c(), '"\\0001\\304\\200\\0012"')
+
+
+if __name__ == "__main__":
+    unittest.main()
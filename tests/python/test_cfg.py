@@ -0,0 +1,92 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Control flow graphs of function bodies."""
+
+import unittest
+import xml.etree.ElementTree as ET
+
+from typing import Dict, List
+
+from chc.app.CCfg import Cfg
+from chc.app.CStmt import CFunctionBody
+from chc.util.fileutil import CHCError
+
+# 2 is the header of a loop with latches 4 and 5; 7 is unreachable and
+# jumps into the loop.
+SUCCS = {1: [2], 2: [3, 6], 3: [4, 5], 4: [2], 5: [2], 6: [], 7: [4]}
+
+
+def function_body(succs: Dict[int, List[int]]) -> CFunctionBody:
+    xbody = ET.Element("sbody")
+    xstmts = ET.SubElement(xbody, "bstmts")
+    for (sid, targets) in succs.items():
+        xstmt = ET.SubElement(xstmts, "stmt", sid=str(sid))
+        ET.SubElement(xstmt, "skind", stag="instr")
+        ET.SubElement(
+            xstmt, "succs", r=",".join(str(target) for target in targets))
+    return CFunctionBody(None, xbody)
+
+
+class TestCfg(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.cfg = Cfg(function_body(SUCCS))
+
+    def test_graph(self) -> None:
+        self.assertEqual(self.cfg.entry, 1)
+        self.assertEqual(self.cfg.nodes, [1, 2, 3, 4, 5, 6, 7])
+        self.assertEqual(self.cfg.predecessors(4), [3, 7])
+
+    def test_unreachable(self) -> None:
+        self.assertEqual(self.cfg.unreachable, [7])
+        self.assertFalse(self.cfg.is_reachable(7))
+        self.assertEqual(self.cfg.dominators(7), [])
+        self.assertIsNone(self.cfg.immediate_dominator(7))
+
+    def test_dominators(self) -> None:
+        self.assertEqual(self.cfg.dominators(5), [1, 2, 3, 5])
+        self.assertEqual(self.cfg.immediate_dominator(6), 2)
+        self.assertIsNone(self.cfg.immediate_dominator(1))
+        self.assertTrue(self.cfg.dominates(2, 4))
+        self.assertFalse(self.cfg.dominates(4, 5))
+
+    def test_loops(self) -> None:
+        [loop] = self.cfg.loops
+        self.assertEqual(loop.header, 2)
+        self.assertEqual(loop.latches, [4, 5])
+        self.assertEqual(loop.body, {2, 3, 4, 5})
+        self.assertFalse(loop.contains(7))
+        self.assertEqual(len(self.cfg.loops_containing(3)), 1)
+        self.assertEqual(self.cfg.loops_containing(6), [])
+
+    def test_unknown_statement(self) -> None:
+        with self.assertRaises(CHCError):
+            self.cfg.successors(8)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,100 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Dictionaries, functions and global varinfos of a c file."""
+
+import shutil
+import tempfile
+import unittest
+from unittest import mock
+
+from chc.app.CApplication import CApplication
+import chc.util.fileutil as UF
+
+from fixtures import write_project
+
+
+class TestCFile(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.targetpath = tempfile.mkdtemp()
+        self.addCleanup(shutil.rmtree, self.targetpath)
+        write_project(self.targetpath)
+        capp = CApplication(
+            self.targetpath, "proj", self.targetpath, self.targetpath)
+        self.cfile = capp.get_file("src/first")
+
+    def test_dictionary_tables(self) -> None:
+        # The declarations are built from the tables read for the
+        # dictionary, not through ElementTree
+        unused = mock.Mock(side_effect=AssertionError("parsed again"))
+        with mock.patch.object(UF, "get_cfile_dictionary_xnode", unused):
+            declarations = self.cfile.declarations
+            dictionary = self.cfile.dictionary
+        self.assertEqual(declarations.get_varinfo(1).vname, "main")
+        self.assertEqual(declarations.get_filename(1), "first.c")
+        self.assertEqual(str(dictionary.get_typ(1)), "int")
+
+    def test_cached(self) -> None:
+        dictionary = self.cfile.dictionary
+        declarations = self.cfile.declarations
+        self.assertIs(self.cfile.dictionary, dictionary)
+        self.assertIs(self.cfile.declarations, declarations)
+        self.assertIs(declarations.cfile, self.cfile)
+
+    def test_reload(self) -> None:
+        dictionary = self.cfile.dictionary
+        declarations = self.cfile.declarations
+        functions = self.cfile.functions
+        self.cfile.reload()
+        self.assertIsNot(self.cfile.dictionary, dictionary)
+        self.assertIsNot(self.cfile.declarations, declarations)
+        self.assertIsNot(self.cfile.functions[1], functions[1])
+        self.assertEqual(self.cfile.declarations.get_varinfo(1).vname, "main")
+
+    def test_functions(self) -> None:
+        self.assertEqual(list(self.cfile.functions), [1])
+        self.assertEqual(self.cfile.functionnames, ["main"])
+        self.assertEqual(self.cfile.functionxref, {"main": 1})
+        main = self.cfile.get_function_by_name("main")
+        self.assertEqual(main.name, "main")
+        self.assertIs(self.cfile.get_function_by_index(1), main)
+        self.assertIs(self.cfile.functions[1], main)
+        self.assertFalse(self.cfile.has_function_by_name("helper"))
+        self.assertFalse(self.cfile.has_function_by_index(2))
+
+    def test_global_varinfos(self) -> None:
+        self.assertTrue(self.cfile.has_global_varinfo(1))
+        self.assertFalse(self.cfile.has_global_varinfo(2))
+        vinfo = self.cfile.get_global_varinfo_by_name("main")
+        self.assertEqual(vinfo.vid, 1)
+        self.assertIs(self.cfile.get_global_varinfo(1), vinfo)
+        with self.assertRaises(UF.CHCError):
+            self.cfile.get_global_varinfo_by_name("helper")
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,133 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Round-trips of dictionary tables through the binary snapshot cache."""
+
+import os
+import shutil
+import tempfile
+import unittest
+
+from typing import Dict, List, Tuple
+
+import chc_rust
+
+FIXTURES = os.path.join(os.path.dirname(__file__), "fixtures")
+
+DX = chc_rust.util.dictionary_xml
+DC = chc_rust.util.dictionary_cache
+
+
+def contents(tables) -> Dict[str, List[Tuple[int, List[str], List[int]]]]:
+    result = {}
+    for name in tables.table_names:
+        if tables.table(name) is not None:
+            result[name] = [
+                (v.index, list(v.tags), list(v.args))
+                for v in tables.table(name)]
+        else:
+            result[name] = tables.string_table(name)
+    return result
+
+
+class TestDictionaryCache(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.tmpdir = tempfile.mkdtemp()
+        self.cachedir = os.path.join(self.tmpdir, "cache")
+        self.xmlfile = os.path.join(self.tmpdir, "small_cdict.xml")
+        shutil.copy(os.path.join(FIXTURES, "small_cdict.xml"), self.xmlfile)
+
+    def tearDown(self) -> None:
+        shutil.rmtree(self.tmpdir)
+
+    def snapshot(self) -> bytes:
+        (name,) = os.listdir(self.cachedir)
+        with open(os.path.join(self.cachedir, name), "rb") as fp:
+            return fp.read()
+
+    def test_string_table_hex(self) -> None:
+        tables = DX.read_xml_tables(self.xmlfile)
+        self.assertEqual(
+            tables.string_table("string-table"),
+            [(1, "hello"), (2, "h\xc3\xa9llo\n")])
+
+    def test_empty_tables(self) -> None:
+        tables = DX.read_xml_tables(self.xmlfile)
+        self.assertTrue(tables.has_table("attrparam-table"))
+        self.assertEqual(tables.table("attrparam-table"), [])
+        self.assertEqual(tables.string_table("attrparam-table"), [])
+        self.assertIsNone(tables.table("string-table"))
+        cached = DC.read_xml_tables_cached(self.xmlfile, self.cachedir)
+        self.assertEqual(cached.table("attrparam-table"), [])
+
+    def test_round_trip(self) -> None:
+        expected = contents(DX.read_xml_tables(self.xmlfile))
+        first = DC.read_xml_tables_cached(self.xmlfile, self.cachedir)
+        self.assertEqual(contents(first), expected)
+        snapshot = self.snapshot()
+        second = DC.read_xml_tables_cached(self.xmlfile, self.cachedir)
+        self.assertEqual(contents(second), expected)
+        self.assertEqual(self.snapshot(), snapshot)
+
+    def test_changed_contents(self) -> None:
+        DC.read_xml_tables_cached(self.xmlfile, self.cachedir)
+        with open(self.xmlfile) as fp:
+            text = fp.read()
+        with open(self.xmlfile, "w") as fp:
+            fp.write(text.replace('t="int,42,iint"', 't="int,43,iint"'))
+        tables = DC.read_xml_tables_cached(self.xmlfile, self.cachedir)
+        self.assertEqual(
+            contents(tables), contents(DX.read_xml_tables(self.xmlfile)))
+        self.assertEqual(
+            list(tables.table("constant-table")[0].tags),
+            ["int", "43", "iint"])
+
+    def test_touched_file_restamps_snapshot(self) -> None:
+        DC.read_xml_tables_cached(self.xmlfile, self.cachedir)
+        snapshot = self.snapshot()
+        stat = os.stat(self.xmlfile)
+        os.utime(self.xmlfile, ns=(stat.st_atime_ns, stat.st_mtime_ns + 10**9))
+        tables = DC.read_xml_tables_cached(self.xmlfile, self.cachedir)
+        self.assertEqual(
+            contents(tables), contents(DX.read_xml_tables(self.xmlfile)))
+        restamped = self.snapshot()
+        self.assertNotEqual(restamped, snapshot)
+        DC.read_xml_tables_cached(self.xmlfile, self.cachedir)
+        self.assertEqual(self.snapshot(), restamped)
+
+    def test_corrupt_snapshot(self) -> None:
+        DC.read_xml_tables_cached(self.xmlfile, self.cachedir)
+        (name,) = os.listdir(self.cachedir)
+        with open(os.path.join(self.cachedir, name), "r+b") as fp:
+            fp.truncate(40)
+        tables = DC.read_xml_tables_cached(self.xmlfile, self.cachedir)
+        self.assertEqual(
+            contents(tables), contents(DX.read_xml_tables(self.xmlfile)))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,54 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Records read from dictionary xml files with tags that are not known."""
+
+import unittest
+
+from chc.util.fileutil import CHCUnknownTagError
+
+from fixtures import load_cdict
+
+TYPES = (
+    '<n a="1" ix="1" t="tint,iint"/>'
+    '<n ix="2" t="tunknown"/>')
+
+
+class TestDictionaryReader(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.cdict = load_cdict(typ_table=TYPES)
+
+    def test_unknown_tag(self) -> None:
+        with self.assertRaises(CHCUnknownTagError):
+            self.cdict.get_typ(2)
+
+    def test_known_tags_are_unaffected(self) -> None:
+        self.assertEqual(str(self.cdict.get_typ(1)), "int")
+
+
+if __name__ == "__main__":
+    unittest.main()
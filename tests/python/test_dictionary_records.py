@@ -0,0 +1,65 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Records of the cdictionary that are constructed in rust."""
+
+import unittest
+
+from fixtures import load_cdict
+
+ATTRPARAMS = (
+    '<n a="4" ix="1" t="aint"/>'
+    '<n a="1" ix="2" t="asizeof"/>'
+    '<n a="1,2" ix="3" t="abinop,+"/>'
+    '<n a="4" ix="4" t="astar"/>'
+    '<n a="1,2,3" ix="5" t="aquestion"/>'
+    '<n a="1" ix="6" t="adot,f"/>')
+
+
+class TestDictionaryRecords(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.cdict = load_cdict(attrparam_table=ATTRPARAMS)
+
+    def test_attrparams(self) -> None:
+        attrs = [self.cdict.get_attrparam(ix) for ix in range(1, 7)]
+        self.assertEqual(
+            [str(a) for a in attrs],
+            ["aint(4)",
+             "asizeof(int)",
+             "abinop(aint(4) + asizeof(int))",
+             "astar()",
+             "aquestion(aint(4),asizeof(int),abinop(aint(4) + asizeof(int)))",
+             "adot(aint(4).f)"])
+        self.assertTrue(attrs[1].is_sizeof)
+        self.assertEqual(attrs[2].op, "+")
+        self.assertEqual(attrs[3].param.index, 4)
+        self.assertTrue(attrs[4].is_question)
+        self.assertEqual(attrs[5].suffix, "f")
+
+
+if __name__ == "__main__":
+    unittest.main()
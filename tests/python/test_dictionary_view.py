@@ -0,0 +1,84 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Read-only views of loaded dictionaries."""
+
+import unittest
+
+from concurrent.futures import ThreadPoolExecutor
+
+from chc.util.IndexedTable import IndexedTableValue
+
+from fixtures import load_cdict
+
+
+class TestDictionaryView(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.cdict = load_cdict()
+        self.view = self.cdict.read_only_view()
+
+    def test_reads_match_dictionary(self) -> None:
+        self.assertEqual(
+            self.view.table_names, [t.name for t in self.cdict.tables])
+        for table in self.cdict.tables:
+            self.assertEqual(self.view.size(table.name), table.size())
+            for (ix, value) in table.items():
+                viewed = self.view.retrieve(table.name, ix)
+                self.assertEqual(
+                    (viewed.tags, viewed.args), (value.tags, value.args))
+                self.assertEqual(
+                    self.view.find(table.name, value.tags, value.args), ix)
+        for (ix, s) in self.cdict.string_table.entries():
+            self.assertEqual(self.view.get_string(ix), s)
+
+    def test_cannot_mutate(self) -> None:
+        with self.assertRaises(AttributeError):
+            self.view.add_tags_args  # type: ignore
+        with self.assertRaises(AttributeError):
+            self.view.table_names = []  # type: ignore
+        with self.assertRaises(AttributeError):
+            self.view.size = None  # type: ignore
+
+    def test_later_changes_are_not_seen(self) -> None:
+        size = self.view.size("exp-table")
+        self.cdict.exp_table.add_tags_args(
+            ["const"], [2, 1],
+            lambda ix, tags, args: IndexedTableValue(ix, tags, args))
+        self.assertEqual(self.cdict.exp_table.size(), size + 1)
+        self.assertEqual(self.view.size("exp-table"), size)
+        self.assertEqual(
+            self.cdict.read_only_view().size("exp-table"), size + 1)
+
+    def test_reads_from_threads(self) -> None:
+        with ThreadPoolExecutor(max_workers=4) as pool:
+            tags = list(pool.map(
+                lambda ix: self.view.retrieve("exp-table", ix).tags, [1, 2]))
+        self.assertEqual(tags, [["const"], ["const"]])
+
+
+if __name__ == "__main__":
+    unittest.main()
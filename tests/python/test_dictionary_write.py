@@ -0,0 +1,72 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Dictionary tables written back to xml by the Rust writer."""
+
+import unittest
+import xml.etree.ElementTree as ET
+
+import chc.util.StringIndexedTable as SI
+
+from fixtures import load_cdict
+
+STRINGS = (
+    '<n ix="1" v="hello"/>'
+    '<n hex="yes" ix="2" v="68c3a96c6c6f0a"/>'
+    '<n hex="yes" ix="3" v="ff0a"/>')
+
+
+class TestDictionaryWrite(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.cdict = load_cdict(string_table=STRINGS)
+
+    def written_strings(self) -> ET.Element:
+        text = self.cdict.write_xml_text(
+            "c-dictionary", self.cdict.string_table.entries())
+        strings = ET.fromstring(text).find("string-table")
+        assert strings is not None
+        return strings
+
+    def test_non_utf8_hex_string(self) -> None:
+        self.assertEqual(self.cdict.string_table.retrieve(3), "\xff\n")
+        written = {
+            n.get("ix"): (n.get("hex"), n.get("v"))
+            for n in self.written_strings()}
+        self.assertEqual(written["3"], ("yes", "ff0a"))
+        self.assertEqual(written["2"], ("yes", "68c3a96c6c6f0a"))
+        self.assertEqual(written["1"], (None, "hello"))
+
+    def test_agrees_with_string_indexed_table(self) -> None:
+        for n in self.written_strings():
+            s = self.cdict.string_table.retrieve(int(n.get("ix", "-1")))
+            (ishex, encoded) = SI.encode(s)
+            self.assertEqual(n.get("hex") == "yes", ishex)
+            self.assertEqual(n.get("v"), encoded)
+
+
+if __name__ == "__main__":
+    unittest.main()
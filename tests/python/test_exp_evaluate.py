@@ -0,0 +1,99 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Evaluation of integer constant expressions."""
+
+import unittest
+
+from chc.util.fileutil import CHCError
+
+import chc_rust
+
+from fixtures import load_cdict
+
+CDataModel = chc_rust.app.c_data_model.CDataModel
+
+CONSTANTS = (
+    '<n ix="1" t="int,42,iint"/>'
+    '<n a="1" ix="2" t="str"/>'
+    '<n ix="3" t="int,18446744073709551615,iulonglong"/>'
+    '<n ix="4" t="int,2147483647,iint"/>'
+    '<n ix="5" t="int,-3,iint"/>')
+
+EXPS = (
+    '<n a="1" ix="1" t="const"/>'
+    '<n a="2" ix="2" t="const"/>'
+    '<n a="3" ix="3" t="const"/>'
+    '<n a="3,3,3" ix="4" t="binop,mult"/>'
+    '<n a="4" ix="5" t="const"/>'
+    '<n a="5,5,1" ix="6" t="binop,mult"/>'
+    '<n a="2" ix="7" t="sizeof"/>'
+    '<n a="1,7,1" ix="8" t="binop,plusa"/>'
+    '<n a="5" ix="9" t="const"/>'
+    '<n a="4" ix="10" t="sizeof"/>'
+    '<n a="5" ix="11" t="sizeof"/>')
+
+TYPES = (
+    '<n a="1" ix="1" t="tint,iint"/>'
+    '<n a="1,1" ix="2" t="tptr"/>'
+    '<n ix="3" t="tint,iulonglong"/>'
+    '<n a="1,7" ix="4" t="tarray"/>'
+    '<n a="1,9" ix="5" t="tarray"/>')
+
+
+class TestExpEvaluate(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.cdict = load_cdict(
+            constant_table=CONSTANTS, exp_table=EXPS, typ_table=TYPES)
+
+    def test_constants(self) -> None:
+        self.assertEqual(self.cdict.get_exp(1).evaluate(), 42)
+        self.assertEqual(self.cdict.get_exp(9).evaluate(), -3)
+        self.assertIsNone(self.cdict.get_exp(2).evaluate())
+
+    def test_unsigned_multiplication_wraps(self) -> None:
+        self.assertEqual(self.cdict.get_exp(4).evaluate(), 1)
+
+    def test_signed_overflow(self) -> None:
+        self.assertIsNone(self.cdict.get_exp(6).evaluate())
+
+    def test_sizeof_depends_on_model(self) -> None:
+        self.assertEqual(self.cdict.get_exp(8).evaluate(CDataModel.ilp32()), 46)
+        self.assertEqual(self.cdict.get_exp(8).evaluate(CDataModel.lp64()), 50)
+
+    def test_array_length_in_model(self) -> None:
+        exp = self.cdict.get_exp(10)
+        self.assertEqual(exp.evaluate(CDataModel.ilp32()), 16)
+        self.assertEqual(exp.evaluate(CDataModel.lp64()), 32)
+
+    def test_negative_array_length(self) -> None:
+        with self.assertRaises(CHCError):
+            self.cdict.get_exp(11).evaluate()
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,58 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Expression records of the cdictionary."""
+
+import unittest
+
+from chc.util.IndexedTable import IndexedTableError
+
+from fixtures import load_cdict
+
+EXPS = (
+    '<n a="1" ix="1" t="const"/>'
+    '<n a="2" ix="2" t="const"/>'
+    '<n a="1,1,2" ix="3" t="question"/>'
+    '<n a="1,1,2,1" ix="4" t="question"/>')
+
+
+class TestExpRecords(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.cdict = load_cdict(exp_table=EXPS)
+
+    def test_question(self) -> None:
+        exp = self.cdict.get_exp(4)
+        self.assertTrue(exp.is_question)
+        self.assertEqual(exp.condition.index, 1)
+
+    def test_short_record(self) -> None:
+        with self.assertRaises(IndexedTableError):
+            self.cdict.get_exp(3)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,64 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Normalisation of casts in expressions."""
+
+import unittest
+
+from fixtures import StubCFile, load_cdict
+
+TYPES = (
+    '<n a="1" ix="1" t="tint,iint"/>'
+    '<n ix="2" t="tint,iuchar"/>'
+    '<n a="1" ix="3" t="tenum,color"/>'
+    '<n ix="4" t="tnamed,myint"/>')
+
+EXPS = (
+    '<n a="1" ix="1" t="const"/>'
+    '<n a="1,1,2" ix="2" t="binop,plusa"/>'
+    '<n a="3,2" ix="3" t="caste"/>'
+    '<n a="1,1,1" ix="4" t="binop,plusa"/>'
+    '<n a="4,4" ix="5" t="caste"/>')
+
+
+class TestExpRewrite(unittest.TestCase):
+
+    def setUp(self) -> None:
+        cfile = StubCFile(
+            typeinfo_table='<n a="1" ix="1" t="myint"/>',
+            enuminfo_table='<n a="1" ix="1" t="color,iuchar"/>')
+        self.cdict = load_cdict(cfile=cfile, exp_table=EXPS, typ_table=TYPES)
+
+    def test_cast_to_compatible_type_is_kept(self) -> None:
+        # enum color has integer kind iuchar: compatible, but not the same type
+        self.assertEqual(self.cdict.get_exp(3).normalize().index, 3)
+
+    def test_cast_to_typedef_of_same_type_is_removed(self) -> None:
+        self.assertEqual(self.cdict.get_exp(5).normalize().index, 4)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,89 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Statistics of function bodies."""
+
+import unittest
+import xml.etree.ElementTree as ET
+
+from chc.app.CFunction import CFunction
+
+FUNCTION = """
+<function>
+  <sbody>
+    <bstmts>
+      <stmt sid="1">
+        <skind stag="instr">
+          <instrs><instr itag="call"/><instr itag="set"/></instrs>
+        </skind>
+        <succs r="2"/>
+      </stmt>
+      <stmt sid="2">
+        <skind stag="if">
+          <thenblock>
+            <bstmts>
+              <stmt sid="3">
+                <skind stag="instr">
+                  <instrs><instr itag="call"/></instrs>
+                </skind>
+                <succs r="4"/>
+              </stmt>
+            </bstmts>
+          </thenblock>
+          <elseblock><bstmts/></elseblock>
+        </skind>
+        <succs r="3,4"/>
+      </stmt>
+      <stmt sid="4"><skind stag="return"/></stmt>
+    </bstmts>
+  </sbody>
+</function>
+"""
+
+
+class TestFunction(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.cfun = CFunction(None, ET.fromstring(FUNCTION), "f")
+
+    def test_counts(self) -> None:
+        self.assertEqual(self.cfun.stmt_count, 5)
+        self.assertEqual(self.cfun.block_count, 1)
+        self.assertEqual(self.cfun.instr_count, 3)
+        self.assertEqual(len(self.cfun.call_instrs), 2)
+
+    def test_counts_agree_with_body(self) -> None:
+        sbody = self.cfun.sbody
+        self.assertEqual(self.cfun.stmt_count, sbody.stmt_count)
+        self.assertEqual(self.cfun.block_count, sbody.block_count)
+        self.assertEqual(self.cfun.instr_count, sbody.instr_count)
+        self.assertEqual(
+            [instr.parent.sid for instr in self.cfun.call_instrs],
+            [instr.parent.sid for instr in sbody.call_instrs])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,128 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Round-trips of dictionary tables through the binary snapshot cache."""
+"""Checkpoints and keys of IndexedTable."""
+
+import unittest
+import xml.etree.ElementTree as ET
+
+from typing import List, Tuple
+
+from chc.util.IndexedTable import IndexedTable, IndexedTableValue, get_key
+
+
+def mk_value(index: int, key: Tuple[str, str]) -> IndexedTableValue:
+    (tags, args) = key
+    return IndexedTableValue(
+        index,
+        tags.split(",") if tags else [],
+        [int(a) for a in args.split(",") if a.lstrip("-").isdigit()])
+
+
+def read_entries(table: IndexedTable, entries: List[str]) -> None:
+    node = ET.fromstring("<t>" + "".join(entries) + "</t>")
+    table.read_xml(node, "n")
+
+
+class TestCheckpoints(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.table = IndexedTable("test-table")
+        self.table.add(("a", "1"), mk_value)
+        self.table.add(("b", "2"), mk_value)
+
+    def test_rollback_removes_additions(self) -> None:
+        self.assertEqual(self.table.begin("x"), 3)
+        self.table.add(("c", "3"), mk_value)
+        self.assertEqual(self.table.rollback("x"), 3)
+        self.assertEqual(self.table.size(), 2)
+        self.assertEqual(self.table.add(("c", "3"), mk_value), 3)
+
+    def test_rollback_restores_remapped_keys(self) -> None:
+        self.table.begin("x")
+        read_entries(self.table, ['<n a="1" ix="2" t="a"/>'])
+        self.assertEqual(self.table.add(("a", "1"), mk_value), 2)
+        self.table.rollback("x")
+        self.assertEqual(self.table.add(("a", "1"), mk_value), 1)
+        self.assertEqual(list(self.table.retrieve(2).tags), ["b"])
+        self.assertEqual(self.table.size(), 2)
+
+    def test_nested_commit_then_rollback(self) -> None:
+        self.table.begin("outer")
+        self.table.begin("inner")
+        self.table.add(("c", "3"), mk_value)
+        self.table.commit("inner")
+        self.assertEqual(self.table.checkpoints, ["outer"])
+        self.table.rollback("outer")
+        self.assertEqual(self.table.size(), 2)
+        self.assertEqual(self.table.checkpoints, [])
+
+    def test_rollback_restores_reserved(self) -> None:
+        self.table.begin("x")
+        ix = self.table.reserve()
+        self.table.commit_reserved(ix, ("c", "3"), mk_value(ix, ("c", "3")))
+        self.table.rollback("x")
+        self.assertEqual(self.table.size(), 2)
+        with self.assertRaises(Exception):
+            self.table.retrieve(ix)
+
+    def test_legacy_checkpoint_is_separate(self) -> None:
+        self.assertEqual(self.table.set_checkpoint(), 3)
+        self.assertEqual(self.table.begin("checkpoint"), 3)
+        self.table.add(("c", "3"), mk_value)
+        self.table.commit("checkpoint")
+        self.assertEqual(self.table.reset_to_checkpoint(), 3)
+        self.assertEqual(self.table.size(), 2)
+
+
+class TestKeys(unittest.TestCase):
+
+    def test_get_key(self) -> None:
+        self.assertEqual(
+            get_key(["tint", "iint"], [1, -2]), ("tint,iint", "1,-2"))
+        self.assertEqual(get_key([], []), ("", ""))
+
+    def test_string_and_parts_agree(self) -> None:
+        table = IndexedTable("test-table")
+        ix = table.add(("tptr", "3,4"), mk_value)
+        def f(
+                index: int, tags: List[str], args: List[int]
+        ) -> IndexedTableValue:
+            return IndexedTableValue(index, tags, args)
+
+        self.assertEqual(table.add_tags_args(["tptr"], [3, 4], f), ix)
+
+    def test_non_canonical_args_are_distinct(self) -> None:
+        table = IndexedTable("test-table")
+        keys = [("a", "1"), ("a", "01"), ("a", "0"), ("a", "-0"), ("a", "x")]
+        indices = [table.add(key, mk_value) for key in keys]
+        self.assertEqual(indices, [1, 2, 3, 4, 5])
+        self.assertEqual([table.add(key, mk_value) for key in keys], indices)
+
+
+if __name__ == "__main__":
+    unittest.main()
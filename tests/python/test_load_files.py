@@ -0,0 +1,157 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Loading all files of an application at once."""
+
+import os
+import shutil
+import tempfile
+import unittest
+from unittest import mock
+
+from chc.app.CApplication import CApplication
+from chc.app.IndexManager import FileVarReference
+import chc.util.fileutil as UF
+
+from fixtures import write_project, write_xrefs
+
+
+class TestLoadFiles(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.targetpath = tempfile.mkdtemp()
+        self.addCleanup(shutil.rmtree, self.targetpath)
+        write_project(self.targetpath)
+        self.capp = CApplication(
+            self.targetpath, "proj", self.targetpath, self.targetpath)
+
+    def write_xrefs(self, cfile: str, vid: int, gvid: int) -> None:
+        write_xrefs(self.targetpath, cfile, vid, gvid)
+
+    def test_load_files(self) -> None:
+        # All files are parsed ahead, none through ElementTree
+        unused = mock.Mock(side_effect=AssertionError("parsed again"))
+        with mock.patch.multiple(
+                UF,
+                get_cfile_dictionary_xnode=unused,
+                get_cfile_xnode=unused,
+                get_cfun_xnode=unused):
+            # Each file is larger than the budget, so parsed by itself
+            files = self.capp.load_files(workers=2, memory_budget=1)
+        self.assertEqual(sorted(files), [1, 2])
+        self.assertEqual(files[1].name, "src/first")
+        self.assertEqual(
+            files[1].declarations.get_varinfo(1).vname, "main")
+        self.assertEqual(files[2].declarations.get_filename(1), "second.c")
+        self.assertEqual(
+            [f.name for f in files[2].functions.values()], ["helper"])
+        self.assertEqual(list(files[1].functions), [1])
+
+    def test_invalid_function_file(self) -> None:
+        filename = UF.get_cfun_filename(
+            self.targetpath, "proj", "src", "second", "helper")
+        with open(filename, "w") as fp:
+            fp.write("<c-analysis><function>")
+        with self.assertRaises(UF.CHCXmlParseError):
+            self.capp.load_files()
+
+    def test_invalid_file_stops_workers(self) -> None:
+        filename = UF.get_cfun_filename(
+            self.targetpath, "proj", "src", "first", "main")
+        with open(filename, "w") as fp:
+            fp.write("<c-analysis><function>")
+        # The second worker waits for room until the first file fails
+        with self.assertRaises(UF.CHCXmlParseError):
+            self.capp.load_files(workers=2, memory_budget=1)
+
+    def test_touched_file_unchanged(self) -> None:
+        self.capp.load_files()
+        filename = UF.get_cfun_filename(
+            self.targetpath, "proj", "src", "first", "main")
+        stat = os.stat(filename)
+        os.utime(filename, (stat.st_atime + 10, stat.st_mtime + 10))
+        self.assertEqual(self.capp.changed_files(), [])
+
+    def test_reload_changed_file(self) -> None:
+        self.capp.load_files()
+        indexmanager = self.capp.indexmanager
+        self.assertEqual(indexmanager.gviddefs, {10: 1, 20: 2})
+        self.write_xrefs("first", 1, 30)
+        self.assertEqual(self.capp.reload_changed_files(), [1])
+        self.assertEqual(indexmanager.vid2gvid[1], {1: 30})
+        self.assertNotIn(1, indexmanager.gvid2vid.get(10, {}))
+        self.assertEqual(indexmanager.gvid2vid[30], {1: 1})
+        self.assertEqual(indexmanager.gviddefs, {20: 2, 30: 1})
+
+    def test_failed_reload_stays_changed(self) -> None:
+        self.capp.load_files()
+        filename = UF.get_cxreffile_filename(
+            self.targetpath, "proj", "src", "first")
+        with open(filename, "w") as fp:
+            fp.write("<c-analysis>")
+        with self.assertRaises(UF.CHCXmlParseError):
+            self.capp.reload_changed_files()
+        self.assertEqual(self.capp.changed_files(), [1])
+        self.write_xrefs("first", 1, 30)
+        self.assertEqual(self.capp.reload_changed_files(), [1])
+        self.assertEqual(self.capp.changed_files(), [])
+
+    def test_touched_file_without_hashes(self) -> None:
+        # Files opened one at a time are recorded without hashes
+        self.assertEqual(sorted(self.capp.files), [1, 2])
+        filename = UF.get_cfun_filename(
+            self.targetpath, "proj", "src", "first", "main")
+        stat = os.stat(filename)
+        os.utime(filename, (stat.st_atime + 10, stat.st_mtime + 10))
+        self.assertEqual(self.capp.changed_files(), [1])
+        self.assertEqual(self.capp.reload_changed_files(), [1])
+        os.utime(filename, (stat.st_atime + 20, stat.st_mtime + 20))
+        self.assertEqual(self.capp.changed_files(), [])
+
+    def test_symbol_table(self) -> None:
+        self.capp.load_files()
+        table = self.capp.symboltable
+        self.assertEqual(table.names, ["helper", "main"])
+        [main] = table.lookup("main")
+        self.assertTrue(main.is_function)
+        self.assertEqual(
+            [r.tuple for r in table.get_definitions("main")], [(1, 1)])
+        self.assertEqual(table.undefined_externals(), [])
+        self.assertIs(self.capp.symboltable, table)
+
+    def test_symbol_table_follows_index_manager(self) -> None:
+        self.capp.load_files()
+        table = self.capp.symboltable
+        # the linker finds that vid 7 of the first file is helper
+        self.capp.indexmanager.add_vid2gvid(FileVarReference(1, 7), 20)
+        self.assertFalse(table.is_current)
+        table = self.capp.symboltable
+        self.assertTrue(table.is_current)
+        self.assertEqual(table.resolve_vid(FileVarReference(1, 7)).tuple, (2, 2))
+
+
+if __name__ == "__main__":
+    unittest.main()
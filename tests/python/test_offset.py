@@ -0,0 +1,102 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Byte offsets of index and field offsets."""
+
+import unittest
+
+from typing import Any, Optional
+
+import chc_rust
+
+from fixtures import StubCFile, load_cdict
+
+CDataModel = chc_rust.app.c_data_model.CDataModel
+
+CONSTANTS = (
+    '<n ix="1" t="int,3,iint"/>'
+    '<n ix="2" t="int,4611686018427387904,iulonglong"/>')
+
+EXPS = (
+    '<n a="1" ix="1" t="const"/>'
+    '<n a="2" ix="2" t="const"/>')
+
+# long long[3], and struct s { int x; long long y[3]; }
+TYPES = (
+    '<n a="1" ix="1" t="tint,iint"/>'
+    '<n a="1" ix="2" t="tint,ilonglong"/>'
+    '<n a="2,1,1" ix="3" t="tarray"/>'
+    '<n a="1,1" ix="4" t="tcomp"/>')
+
+OFFSETS = (
+    '<n ix="1" t="n"/>'
+    '<n a="1,1" ix="2" t="i"/>'
+    '<n a="2,1" ix="3" t="i"/>'
+    '<n a="1,2" ix="4" t="f,y"/>'
+    '<n a="1,3" ix="5" t="f,y"/>')
+
+FIELDS = (
+    '<n a="1,1,-1,-1,-1" ix="1" t="x"/>'
+    '<n a="1,3,-1,-1,-1" ix="2" t="y"/>')
+
+
+class StructCFile(StubCFile):
+
+    def get_compinfo_by_ckey(self, ckey: int) -> Any:
+        return self.declarations.get_compinfo(ckey)
+
+
+class TestOffset(unittest.TestCase):
+
+    def setUp(self) -> None:
+        cfile = StructCFile(
+            fieldinfo_table=FIELDS,
+            compinfo_table='<n a="1,1,1,1,2" ix="1" t="s"/>')
+        self.cdict = load_cdict(
+            cfile=cfile,
+            constant_table=CONSTANTS,
+            exp_table=EXPS,
+            typ_table=TYPES,
+            offset_table=OFFSETS)
+
+    def resolve(self, offset: int, typ: int) -> Optional[int]:
+        return self.cdict.get_offset(offset).resolve(
+            self.cdict.get_typ(typ), CDataModel.lp64())
+
+    def test_index(self) -> None:
+        self.assertEqual(self.resolve(1, 3), 0)
+        self.assertEqual(self.resolve(2, 3), 24)
+
+    def test_index_overflow(self) -> None:
+        self.assertIsNone(self.resolve(3, 3))
+
+    def test_field(self) -> None:
+        self.assertEqual(self.resolve(4, 4), 32)
+        self.assertIsNone(self.resolve(5, 4))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,78 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Integrity checks of dictionary tables."""
+
+import unittest
+
+from fixtures import load_cdict
+
+EXPS = (
+    '<n a="1" ix="1" t="const"/>'
+    '<n a="2" ix="2" t="const"/>'
+    '<n a="9" ix="3" t="const"/>'
+    '<n a="1,1" ix="4" t="binop,plusa"/>'
+    '<n a="1,2,1" ix="5" t="binop,plusa"/>')
+
+TYPES = (
+    '<n a="1" ix="1" t="tint,iint"/>'
+    '<n a="1,1" ix="2" t="tptr"/>'
+    '<n a="1,-1,0" ix="3" t="tfun"/>'
+    '<n ix="4" t="tint,iuchar"/>')
+
+
+class TestTableCheck(unittest.TestCase):
+
+    def test_fixture_is_ok(self) -> None:
+        report = load_cdict().check_integrity()
+        self.assertTrue(report.is_ok, str(report))
+        self.assertIn("exp-table", report.tables)
+
+    def test_dangling_arg(self) -> None:
+        report = load_cdict(exp_table=EXPS).check_integrity()
+        (issue,) = report.select(kind="dangling-arg")
+        self.assertEqual((issue.table, issue.index), ("exp-table", 3))
+        self.assertIn("constant-table", issue.message)
+
+    def test_dangling_string(self) -> None:
+        report = load_cdict(string_table='<n ix="2" v="x"/>').check_integrity()
+        (issue,) = report.select(kind="dangling-arg")
+        self.assertEqual((issue.table, issue.index), ("constant-table", 2))
+
+    def test_too_few_args(self) -> None:
+        report = load_cdict(exp_table=EXPS).check_integrity()
+        (issue,) = report.select(kind="too-few-args")
+        self.assertEqual((issue.table, issue.index), ("exp-table", 4))
+        self.assertEqual(
+            report.counts(), {"dangling-arg": 1, "too-few-args": 1})
+
+    def test_attributes_may_be_omitted(self) -> None:
+        report = load_cdict(typ_table=TYPES).check_integrity()
+        self.assertTrue(report.is_ok, str(report))
+
+
+if __name__ == "__main__":
+    unittest.main()
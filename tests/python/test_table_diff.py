@@ -0,0 +1,90 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Structural diffs of dictionaries read from fixture files."""
+
+import unittest
+
+from typing import Dict
+
+from chc.app.CFileDictionary import CFileDictionary
+
+from fixtures import load_cdict
+
+
+class TestTableDiff(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.old = load_cdict()
+        self.new = load_cdict("small_cdict_renumbered.xml")
+
+    def diffs(self, old: CFileDictionary, new: CFileDictionary) -> Dict:
+        return {d.table: d for d in old.diff_with(new)}
+
+    def test_identical(self) -> None:
+        for diff in self.old.diff_with(load_cdict()):
+            self.assertTrue(diff.is_empty, str(diff))
+
+    def test_renumbered_and_duplicate_entries(self) -> None:
+        diffs = self.diffs(self.old, self.new)
+        constants = diffs["constant-table"]
+        self.assertEqual([ix for (ix, _) in constants.added], [3])
+        self.assertEqual(constants.removed, [])
+        self.assertEqual(
+            [(old, new) for (_, old, new) in constants.reindexed],
+            [(1, 2), (2, 1)])
+        exps = diffs["exp-table"]
+        self.assertEqual([ix for (ix, _) in exps.added], [3])
+        self.assertEqual(exps.removed, [])
+        self.assertEqual(exps.reindexed, [])
+        for name in ["typ-table", "offset-table", "attributes-table"]:
+            self.assertTrue(diffs[name].is_empty, str(diffs[name]))
+
+    def test_duplicates_removed(self) -> None:
+        diffs = self.diffs(self.new, self.old)
+        constants = diffs["constant-table"]
+        self.assertEqual(constants.added, [])
+        self.assertEqual([ix for (ix, _) in constants.removed], [3])
+        self.assertEqual(
+            [(old, new) for (_, old, new) in constants.reindexed],
+            [(1, 2), (2, 1)])
+
+    def test_cycles_do_not_depend_on_numbering(self) -> None:
+        old = load_cdict(
+            attrparam_table=(
+                '<n a="2" ix="1" t="acons,f"/><n a="1" ix="2" t="acons,g"/>'))
+        new = load_cdict(
+            attrparam_table=(
+                '<n a="2" ix="1" t="acons,g"/><n a="1" ix="2" t="acons,f"/>'))
+        diff = self.diffs(old, new)["attrparam-table"]
+        self.assertEqual(diff.added, [])
+        self.assertEqual(diff.removed, [])
+        self.assertEqual(
+            [(old, new) for (_, old, new) in diff.reindexed], [(1, 2), (2, 1)])
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,62 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Function types and their structural comparison."""
+
+import unittest
+
+from fixtures import load_cdict
+
+TYPES = (
+    '<n a="1" ix="1" t="tint,iint"/>'
+    '<n a="1,-1,0" ix="2" t="tfun"/>'
+    '<n a="1,-1,1" ix="3" t="tfun"/>'
+    '<n a="1,-1,0" ix="4" t="tfun"/>')
+
+
+class TestTypCompatibility(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.cdict = load_cdict(typ_table=TYPES)
+
+    def test_function_types(self) -> None:
+        fun = self.cdict.get_typ(2)
+        self.assertTrue(fun.is_function)
+        self.assertFalse(fun.is_vararg)
+        self.assertTrue(fun.is_default_function_prototype)
+        self.assertEqual(fun.return_type.index, 1)
+        self.assertIsNone(fun.funargs)
+        self.assertEqual(str(fun), "(None):int")
+        self.assertTrue(self.cdict.get_typ(3).is_vararg)
+
+    def test_function_compatibility(self) -> None:
+        fun = self.cdict.get_typ(2)
+        self.assertTrue(fun.is_compatible(self.cdict.get_typ(4)))
+        self.assertFalse(fun.is_compatible(self.cdict.get_typ(1)))
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,104 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Expansion of typedefs and enums through the file declarations."""
+
+import unittest
+
+from chc.util.IndexedTable import IndexedTableValue
+
+from fixtures import StubCFile, load_cdict
+
+TYPES = (
+    '<n a="1" ix="1" t="tint,iint"/>'
+    '<n ix="2" t="tint,iuchar"/>'
+    '<n a="1" ix="3" t="tenum,color"/>'
+    '<n a="1" ix="4" t="tenum,shape"/>'
+    '<n ix="5" t="tnamed,myint"/>'
+    '<n a="2" ix="6" t="tnamed,myint"/>')
+
+
+class TestTypExpansion(unittest.TestCase):
+
+    def setUp(self) -> None:
+        cfile = StubCFile(
+            typeinfo_table='<n a="1" ix="1" t="myint"/>',
+            enuminfo_table='<n a="1" ix="1" t="color,iuchar"/>')
+        self.cdict = load_cdict(
+            cfile=cfile,
+            attrparam_table='<n a="8" ix="1" t="aint"/>',
+            attribute_table='<n a="1" ix="1" t="aligned"/>',
+            attributes_table='<n ix="1"/><n a="1" ix="2"/>',
+            typ_table=TYPES)
+
+    def test_enum_ikind(self) -> None:
+        color = self.cdict.get_typ(3)
+        self.assertEqual(color.sizeof(), 1)
+        self.assertTrue(color.is_compatible(self.cdict.get_typ(2)))
+        self.assertFalse(color.is_compatible(self.cdict.get_typ(1)))
+        shape = self.cdict.get_typ(4)
+        self.assertIsNone(shape.size)
+        self.assertFalse(shape.is_compatible(self.cdict.get_typ(1)))
+
+    def test_typedef_expansion(self) -> None:
+        typsize = self.cdict.typ_table.size()
+        attributessize = self.cdict.attributes_table.size()
+        named = self.cdict.get_typ(6)
+        self.assertEqual(named.expand().index, 1)
+        self.assertEqual(named.sizeof(), 4)
+        self.assertEqual(named.alignof(), 8)
+        self.assertEqual(self.cdict.typ_table.size(), typsize)
+        self.assertEqual(self.cdict.attributes_table.size(), attributessize)
+
+    def test_typedef_redefined(self) -> None:
+        self.assertEqual(self.cdict.get_typ(5).expand().index, 1)
+        typeinfo_table = self.cdict.decls.typeinfo_table
+        typeinfo_table.reset()
+        typeinfo_table.add_tags_args(
+            ["myint"], [2],
+            lambda ix, tags, args: IndexedTableValue(ix, tags, args))
+        self.assertEqual(self.cdict.get_typ(5).expand().index, 2)
+        self.assertEqual(self.cdict.get_typ(5).sizeof(), 1)
+
+    def test_expansion_after_new_types(self) -> None:
+        self.assertEqual(self.cdict.get_typ(6).alignof(), 8)
+        self.cdict.typ_table.add_tags_args(
+            ["tint", "ilong"], [1],
+            lambda ix, tags, args: IndexedTableValue(ix, tags, args))
+        self.assertEqual(self.cdict.get_typ(6).expand().index, 1)
+        self.assertEqual(self.cdict.get_typ(6).alignof(), 8)
+
+    def test_enum_redefined(self) -> None:
+        self.assertEqual(self.cdict.get_typ(4).size, None)
+        self.cdict.decls.enuminfo_table.add_tags_args(
+            ["shape", "ishort"], [1],
+            lambda ix, tags, args: IndexedTableValue(ix, tags, args))
+        self.assertEqual(self.cdict.get_typ(4).sizeof(), 2)
+        self.assertEqual(self.cdict.get_typ(3).sizeof(), 1)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,142 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Sizes and alignments of types in a target data model."""
+
+import shutil
+import tempfile
+import unittest
+
+from chc.app.CApplication import CApplication
+from chc.cmdline.AnalysisManager import AnalysisManager
+
+import chc_rust
+
+from fixtures import StubCFile, load_cdict
+
+CDM = chc_rust.app.c_data_model
+
+TYPES = (
+    '<n a="1" ix="1" t="tint,iint"/>'
+    '<n a="2" ix="2" t="tint,iint"/>'
+    '<n a="1,-1,0" ix="3" t="tfun"/>'
+    '<n a="1" ix="4" t="tint,ilong"/>'
+    '<n a="4,1" ix="5" t="tptr"/>')
+
+# char big[2^60], char small[16], and long long huge[2^62]
+CONSTANTS = (
+    '<n ix="1" t="int,42,iint"/>'
+    '<n a="1" ix="2" t="str"/>'
+    '<n ix="3" t="int,1152921504606846976,iulonglong"/>'
+    '<n ix="4" t="int,16,iint"/>'
+    '<n ix="5" t="int,4611686018427387904,iulonglong"/>')
+
+EXPS = (
+    '<n a="1" ix="1" t="const"/>'
+    '<n a="2" ix="2" t="const"/>'
+    '<n a="3" ix="3" t="const"/>'
+    '<n a="4" ix="4" t="const"/>'
+    '<n a="5" ix="5" t="const"/>')
+
+ARRAY_TYPES = (
+    '<n a="1" ix="1" t="tint,iint"/>'
+    '<n a="1" ix="2" t="tint,ichar"/>'
+    '<n a="2,3,1" ix="3" t="tarray"/>'
+    '<n a="2,4,1" ix="4" t="tarray"/>'
+    '<n a="1" ix="5" t="tint,ilonglong"/>'
+    '<n a="5,5,1" ix="6" t="tarray"/>')
+
+FIELDS = (
+    '<n a="1,3,-1,-1,-1" ix="1" t="big"/>'
+    '<n a="2,4,-1,-1,-1" ix="2" t="small"/>')
+
+COMPS = (
+    '<n a="1,1,1,1" ix="1" t="big_s"/>'
+    '<n a="2,1,1,2" ix="2" t="small_s"/>')
+
+
+class TestTypSize(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.cdict = load_cdict(
+            attrparam_table='<n a="8" ix="1" t="aint"/>',
+            attribute_table='<n a="1" ix="1" t="aligned"/>',
+            attributes_table='<n ix="1"/><n a="1" ix="2"/>',
+            typ_table=TYPES)
+
+    def test_int(self) -> None:
+        typ = self.cdict.get_typ(1)
+        self.assertEqual(typ.sizeof(), 4)
+        self.assertEqual(typ.alignof(), 4)
+
+    def test_aligned_type(self) -> None:
+        typ = self.cdict.get_typ(2)
+        self.assertEqual(typ.sizeof(), 4)
+        self.assertEqual(typ.alignof(), 8)
+
+    def test_function_has_no_size(self) -> None:
+        self.assertIsNone(self.cdict.get_typ(3).size)
+
+    def test_analysis_manager_data_model(self) -> None:
+        self.addCleanup(CDM.set_data_model, CDM.data_model())
+        tmpdir = tempfile.mkdtemp()
+        self.addCleanup(shutil.rmtree, tmpdir)
+        capp = CApplication(tmpdir, "proj", tmpdir, tmpdir)
+        AnalysisManager(capp, wordsize=64)
+        self.assertEqual(str(CDM.data_model()), str(CDM.CDataModel.lp64()))
+        self.assertEqual(self.cdict.get_typ(1).sizeof(), 4)
+        self.assertEqual(self.cdict.get_typ(4).sizeof(), 8)
+        self.assertEqual(self.cdict.get_typ(5).sizeof(), 8)
+        AnalysisManager(capp, wordsize=32)
+        self.assertEqual(self.cdict.get_typ(4).sizeof(), 4)
+        self.assertEqual(self.cdict.get_typ(5).sizeof(), 4)
+
+
+class TestTypSizeOverflow(unittest.TestCase):
+
+    def setUp(self) -> None:
+        cfile = StubCFile(fieldinfo_table=FIELDS, compinfo_table=COMPS)
+        self.cdict = load_cdict(
+            cfile=cfile,
+            constant_table=CONSTANTS,
+            exp_table=EXPS,
+            typ_table=ARRAY_TYPES)
+        self.decls = cfile.declarations
+
+    def test_array(self) -> None:
+        self.assertEqual(self.cdict.get_typ(3).size, 2 ** 60)
+        self.assertEqual(self.cdict.get_typ(4).size, 16)
+        self.assertIsNone(self.cdict.get_typ(6).size)
+
+    def test_struct(self) -> None:
+        # 2^60 bytes are 2^63 bits
+        self.assertEqual(self.decls.get_fieldinfo(1).size, 2 ** 60)
+        self.assertIsNone(self.decls.get_compinfo(1).size)
+        self.assertEqual(self.decls.get_compinfo(2).size, 16)
+
+
+if __name__ == "__main__":
+    unittest.main()
@@ -0,0 +1,155 @@
+# ------------------------------------------------------------------------------
+# CodeHawk C Analyzer
+# Author: Henny Sipma
+# ------------------------------------------------------------------------------
+# The MIT License (MIT)
+#
+# Copyright (c) 2023-2024 Aarno Labs LLC
+#
+# Permission is hereby granted, free of charge, to any person obtaining a copy
+# of this software and associated documentation files (the "Software"), to deal
+# in the Software without restriction, including without limitation the rights
+# to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+# copies of the Software, and to permit persons to whom the Software is
+# furnished to do so, subject to the following conditions:
+#
+# The above copyright notice and this permission notice shall be included in all
+# copies or substantial portions of the Software.
+#
+# THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+# IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+# FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+# AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+# LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+# OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+# SOFTWARE.
+# ------------------------------------------------------------------------------
+"""Depth-first traversal of expressions and lvalues."""
+
+import unittest
+
+from typing import Any, List, Tuple
+
+from fixtures import load_cdict
+
+TYPES = (
+    '<n a="1" ix="1" t="tint,iint"/>'
+    '<n a="1,1" ix="2" t="tptr"/>')
+
+LHOSTS = (
+    '<n a="3" ix="1" t="var,p"/>'
+    '<n a="3" ix="2" t="mem"/>')
+
+LVALS = (
+    '<n a="1,1" ix="1"/>'
+    '<n a="2,1" ix="2"/>')
+
+# *(p + 1) + 1
+EXPS = (
+    '<n a="1" ix="1" t="const"/>'
+    '<n a="1" ix="2" t="lval"/>'
+    '<n a="2,1,2" ix="3" t="binop,plusa"/>'
+    '<n a="2" ix="4" t="lval"/>'
+    '<n a="4,1,1" ix="5" t="binop,plusa"/>')
+
+PREORDER = [
+    ("CExpBinOp", 5),
+    ("CExpLval", 4),
+    ("CLval", 2),
+    ("CLHostMem", 2),
+    ("CExpBinOp", 3),
+    ("CExpLval", 2),
+    ("CLval", 1),
+    ("CLHostVar", 1),
+    ("COffset", 1),
+    ("CExpConst", 1),
+    ("CTypPtr", 2),
+    ("CTypInt", 1),
+    ("COffset", 1),
+    ("CExpConst", 1),
+    ("CTypInt", 1)]
+
+POSTORDER = [
+    ("CLHostVar", 1),
+    ("COffset", 1),
+    ("CLval", 1),
+    ("CExpLval", 2),
+    ("CExpConst", 1),
+    ("CTypInt", 1),
+    ("CTypPtr", 2),
+    ("CExpBinOp", 3),
+    ("CLHostMem", 2),
+    ("COffset", 1),
+    ("CLval", 2),
+    ("CExpLval", 4),
+    ("CExpConst", 1),
+    ("CTypInt", 1),
+    ("CExpBinOp", 5)]
+
+
+def node(n: Any) -> Tuple[str, int]:
+    return (type(n).__name__, n.index)
+
+
+class TestVisitor(unittest.TestCase):
+
+    def setUp(self) -> None:
+        self.cdict = load_cdict(
+            constant_table='<n ix="1" t="int,1,iint"/>',
+            typ_table=TYPES,
+            lhost_table=LHOSTS,
+            lval_table=LVALS,
+            exp_table=EXPS)
+        self.events: List[Tuple[str, Tuple[str, int]]] = []
+
+    def enter(self, n: Any) -> None:
+        self.events.append(("enter", node(n)))
+
+    def leave(self, n: Any) -> None:
+        self.events.append(("leave", node(n)))
+
+    def visited(self, event: str) -> List[Tuple[str, int]]:
+        return [n for (e, n) in self.events if e == event]
+
+    def test_order(self) -> None:
+        result = self.cdict.get_exp(5).walk(self.enter, self.leave)
+        self.assertIsNone(result)
+        self.assertEqual(self.visited("enter"), PREORDER)
+        self.assertEqual(self.visited("leave"), POSTORDER)
+        # a node is left after all nodes entered below it
+        self.assertEqual(self.events[:3], [
+            ("enter", ("CExpBinOp", 5)),
+            ("enter", ("CExpLval", 4)),
+            ("enter", ("CLval", 2))])
+        self.assertEqual(self.events[-1], ("leave", ("CExpBinOp", 5)))
+
+    def test_lval(self) -> None:
+        self.cdict.get_lval(2).walk(self.enter)
+        self.assertEqual(self.visited("enter"), PREORDER[2:13])
+
+    def test_stop_on_enter(self) -> None:
+        def is_var(n: Any) -> Any:
+            return n if type(n).__name__ == "CLHostVar" else None
+
+        result = self.cdict.get_exp(5).walk(
+            lambda n: self.enter(n) or is_var(n), self.leave)
+        self.assertEqual(node(result), ("CLHostVar", 1))
+        self.assertEqual(self.visited("enter"), PREORDER[:8])
+        self.assertEqual(self.visited("leave"), [])
+
+    def test_stop_on_leave(self) -> None:
+        result = self.cdict.get_exp(5).walk(
+            self.enter, lambda n: "stop" if node(n) == ("CLval", 1) else None)
+        self.assertEqual(result, "stop")
+        self.assertEqual(self.visited("enter"), PREORDER[:9])
+
+    def test_hook_errors_propagate(self) -> None:
+        def fail(n: Any) -> None:
+            raise ValueError(node(n))
+
+        with self.assertRaises(ValueError):
+            self.cdict.get_exp(5).walk(fail)
+
+
+if __name__ == "__main__":
+    unittest.main()